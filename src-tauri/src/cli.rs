@@ -0,0 +1,257 @@
+//! Headless command line for scripted host changes, e.g.
+//!
+//! ```text
+//! palhost swap --account <id> --world <id> --first <player> --second <player>
+//! palhost set-host --account <id> --world <id> --player <player>
+//! palhost export --account <id> --world <id> | ssh remote 'cat > world.zip'
+//! ```
+//!
+//! Runs the same swap and export code as the GUI without starting Tauri,
+//! with the GUI's settings and world locks. The
+//! result is printed to stdout as JSON (to stderr when `export` writes the
+//! ZIP to stdout); progress and log lines go to stderr.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::{
+  apply_app_config, backup_root_for, config_path_in, lock_world_in, long_path, normalize_id, players_dir,
+  players_in_world, read_app_config, set_host_in_dir, swap_players_full, wait_for_idle_in, world_dir, write_world_zip,
+  MatchStrategy, SwapOptions, WorldLocks,
+};
+
+const USAGE: &str = "usage:
+  palhost [--headless] swap --account <id> --world <id> --first <player> --second <player> [options]
+  palhost [--headless] set-host --account <id> --world <id> --player <player> [options]
+  palhost [--headless] export --account <id> --world <id> [--out <file>] [--include-game-backups]
+options:
+  --reconstruct              rebuild a missing player .sav from Level.sav
+  --fix-base-camps           set-host only: hand the old host's base camps to the new host
+  --match-strategy <s>       instanceId (default), playerUId or auto
+  --no-backup                skip the pre-swap backup
+  --backup-root <dir>        back up here instead of the configured backup folder
+  --out <file>               write the export ZIP here; stdout when missing or -
+  --include-game-backups     keep the game's backup/ folder in the export";
+
+/// Exit codes: success, a failed operation, bad arguments.
+const EXIT_OK: i32 = 0;
+const EXIT_FAILED: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+/// Flags that take no value.
+const SWITCHES: &[&str] = &["reconstruct", "no-backup", "fix-base-camps", "include-game-backups"];
+
+#[derive(Debug, PartialEq)]
+struct CliArgs {
+  command: String,
+  options: HashMap<String, String>,
+}
+
+impl CliArgs {
+  fn required(&self, name: &str) -> Result<&str, String> {
+    self
+      .options
+      .get(name)
+      .map(String::as_str)
+      .ok_or_else(|| format!("missing --{name}"))
+  }
+
+  fn switch(&self, name: &str) -> bool {
+    self.options.contains_key(name)
+  }
+
+  /// `export` without `--out <file>` streams the ZIP to stdout.
+  fn exports_to_stdout(&self) -> bool {
+    self.command == "export" && self.options.get("out").filter(|o| *o != "-").is_none()
+  }
+}
+
+/// Parse `args` (without the program name). `None` when they don't ask for
+/// a headless command, so the GUI should start.
+fn parse_args(args: &[String]) -> Option<Result<CliArgs, String>> {
+  let mut rest = args.iter().peekable();
+  let headless = rest.next_if(|a| *a == "--headless").is_some();
+  let command = match rest.next() {
+    Some(c) if c == "swap" || c == "set-host" || c == "export" => c.clone(),
+    Some(other) if headless => return Some(Err(format!("unknown command '{other}'"))),
+    None if headless => return Some(Err("missing command".into())),
+    _ => return None,
+  };
+  let mut options = HashMap::new();
+  while let Some(arg) = rest.next() {
+    let Some(name) = arg.strip_prefix("--") else {
+      return Some(Err(format!("unexpected argument '{arg}'")));
+    };
+    let value = if SWITCHES.contains(&name) {
+      String::new()
+    } else {
+      match rest.next() {
+        Some(v) => v.clone(),
+        None => return Some(Err(format!("--{name} needs a value"))),
+      }
+    };
+    options.insert(name.to_string(), value);
+  }
+  if command == "swap" && options.contains_key("fix-base-camps") {
+    return Some(Err("--fix-base-camps only applies to set-host".into()));
+  }
+  Some(Ok(CliArgs { command, options }))
+}
+
+/// The GUI's app data dir (Tauri's `app_data_dir`), which holds its
+/// settings and world locks.
+fn app_data_dir() -> Result<PathBuf, String> {
+  let conf: Value = serde_json::from_str(include_str!("../tauri.conf.json")).map_err(|e| e.to_string())?;
+  let identifier = conf["identifier"].as_str().ok_or("tauri.conf.json has no identifier")?;
+  let data = dirs::data_dir().ok_or("Cannot find the app data folder.")?;
+  Ok(data.join(identifier))
+}
+
+fn execute(args: &CliArgs) -> Result<Value, String> {
+  // Same settings as the GUI: Epic root, custom host slots, backup root
+  let data_dir = app_data_dir()?;
+  let config_path = config_path_in(&data_dir)?;
+  let config = read_app_config(&config_path).map_err(|e| format!("Cannot load settings from {}: {e}", config_path.display()))?;
+  apply_app_config(&config);
+
+  let account = args.required("account")?;
+  let world = args.required("world")?;
+  let wpath = world_dir(account, world)?;
+  let pdir = players_dir(account, world)?;
+  if !wpath.is_dir() {
+    return Err(format!("World folder not found: {}", wpath.display()));
+  }
+  // Even an export must not zip a world a swap is halfway through writing
+  let _guard = lock_world_in(&WorldLocks::default(), &data_dir, account, world)?;
+  // Like the GUI, don't read or write a world the game is still saving
+  wait_for_idle_in(&config, &wpath)?;
+  if args.command == "export" {
+    return export(args, &long_path(&wpath), world, config.backup_root_override.is_some());
+  }
+  let match_strategy = match args.options.get("match-strategy") {
+    Some(s) => serde_json::from_value::<MatchStrategy>(json!(s)).map_err(|_| format!("unknown match strategy '{s}'"))?,
+    None => MatchStrategy::default(),
+  };
+  let backup_root = match args.options.get("backup-root") {
+    Some(dir) => PathBuf::from(dir),
+    None => backup_root_for(config.backup_root_override.as_deref(), account, world)?,
+  };
+  let opts = SwapOptions {
+    reconstruct_missing: args.switch("reconstruct"),
+    auto_backup: !args.switch("no-backup"),
+    print_progress: true,
+    fix_base_camps: args.switch("fix-base-camps"),
+    backup_root: Some(&backup_root),
+    match_strategy,
+    ..Default::default()
+  };
+
+  let changed = match args.command.as_str() {
+    "swap" => {
+      let first = normalize_id(args.required("first")?);
+      let second = normalize_id(args.required("second")?);
+      swap_players_full(&wpath, &pdir, &first, &second, &opts)?;
+      true
+    }
+    _ => set_host_in_dir(&wpath, &pdir, args.required("player")?, &opts)?,
+  };
+  let players = players_in_world(&wpath, &pdir)?;
+  Ok(json!({ "ok": true, "command": args.command, "changed": changed, "players": players }))
+}
+
+/// Zip the world to `--out` or, without it, stream it to stdout.
+fn export(args: &CliArgs, wpath: &Path, world: &str, backup_root_overridden: bool) -> Result<Value, String> {
+  let include_game_backups = args.switch("include-game-backups");
+  let mut progress = |done: usize, total: usize| eprintln!("[{:>3}%] {done}/{total} files", done * 100 / total);
+  if args.exports_to_stdout() {
+    let zip = zip::ZipWriter::new_stream(std::io::stdout().lock());
+    let mut out = write_world_zip(zip, wpath, world, include_game_backups, backup_root_overridden, None, &mut progress)?.into_inner();
+    out.flush().map_err(|e| format!("Cannot write to stdout: {e}"))?;
+    return Ok(json!({ "ok": true, "command": args.command, "out": "-" }));
+  }
+  let out = args.required("out")?;
+  let file = std::fs::File::create(out).map_err(|e| format!("Cannot create {out}: {e}"))?;
+  write_world_zip(zip::ZipWriter::new(file), wpath, world, include_game_backups, backup_root_overridden, None, &mut progress)?;
+  Ok(json!({ "ok": true, "command": args.command, "out": out }))
+}
+
+/// Release builds are GUI-subsystem executables with no console of their
+/// own; borrow the one of the shell that started us so output shows up.
+/// Redirected stdout/stderr keep working either way.
+#[cfg(windows)]
+fn attach_parent_console() {
+  const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+  #[link(name = "kernel32")]
+  extern "system" {
+    fn AttachConsole(process_id: u32) -> i32;
+  }
+  // SAFETY: plain Win32 call; failing (no parent console) is harmless.
+  unsafe {
+    AttachConsole(ATTACH_PARENT_PROCESS);
+  }
+}
+
+#[cfg(not(windows))]
+fn attach_parent_console() {}
+
+/// Run a headless command if `args` (without the program name) ask for one
+/// and return the process exit code; `None` means start the GUI.
+pub fn run_cli(args: Vec<String>) -> Option<i32> {
+  let parsed = parse_args(&args)?;
+  attach_parent_console();
+  let args = match parsed {
+    Ok(args) => args,
+    Err(e) => {
+      eprintln!("{e}\n{USAGE}");
+      return Some(EXIT_USAGE);
+    }
+  };
+  let (output, code) = match execute(&args) {
+    Ok(output) => (output, EXIT_OK),
+    Err(e) => (json!({ "ok": false, "command": args.command, "error": e }), EXIT_FAILED),
+  };
+  let output = serde_json::to_string_pretty(&output).unwrap_or_default();
+  if args.exports_to_stdout() {
+    eprintln!("{output}");
+  } else {
+    println!("{output}");
+  }
+  Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(String::from).collect()
+  }
+
+  #[test]
+  fn test_parse_args() {
+    assert_eq!(parse_args(&[]), None);
+    assert_eq!(parse_args(&args("--some-tauri-flag")), None);
+
+    let parsed = parse_args(&args("swap --account a --world w --first x --second y --no-backup")).unwrap().unwrap();
+    assert_eq!(parsed.command, "swap");
+    assert_eq!(parsed.required("second"), Ok("y"));
+    assert!(parsed.switch("no-backup") && !parsed.switch("reconstruct"));
+
+    let parsed = parse_args(&args("--headless set-host --account a --world w --player p")).unwrap().unwrap();
+    assert_eq!(parsed.required("player"), Ok("p"));
+    assert_eq!(parsed.required("first"), Err("missing --first".into()));
+
+    let parsed = parse_args(&args("export --account a --world w --include-game-backups")).unwrap().unwrap();
+    assert!(parsed.switch("include-game-backups") && parsed.exports_to_stdout());
+    let parsed = parse_args(&args("export --account a --world w --out w.zip")).unwrap().unwrap();
+    assert!(!parsed.exports_to_stdout());
+
+    assert!(parse_args(&args("--headless frobnicate")).unwrap().is_err());
+    assert!(parse_args(&args("swap --account")).unwrap().is_err());
+    assert!(parse_args(&args("swap stray")).unwrap().is_err());
+    assert!(parse_args(&args("swap --first x --second y --fix-base-camps")).unwrap().is_err());
+    assert!(parse_args(&args("set-host --player p --fix-base-camps")).unwrap().unwrap().switch("fix-base-camps"));
+  }
+}
@@ -6,7 +6,8 @@
 //!
 //! The outer `.sav` container supports three compression schemes:
 //!   - 0x32 / "PlZ" – double-zlib
-//!   - 0x31 / "PlM" – Oodle (Mermaid) via the game's `oo2core` DLL
+//!   - 0x31 / "PlM" – Oodle (Mermaid); decoded in pure Rust, re-encoded only
+//!     via the game's `oo2core` DLL
 //!   - 0x30 / "CNK" – single-zlib with a 24-byte header (wrapper)
 //!
 //! Inside the decompressed data is the GVAS binary stream.
@@ -18,6 +19,7 @@ use flate2::Compression;
 use serde_json::{json, Map, Value};
 use std::collections::HashSet;
 use std::io::{self, Cursor, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::LazyLock;
 
 use crate::oodle;
@@ -27,6 +29,10 @@ static EMPTY_VEC: LazyLock<Vec<Value>> = LazyLock::new(Vec::new);
 
 // ── SAV container ────────────────────────────────────────
 
+/// Pseudo save_type for an uncompressed GVAS stream with no container.
+/// The game can't load these; they exist to diff our output byte-for-byte.
+pub const SAVE_TYPE_RAW_GVAS: u8 = 0xFF;
+
 /// Decompress a `.sav` file into raw GVAS bytes.
 /// Returns `(gvas_bytes, save_type)`.
 ///
@@ -34,7 +40,22 @@ static EMPTY_VEC: LazyLock<Vec<Value>> = LazyLock::new(Vec::new);
 ///   - `0x32` / magic "PlZ" – double-zlib
 ///   - `0x31` / magic "PlM" – Oodle (requires `oo2core` DLL from Palworld)
 ///   - `0x30` / magic "CNK" – wrapper; re-reads inner header then decompresses
+///
+/// A bare GVAS stream is refused; see `decompress_sav_with`.
 pub fn decompress_sav(data: &[u8]) -> Result<(Vec<u8>, u8), String> {
+    decompress_sav_with(data, false)
+}
+
+/// `decompress_sav`, also accepting a bare GVAS stream (no container) as
+/// `SAVE_TYPE_RAW_GVAS` when `allow_raw_gvas` is set. Only for the
+/// debugging conversions; the game never writes these.
+pub fn decompress_sav_with(data: &[u8], allow_raw_gvas: bool) -> Result<(Vec<u8>, u8), String> {
+    if data.starts_with(b"GVAS") {
+        if !allow_raw_gvas {
+            return Err("Bare GVAS stream, not a .sav container".into());
+        }
+        return Ok((data.to_vec(), SAVE_TYPE_RAW_GVAS));
+    }
     if data.len() < 12 {
         return Err("SAV file too small".into());
     }
@@ -98,15 +119,29 @@ pub fn decompress_sav(data: &[u8]) -> Result<(Vec<u8>, u8), String> {
 
 /// Compress raw GVAS bytes back into `.sav` format.
 ///
-/// **PLM (0x31) is automatically converted to PLZ (0x32)**, because
-/// Oodle compression requires the proprietary SDK.  Palworld reads PLZ
-/// files regardless of the original format.
+/// PLM (0x31) is recompressed with Oodle when the game's `oo2core` DLL has
+/// been configured (see `oodle::set_dll_path`); otherwise it is **converted
+/// to PLZ (0x32)**.  Palworld reads PLZ files regardless of the original format.
+/// A configured DLL that can't be loaded or doesn't round-trip is an error.
 pub fn compress_sav(gvas: &[u8], save_type: u8) -> Result<Vec<u8>, String> {
-    // PLM → PLZ: we can decompress Oodle via the game DLL, but we cannot
-    // recompress without the Oodle SDK.  PalworldSaveTools does the same.
+    // PLM → PLZ unless the DLL is configured.
     let effective = if save_type == 0x31 { 0x32 } else { save_type };
+    if save_type == 0x31 {
+        if let Some(compressed) = oodle::compress(gvas, oodle::LEVEL_NORMAL)? {
+            let mut out = Vec::with_capacity(12 + compressed.len());
+            out.write_u32::<LittleEndian>(gvas.len() as u32)
+                .map_err(|e| e.to_string())?;
+            out.write_u32::<LittleEndian>(compressed.len() as u32)
+                .map_err(|e| e.to_string())?;
+            out.extend_from_slice(b"PlM");
+            out.push(0x31);
+            out.extend_from_slice(&compressed);
+            return Ok(out);
+        }
+    }
 
     match effective {
+        SAVE_TYPE_RAW_GVAS => Ok(gvas.to_vec()),
         0x32 => {
             // Double-zlib (PlZ type 50)
             let mut enc1 = ZlibEncoder::new(Vec::new(), Compression::default());
@@ -145,10 +180,23 @@ pub fn compress_sav(gvas: &[u8], save_type: u8) -> Result<Vec<u8>, String> {
     }
 }
 
+/// Compress raw GVAS bytes as a CNK `.sav`: a 12-byte outer header with the
+/// "CNK" magic in front of a single-zlib (0x30) save, the 24-byte layout
+/// `decompress_sav` unwraps.
+pub fn compress_sav_cnk(gvas: &[u8]) -> Result<Vec<u8>, String> {
+    let inner = compress_sav(gvas, 0x30)?;
+    let mut out = Vec::with_capacity(12 + inner.len());
+    out.extend_from_slice(&inner[..8]);
+    out.extend_from_slice(b"CNK");
+    out.push(0x30);
+    out.extend_from_slice(&inner);
+    Ok(out)
+}
+
 // ── UUID helpers ─────────────────────────────────────────
 
 /// Read 16 bytes as a UUID string with Unreal's byte-swizzle convention.
-fn read_uuid(cur: &mut Cursor<&[u8]>) -> io::Result<String> {
+fn read_uuid<R: Read>(cur: &mut R) -> io::Result<String> {
     let mut raw = [0u8; 16];
     cur.read_exact(&mut raw)?;
     Ok(format!(
@@ -183,7 +231,7 @@ fn write_uuid(w: &mut Vec<u8>, s: &str) -> Result<(), String> {
 
 // ── FString helpers ──────────────────────────────────────
 
-fn read_fstring(cur: &mut Cursor<&[u8]>) -> io::Result<String> {
+fn read_fstring<R: Read>(cur: &mut R) -> io::Result<String> {
     let size = cur.read_i32::<LittleEndian>()?;
     if size == 0 {
         return Ok(String::new());
@@ -240,7 +288,7 @@ fn write_fstring(w: &mut Vec<u8>, s: &str) -> Result<(), String> {
 
 // ── Optional GUID ────────────────────────────────────────
 
-fn read_optional_uuid(cur: &mut Cursor<&[u8]>) -> io::Result<Value> {
+fn read_optional_uuid<R: Read>(cur: &mut R) -> io::Result<Value> {
     let flag = cur.read_u8()?;
     if flag != 0 {
         let uuid = read_uuid(cur)?;
@@ -283,7 +331,6 @@ fn is_skip_path(path: &str) -> bool {
         "EffectMap",
         // All other worldSaveData children we don't need
         "ItemContainerSaveData",
-        "CharacterContainerSaveData",
         "DynamicItemSaveData",
         "MapObjectSaveData",
         "WorkSaveData",
@@ -307,6 +354,15 @@ fn is_skip_path(path: &str) -> bool {
     false
 }
 
+// ── Paths decoded on a best-effort basis ──
+// These used to be skip paths.  They are decoded normally, but fall back to
+// the raw skip representation if the decode fails, so one unexpected layout
+// can't break the whole Level.sav parse.
+
+fn is_fallback_path(path: &str) -> bool {
+    path.ends_with(".worldSaveData.CharacterContainerSaveData")
+}
+
 // ── Palworld-specific type hints for MapProperty key/value struct types ──
 
 fn type_hint_for(path: &str) -> Option<&'static str> {
@@ -376,6 +432,45 @@ fn is_character_rawdata_path(path: &str) -> bool {
 
 // ── GVAS reader ─────────────────────────────────────────
 
+fn read_gvas_header<R: Read>(r: &mut R) -> Result<Value, String> {
+    let magic = r.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
+    if magic != 0x53415647 {
+        return Err(format!("Bad GVAS magic: 0x{magic:08X}"));
+    }
+    let save_game_version = r.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let pkg_ver_ue4 = r.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let pkg_ver_ue5 = r.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let ev_major = r.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
+    let ev_minor = r.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
+    let ev_patch = r.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
+    let ev_changelist = r.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let ev_branch = read_fstring(r).map_err(|e| e.to_string())?;
+    let cv_format = r.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
+    // Custom versions array
+    let cv_count = r.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let mut custom_versions = Vec::new();
+    for _ in 0..cv_count {
+        let guid = read_uuid(r).map_err(|e| e.to_string())?;
+        let ver = r.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
+        custom_versions.push(json!([guid, ver]));
+    }
+    let save_game_class_name = read_fstring(r).map_err(|e| e.to_string())?;
+    Ok(json!({
+        "magic": magic,
+        "save_game_version": save_game_version,
+        "package_file_version_ue4": pkg_ver_ue4,
+        "package_file_version_ue5": pkg_ver_ue5,
+        "engine_version_major": ev_major,
+        "engine_version_minor": ev_minor,
+        "engine_version_patch": ev_patch,
+        "engine_version_changelist": ev_changelist,
+        "engine_version_branch": ev_branch,
+        "custom_version_format": cv_format,
+        "custom_versions": custom_versions,
+        "save_game_class_name": save_game_class_name,
+    }))
+}
+
 struct GvasReader<'a> {
     cur: Cursor<&'a [u8]>,
 }
@@ -392,42 +487,7 @@ impl<'a> GvasReader<'a> {
     }
 
     fn read_header(&mut self) -> Result<Value, String> {
-        let magic = self.cur.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
-        if magic != 0x53415647 {
-            return Err(format!("Bad GVAS magic: 0x{magic:08X}"));
-        }
-        let save_game_version = self.cur.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
-        let pkg_ver_ue4 = self.cur.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
-        let pkg_ver_ue5 = self.cur.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
-        let ev_major = self.cur.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
-        let ev_minor = self.cur.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
-        let ev_patch = self.cur.read_u16::<LittleEndian>().map_err(|e| e.to_string())?;
-        let ev_changelist = self.cur.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
-        let ev_branch = read_fstring(&mut self.cur).map_err(|e| e.to_string())?;
-        let cv_format = self.cur.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
-        // Custom versions array
-        let cv_count = self.cur.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
-        let mut custom_versions = Vec::new();
-        for _ in 0..cv_count {
-            let guid = read_uuid(&mut self.cur).map_err(|e| e.to_string())?;
-            let ver = self.cur.read_i32::<LittleEndian>().map_err(|e| e.to_string())?;
-            custom_versions.push(json!([guid, ver]));
-        }
-        let save_game_class_name = read_fstring(&mut self.cur).map_err(|e| e.to_string())?;
-        Ok(json!({
-            "magic": magic,
-            "save_game_version": save_game_version,
-            "package_file_version_ue4": pkg_ver_ue4,
-            "package_file_version_ue5": pkg_ver_ue5,
-            "engine_version_major": ev_major,
-            "engine_version_minor": ev_minor,
-            "engine_version_patch": ev_patch,
-            "engine_version_changelist": ev_changelist,
-            "engine_version_branch": ev_branch,
-            "custom_version_format": cv_format,
-            "custom_versions": custom_versions,
-            "save_game_class_name": save_game_class_name,
-        }))
+        read_gvas_header(&mut self.cur)
     }
 
     fn read_properties(&mut self, path: &str) -> Result<Map<String, Value>, String> {
@@ -453,11 +513,35 @@ impl<'a> GvasReader<'a> {
             return self.read_skip_property(type_name, size, path);
         }
 
+        if is_fallback_path(path) {
+            return self.read_property_with_fallback(type_name, size, path);
+        }
+
         // Custom decode for GroupSaveDataMap (reads as MapProperty then decodes group rawdata)
         if is_group_rawdata_path(path) {
             return self.read_group_map_property(size, path);
         }
 
+        self.read_typed_property(type_name, size, path)
+    }
+
+    /// Decode a property normally, but return the raw skip representation
+    /// instead if decoding fails or doesn't consume exactly the property's bytes.
+    fn read_property_with_fallback(&mut self, type_name: &str, size: usize, path: &str) -> Result<Value, String> {
+        let start = self.cur.position();
+        let raw = self.read_skip_property(type_name, size, path)?;
+        let end = self.cur.position();
+        self.cur.set_position(start);
+        match self.read_typed_property(type_name, size, path) {
+            Ok(value) if self.cur.position() == end => Ok(value),
+            _ => {
+                self.cur.set_position(end);
+                Ok(raw)
+            }
+        }
+    }
+
+    fn read_typed_property(&mut self, type_name: &str, size: usize, path: &str) -> Result<Value, String> {
         match type_name {
             "IntProperty" => self.read_int_property(),
             "UInt16Property" => self.read_uint16_property(),
@@ -1326,11 +1410,226 @@ fn decode_character_rawdata(data: &[u8]) -> Result<Value, String> {
     }
 }
 
+// ── Character container slot RawData ─────────────────
+
+/// Decode the RawData bytes of a `CharacterContainerSaveData` slot:
+/// player_uid(16) + instance_id(16) + permission_tribe_id(1).
+/// Returns `(player_uid, instance_id)`, or `None` for a malformed blob.
+pub fn decode_container_slot_rawdata(data: &[u8]) -> Option<(String, String)> {
+    if data.len() < 32 {
+        return None;
+    }
+    let mut cur = Cursor::new(data);
+    let player_uid = read_uuid(&mut cur).ok()?;
+    let instance_id = read_uuid(&mut cur).ok()?;
+    Some((player_uid, instance_id))
+}
+
+/// Container slot RawData with its instance_id replaced; the player_uid and
+/// anything after the two ids are kept.
+pub fn set_container_slot_instance(data: &[u8], instance_id: &str) -> Result<Vec<u8>, String> {
+    if data.len() < 32 {
+        return Err("Container slot RawData is too short".into());
+    }
+    let mut out = data[..16].to_vec();
+    write_uuid(&mut out, instance_id)?;
+    out.extend_from_slice(&data[32..]);
+    Ok(out)
+}
+
+// ── Base camp guild field ────────────────────────────
+// BaseCampSaveData stays a skipped blob.  These walk just far enough into it
+// to reach each camp's RawData, which starts with
+// id(16) + name(FString) + state(1) + transform(80) + area_range(4)
+// + group_id_belong_to(16) + fast_travel_local_transform(80)
+// + owner_map_object_instance_id(16).
+// A camp belongs to a guild, not a player: only group_id_belong_to is ever
+// read or rewritten.  owner_map_object_instance_id is a map object (the
+// camp's palbox) and is only checked to be there.
+
+const TRANSFORM_SIZE: u64 = 80;
+
+/// Blob offset of every camp's `group_id_belong_to`. Fails unless the whole
+/// blob decodes.
+fn base_camp_group_offsets(raw: &[u8]) -> Result<Vec<usize>, String> {
+    let err = |e: io::Error| format!("BaseCampSaveData doesn't decode: {e}");
+    let mut cur = Cursor::new(raw);
+    let _unknown = cur.read_u32::<LittleEndian>().map_err(err)?;
+    let count = cur.read_u32::<LittleEndian>().map_err(err)?;
+    let mut offsets = Vec::new();
+    for _ in 0..count {
+        skip_bytes(&mut cur, 16).map_err(err)?; // key Guid
+        let mut found = false;
+        loop {
+            let name = read_fstring(&mut cur).map_err(err)?;
+            if name == "None" || name.is_empty() {
+                break;
+            }
+            let type_name = read_fstring(&mut cur).map_err(err)?;
+            let size = cur.read_u64::<LittleEndian>().map_err(err)?;
+            if name != "RawData" || type_name != "ArrayProperty" {
+                skip_property_value(&mut cur, &type_name, size).map_err(err)?;
+                continue;
+            }
+            if read_fstring(&mut cur).map_err(err)? != "ByteProperty" {
+                return Err("BaseCampSaveData RawData is not a byte array.".into());
+            }
+            read_optional_uuid(&mut cur).map_err(err)?;
+            let len = cur.read_u32::<LittleEndian>().map_err(err)? as u64;
+            let end = cur.position() + len;
+            skip_bytes(&mut cur, 16).map_err(err)?; // id
+            read_fstring(&mut cur).map_err(err)?; // name
+            skip_bytes(&mut cur, 1 + TRANSFORM_SIZE + 4).map_err(err)?;
+            offsets.push(cur.position() as usize);
+            // group_id_belong_to, fast travel transform, owner map object id
+            if cur.position() + 16 + TRANSFORM_SIZE + 16 > end {
+                return Err("BaseCampSaveData RawData is too short for its owner fields.".into());
+            }
+            cur.set_position(end);
+            found = true;
+        }
+        if !found {
+            return Err("BaseCampSaveData entry has no RawData.".into());
+        }
+    }
+    if cur.position() != raw.len() as u64 {
+        return Err("BaseCampSaveData has trailing bytes after its entries.".into());
+    }
+    Ok(offsets)
+}
+
+fn base_camp_blob(camps: &Value) -> Result<Vec<u8>, String> {
+    if camps.get("skip_type").and_then(|t| t.as_str()) != Some("MapProperty") {
+        return Err("BaseCampSaveData is not in the expected raw layout.".into());
+    }
+    base64_decode(camps["value"].as_str().unwrap_or(""))
+}
+
+/// The guild (`group_id_belong_to`) of every camp in BaseCampSaveData, in
+/// blob order.
+pub fn base_camp_group_ids(camps: &Value) -> Result<Vec<String>, String> {
+    let raw = base_camp_blob(camps)?;
+    base_camp_group_offsets(&raw)?
+        .into_iter()
+        .map(|at| read_uuid(&mut &raw[at..at + 16]).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Rewrite the camps' guild ids through `map` (`None` keeps one). Nothing
+/// else in the blob is touched. Returns how many camps changed.
+pub fn map_base_camp_group_ids(camps: &mut Value, mut map: impl FnMut(&str) -> Option<String>) -> Result<usize, String> {
+    let mut raw = base_camp_blob(camps)?;
+    let mut changed = 0;
+    for at in base_camp_group_offsets(&raw)? {
+        let current = read_uuid(&mut &raw[at..at + 16]).map_err(|e| e.to_string())?;
+        if let Some(next) = map(&current) {
+            let mut bytes = Vec::with_capacity(16);
+            write_uuid(&mut bytes, &next)?;
+            raw[at..at + 16].copy_from_slice(&bytes);
+            changed += 1;
+        }
+    }
+    if changed > 0 {
+        camps["value"] = json!(base64_encode(&raw));
+    }
+    Ok(changed)
+}
+
+/// A raw BaseCampSaveData property holding one camp per
+/// `(group_id_belong_to, owner_map_object_instance_id)`. Every transform and
+/// trailing byte run is filled with `decoy`'s bytes, so only the guild
+/// fields may change when a caller rewrites `decoy`.
+#[cfg(test)]
+pub(crate) fn base_camp_test_property(camps: &[(&str, &str)], decoy: &str) -> Value {
+    let mut decoy_bytes = Vec::new();
+    write_uuid(&mut decoy_bytes, decoy).unwrap();
+    let filler = |n: usize| decoy_bytes.iter().copied().cycle().take(n).collect::<Vec<u8>>();
+    let mut raw = vec![0u8; 4];
+    raw.extend_from_slice(&(camps.len() as u32).to_le_bytes());
+    for (i, (group, owner)) in camps.iter().enumerate() {
+        let mut camp = Vec::new();
+        write_uuid(&mut camp, &format!("{:08x}-0000-0000-0000-00000000cafe", i + 1)).unwrap();
+        write_fstring(&mut camp, "Camp").unwrap();
+        camp.push(1);
+        camp.extend(filler(TRANSFORM_SIZE as usize + 4));
+        write_uuid(&mut camp, group).unwrap();
+        camp.extend(filler(TRANSFORM_SIZE as usize));
+        write_uuid(&mut camp, owner).unwrap();
+        camp.extend(filler(24));
+
+        raw.extend(filler(16)); // key Guid
+        write_fstring(&mut raw, "Level").unwrap();
+        write_fstring(&mut raw, "IntProperty").unwrap();
+        raw.extend_from_slice(&4u64.to_le_bytes());
+        raw.push(0);
+        raw.extend_from_slice(&1i32.to_le_bytes());
+        write_fstring(&mut raw, "RawData").unwrap();
+        write_fstring(&mut raw, "ArrayProperty").unwrap();
+        raw.extend_from_slice(&(camp.len() as u64 + 4).to_le_bytes());
+        write_fstring(&mut raw, "ByteProperty").unwrap();
+        raw.push(0);
+        raw.extend_from_slice(&(camp.len() as u32).to_le_bytes());
+        raw.extend(camp);
+        write_fstring(&mut raw, "None").unwrap();
+    }
+    json!({"skip_type": "MapProperty", "key_type": "StructProperty", "value_type": "StructProperty",
+           "id": null, "value": base64_encode(&raw), "type": "MapProperty"})
+}
+
+// ── Shallow inspection of skipped properties ──────────
+// Skipped Array/Map/Set properties keep their body as a base64 blob.  These
+// helpers answer simple questions about them without decoding the elements,
+// falling back to walking the JSON if the property was decoded after all.
+
+/// Number of entries in an Array/Map/Set property.
+pub fn property_entry_count(prop: &Value) -> Option<usize> {
+    match prop.get("skip_type").and_then(|t| t.as_str()) {
+        Some(skip_type) => {
+            let raw = base64_decode(prop["value"].as_str()?).ok()?;
+            // Arrays start with the count; maps and sets with a 0 u32 first.
+            let at = match skip_type {
+                "ArrayProperty" => 0,
+                "MapProperty" | "SetProperty" => 4,
+                _ => return None,
+            };
+            let bytes: [u8; 4] = raw.get(at..at + 4)?.try_into().ok()?;
+            Some(u32::from_le_bytes(bytes) as usize)
+        }
+        None => {
+            let v = &prop["value"];
+            v.as_array()
+                .or_else(|| v["values"].as_array())
+                .map(|a| a.len())
+        }
+    }
+}
+
+/// How many times `uuid` appears in a property (as a 16-byte GUID in a
+/// skipped blob, or as a string in decoded JSON).
+pub fn property_guid_occurrences(prop: &Value, uuid: &str) -> Option<usize> {
+    if prop.get("skip_type").is_none() {
+        return Some(count_json_strings(prop, uuid));
+    }
+    let raw = base64_decode(prop["value"].as_str()?).ok()?;
+    let mut needle = Vec::with_capacity(16);
+    write_uuid(&mut needle, uuid).ok()?;
+    Some(raw.windows(16).filter(|w| *w == needle.as_slice()).count())
+}
+
+fn count_json_strings(v: &Value, target: &str) -> usize {
+    match v {
+        Value::String(s) => s.eq_ignore_ascii_case(target) as usize,
+        Value::Array(a) => a.iter().map(|x| count_json_strings(x, target)).sum(),
+        Value::Object(m) => m.values().map(|x| count_json_strings(x, target)).sum(),
+        _ => 0,
+    }
+}
+
 // ── Base64 helper (we use this for large raw data skip blobs) ──
 
 fn base64_encode(data: &[u8]) -> String {
     const CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut out = String::with_capacity((data.len() + 2) / 3 * 4);
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
     for chunk in data.chunks(3) {
         let b0 = chunk[0] as u32;
         let b1 = if chunk.len() > 1 { chunk[1] as u32 } else { 0 };
@@ -2266,6 +2565,314 @@ pub fn sav_to_json(data: &[u8]) -> Result<(Value, u8), String> {
     ))
 }
 
+/// Decompress a `.sav` and decode only its GVAS header, without touching the
+/// properties.  Returns `(header, first `prefix_len` GVAS bytes, save_type)`.
+pub fn sav_header(data: &[u8], prefix_len: usize) -> Result<(Value, Vec<u8>, u8), String> {
+    let (gvas, save_type) = decompress_sav(data)?;
+    let prefix = gvas[..gvas.len().min(prefix_len)].to_vec();
+    let header = GvasReader::new(&gvas).read_header()?;
+    Ok((header, prefix, save_type))
+}
+
+// ── Streaming metadata scan ─────────────────────────────
+
+/// A top-level `worldSaveData` child, as seen by `scan_world_data_keys`.
+#[derive(Debug, Clone)]
+pub struct WorldDataKey {
+    pub name: String,
+    pub type_name: String,
+    /// Serialized size of the value in bytes.
+    pub size: u64,
+    /// Element count for Array/Map/Set properties.
+    pub entry_count: Option<u32>,
+}
+
+/// Open a `.sav` (a file, a byte slice, ...) as a GVAS byte stream. zlib
+/// saves are inflated on the fly and only read as far as the caller gets;
+/// Oodle has no streaming API, so PlM saves are still read and decompressed
+/// up front.
+fn open_gvas_stream<'a, R: Read + 'a>(mut r: R) -> Result<(Box<dyn Read + 'a>, u8), String> {
+    let mut head = Vec::with_capacity(24);
+    r.by_ref().take(12).read_to_end(&mut head).map_err(|e| format!("read sav header: {e}"))?;
+    if head.starts_with(b"GVAS") {
+        return Err("Bare GVAS stream, not a .sav container".into());
+    }
+    if head.len() < 12 {
+        return Err("SAV file too small".into());
+    }
+    let save_type = if &head[8..11] == b"CNK" {
+        r.by_ref().take(12).read_to_end(&mut head).map_err(|e| format!("read sav header: {e}"))?;
+        if head.len() < 24 {
+            return Err("CNK file too small for inner header".into());
+        }
+        head[23]
+    } else {
+        head[11]
+    };
+    match save_type {
+        0x32 => Ok((Box::new(ZlibDecoder::new(ZlibDecoder::new(r))), save_type)),
+        0x30 => Ok((Box::new(ZlibDecoder::new(r)), save_type)),
+        _ => {
+            r.read_to_end(&mut head).map_err(|e| format!("read sav: {e}"))?;
+            let (gvas, save_type) = decompress_sav(&head)?;
+            Ok((Box::new(Cursor::new(gvas)), save_type))
+        }
+    }
+}
+
+fn skip_bytes<R: Read>(r: &mut R, n: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut r.take(n), &mut io::sink())?;
+    if skipped < n {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
+
+/// Read one property's type-specific header and step over its value.
+/// Returns the element count for containers.
+fn skip_property_value<R: Read>(r: &mut R, type_name: &str, size: u64) -> io::Result<Option<u32>> {
+    let count_offset = match type_name {
+        "BoolProperty" => {
+            r.read_u8()?;
+            read_optional_uuid(r)?;
+            return Ok(None);
+        }
+        "StructProperty" => {
+            read_fstring(r)?;
+            read_uuid(r)?;
+            None
+        }
+        "ArrayProperty" | "SetProperty" | "EnumProperty" | "ByteProperty" => {
+            read_fstring(r)?;
+            match type_name {
+                "ArrayProperty" => Some(0),
+                "SetProperty" => Some(4),
+                _ => None,
+            }
+        }
+        "MapProperty" => {
+            read_fstring(r)?;
+            read_fstring(r)?;
+            Some(4)
+        }
+        _ => None,
+    };
+    read_optional_uuid(r)?;
+    match count_offset {
+        Some(offset) if size >= offset + 4 => {
+            skip_bytes(r, offset)?;
+            let count = r.read_u32::<LittleEndian>()?;
+            skip_bytes(r, size - offset - 4)?;
+            Ok(Some(count))
+        }
+        _ => {
+            skip_bytes(r, size)?;
+            Ok(None)
+        }
+    }
+}
+
+/// Decode only the GVAS header, reading no further into the stream than needed.
+pub fn scan_sav_header<R: Read>(r: R) -> Result<(Value, u8), String> {
+    let (mut stream, save_type) = open_gvas_stream(r)?;
+    Ok((read_gvas_header(&mut stream)?, save_type))
+}
+
+/// List `worldSaveData`'s children (name, type, size, entry count) without
+/// materializing any of them. Blobs are streamed past, never buffered, and
+/// nothing after `worldSaveData` is read.
+pub fn scan_world_data_keys<R: Read>(r: R) -> Result<(Value, Vec<WorldDataKey>), String> {
+    let (mut r, _) = open_gvas_stream(r)?;
+    let header = read_gvas_header(&mut r)?;
+    loop {
+        let name = read_fstring(&mut r).map_err(|e| format!("read prop name: {e}"))?;
+        if name == "None" || name.is_empty() {
+            return Err("worldSaveData not found.".into());
+        }
+        let type_name = read_fstring(&mut r).map_err(|e| e.to_string())?;
+        let size = r.read_u64::<LittleEndian>().map_err(|e| e.to_string())?;
+        if name != "worldSaveData" || type_name != "StructProperty" {
+            skip_property_value(&mut r, &type_name, size).map_err(|e| format!("skip {name}: {e}"))?;
+            continue;
+        }
+        read_fstring(&mut r).map_err(|e| e.to_string())?;
+        read_uuid(&mut r).map_err(|e| e.to_string())?;
+        read_optional_uuid(&mut r).map_err(|e| e.to_string())?;
+
+        let mut keys = Vec::new();
+        loop {
+            let name = read_fstring(&mut r).map_err(|e| format!("read worldSaveData key: {e}"))?;
+            if name == "None" || name.is_empty() {
+                return Ok((header, keys));
+            }
+            let type_name = read_fstring(&mut r).map_err(|e| e.to_string())?;
+            let size = r.read_u64::<LittleEndian>().map_err(|e| e.to_string())?;
+            let entry_count = skip_property_value(&mut r, &type_name, size)
+                .map_err(|e| format!("skip worldSaveData.{name}: {e}"))?;
+            keys.push(WorldDataKey { name, type_name, size, entry_count });
+        }
+    }
+}
+
+/// `Read` adapter that keeps a copy of everything read through it, so a
+/// streamed-past property can still be decoded if it turns out to be wanted.
+struct Recorder<R> {
+    inner: R,
+    bytes: Vec<u8>,
+}
+
+impl<R: Read> Read for Recorder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// `OwnerPlayerUId` of a character's RawData bytes, found by name and
+/// decoded on its own rather than through the whole property bag.
+fn character_owner_uid(raw: &[u8]) -> Option<String> {
+    const NAME: &[u8] = b"\x0f\0\0\0OwnerPlayerUId\0";
+    let at = raw.windows(NAME.len()).position(|w| w == NAME)?;
+    let mut cur = Cursor::new(&raw[at + NAME.len()..]);
+    if read_fstring(&mut cur).ok()? != "StructProperty" {
+        return None;
+    }
+    cur.read_u64::<LittleEndian>().ok()?;
+    if read_fstring(&mut cur).ok()? != "Guid" {
+        return None;
+    }
+    read_uuid(&mut cur).ok()?;
+    read_optional_uuid(&mut cur).ok()?;
+    read_uuid(&mut cur).ok()
+}
+
+/// Walk one CharacterSaveParameterMap entry: `(PlayerUId, RawData bytes)`.
+fn scan_character_entry<R: Read>(r: &mut R) -> io::Result<(String, Vec<u8>)> {
+    let mut player_uid = String::new();
+    let mut raw = Vec::new();
+    // The key bag, then the value bag
+    for _ in 0..2 {
+        loop {
+            let name = read_fstring(r)?;
+            if name == "None" || name.is_empty() {
+                break;
+            }
+            let type_name = read_fstring(r)?;
+            let size = r.read_u64::<LittleEndian>()?;
+            match (name.as_str(), type_name.as_str()) {
+                ("PlayerUId", "StructProperty") => {
+                    let struct_type = read_fstring(r)?;
+                    read_uuid(r)?;
+                    read_optional_uuid(r)?;
+                    if struct_type == "Guid" && size == 16 {
+                        player_uid = read_uuid(r)?;
+                    } else {
+                        skip_bytes(r, size)?;
+                    }
+                }
+                ("RawData", "ArrayProperty") => {
+                    read_fstring(r)?;
+                    read_optional_uuid(r)?;
+                    raw.clear();
+                    r.by_ref().take(size).read_to_end(&mut raw)?;
+                    if (raw.len() as u64) < size {
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    }
+                    raw.drain(..raw.len().min(4)); // element count
+                }
+                _ => {
+                    skip_property_value(r, &type_name, size)?;
+                }
+            }
+        }
+    }
+    Ok((player_uid, raw))
+}
+
+/// Decode Level.sav's header and just enough of `worldSaveData` for one
+/// player: the children named in `children`, and of
+/// CharacterSaveParameterMap only `player_uid`'s own entry and those of the
+/// pals they own. Every other child and character is streamed past
+/// undecoded. The result has `sav_to_json`'s shape, minus what was skipped.
+pub fn scan_player_world_data<R: Read>(r: R, player_uid: &str, children: &[&str]) -> Result<Value, String> {
+    const CSPM: &str = "CharacterSaveParameterMap";
+    let (mut r, _) = open_gvas_stream(r)?;
+    let header = read_gvas_header(&mut r)?;
+    loop {
+        let name = read_fstring(&mut r).map_err(|e| format!("read prop name: {e}"))?;
+        if name == "None" || name.is_empty() {
+            return Err("worldSaveData not found.".into());
+        }
+        let type_name = read_fstring(&mut r).map_err(|e| e.to_string())?;
+        let size = r.read_u64::<LittleEndian>().map_err(|e| e.to_string())?;
+        if name != "worldSaveData" || type_name != "StructProperty" {
+            skip_property_value(&mut r, &type_name, size).map_err(|e| format!("skip {name}: {e}"))?;
+            continue;
+        }
+        read_fstring(&mut r).map_err(|e| e.to_string())?;
+        read_uuid(&mut r).map_err(|e| e.to_string())?;
+        read_optional_uuid(&mut r).map_err(|e| e.to_string())?;
+
+        let mut world_data = Map::new();
+        loop {
+            let name = read_fstring(&mut r).map_err(|e| format!("read worldSaveData key: {e}"))?;
+            if name == "None" || name.is_empty() {
+                return Ok(json!({
+                    "header": header,
+                    "properties": {"worldSaveData": {"value": Value::Object(world_data)}},
+                }));
+            }
+            let type_name = read_fstring(&mut r).map_err(|e| e.to_string())?;
+            let size = r.read_u64::<LittleEndian>().map_err(|e| e.to_string())?;
+            let path = format!(".worldSaveData.{name}");
+            if name == CSPM && type_name == "MapProperty" {
+                let map = scan_player_characters(&mut r, player_uid, &path).map_err(|e| format!("scan {CSPM}: {e}"))?;
+                world_data.insert(name, map);
+            } else if children.contains(&name.as_str()) {
+                let mut rec = Recorder { inner: &mut r, bytes: Vec::new() };
+                skip_property_value(&mut rec, &type_name, size).map_err(|e| format!("read {name}: {e}"))?;
+                let value = GvasReader::new(&rec.bytes).read_property(&type_name, size as usize, &path)?;
+                world_data.insert(name, value);
+            } else {
+                skip_property_value(&mut r, &type_name, size).map_err(|e| format!("skip worldSaveData.{name}: {e}"))?;
+            }
+        }
+    }
+}
+
+/// The CharacterSaveParameterMap part of `scan_player_world_data`, read
+/// from just after the property's size.
+fn scan_player_characters<R: Read>(r: &mut R, player_uid: &str, path: &str) -> Result<Value, String> {
+    let key_type = read_fstring(r).map_err(|e| e.to_string())?;
+    let value_type = read_fstring(r).map_err(|e| e.to_string())?;
+    read_optional_uuid(r).map_err(|e| e.to_string())?;
+    let _unknown = r.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let count = r.read_u32::<LittleEndian>().map_err(|e| e.to_string())?;
+    let (key_path, value_path) = (format!("{path}.Key"), format!("{path}.Value"));
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let mut rec = Recorder { inner: &mut *r, bytes: Vec::new() };
+        let (uid, raw) = scan_character_entry(&mut rec).map_err(|e| e.to_string())?;
+        let owned = || character_owner_uid(&raw).is_some_and(|o| o.eq_ignore_ascii_case(player_uid));
+        if uid.eq_ignore_ascii_case(player_uid) || owned() {
+            let mut reader = GvasReader::new(&rec.bytes);
+            let key = reader.read_map_value(&key_type, "", &key_path)?;
+            let value = reader.read_map_value(&value_type, "", &value_path)?;
+            entries.push(json!({"key": key, "value": value}));
+        }
+    }
+    Ok(json!({
+        "key_type": key_type,
+        "value_type": value_type,
+        "key_struct_type": "",
+        "value_struct_type": "",
+        "id": null,
+        "value": entries,
+        "type": "MapProperty"
+    }))
+}
+
 /// Serialize a JSON structure back to `.sav` binary format.
 pub fn json_to_sav(json: &Value, save_type: u8) -> Result<Vec<u8>, String> {
     let mut writer = GvasWriter::new();
@@ -2282,9 +2889,23 @@ pub fn json_to_sav(json: &Value, save_type: u8) -> Result<Vec<u8>, String> {
 
 // ── Deep UID swap ───────────────────────────────────────
 
+/// How many JSON nodes `deep_swap_uids` visits between progress calls by default.
+pub const DEEP_SWAP_REPORT_EVERY: usize = 50_000;
+
 /// Recursively walk the JSON tree and swap every occurrence of `old_uid` ↔ `new_uid`
 /// in ownership-related fields.
-pub fn deep_swap_uids(data: &mut Value, old_uid: &str, new_uid: &str) {
+///
+/// `progress(visited, total)` is called, and `cancel` checked, every
+/// `report_every` nodes (`DEEP_SWAP_REPORT_EVERY` when `None`); once `cancel`
+/// is set the walk stops with an error, leaving `data` partly swapped.
+pub fn deep_swap_uids(
+    data: &mut Value,
+    old_uid: &str,
+    new_uid: &str,
+    progress: Option<&dyn Fn(usize, usize)>,
+    cancel: Option<&AtomicBool>,
+    report_every: Option<usize>,
+) -> Result<usize, String> {
     let swap_keys: HashSet<&str> = [
         "OwnerPlayerUId",
         "owner_player_uid",
@@ -2294,42 +2915,93 @@ pub fn deep_swap_uids(data: &mut Value, old_uid: &str, new_uid: &str) {
     .into_iter()
     .collect();
 
-    deep_swap_recursive(data, old_uid, new_uid, &swap_keys);
+    let mut walk = DeepSwapWalk {
+        old_uid,
+        new_uid,
+        keys: &swap_keys,
+        visited: 0,
+        report_every: report_every.unwrap_or(DEEP_SWAP_REPORT_EVERY).max(1),
+        total: if progress.is_some() { count_nodes(data) } else { 0 },
+        progress,
+        cancel,
+    };
+    let swapped = walk.swap(data)?;
+    if let Some(progress) = progress {
+        progress(walk.total, walk.total);
+    }
+    Ok(swapped)
+}
+
+fn count_nodes(data: &Value) -> usize {
+    1 + match data {
+        Value::Object(map) => map.values().map(count_nodes).sum(),
+        Value::Array(arr) => arr.iter().map(count_nodes).sum(),
+        _ => 0,
+    }
 }
 
-fn deep_swap_recursive(data: &mut Value, old_uid: &str, new_uid: &str, keys: &HashSet<&str>) {
-    match data {
-        Value::Object(map) => {
-            for key in keys.iter() {
-                if let Some(v) = map.get_mut(*key) {
-                    // Could be {"value": "uuid"} (StructProperty) or just "uuid" (string)
-                    if let Some(inner) = v.as_object_mut() {
-                        if let Some(val_str) = inner.get("value").and_then(|s| s.as_str()) {
-                            if val_str == old_uid {
-                                inner.insert("value".to_string(), json!(new_uid));
-                            } else if val_str == new_uid {
-                                inner.insert("value".to_string(), json!(old_uid));
+struct DeepSwapWalk<'a> {
+    old_uid: &'a str,
+    new_uid: &'a str,
+    keys: &'a HashSet<&'a str>,
+    visited: usize,
+    report_every: usize,
+    total: usize,
+    progress: Option<&'a dyn Fn(usize, usize)>,
+    cancel: Option<&'a AtomicBool>,
+}
+
+impl DeepSwapWalk<'_> {
+    fn swap(&mut self, data: &mut Value) -> Result<usize, String> {
+        self.visited += 1;
+        if self.visited % self.report_every == 0 {
+            if self.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                return Err("Deep swap cancelled".into());
+            }
+            if let Some(progress) = self.progress {
+                progress(self.visited, self.total);
+            }
+        }
+        let (old_uid, new_uid) = (self.old_uid, self.new_uid);
+        let mut swapped = 0;
+        match data {
+            Value::Object(map) => {
+                for key in self.keys.iter() {
+                    if let Some(v) = map.get_mut(*key) {
+                        // Could be {"value": "uuid"} (StructProperty) or just "uuid" (string)
+                        if let Some(inner) = v.as_object_mut() {
+                            if let Some(val_str) = inner.get("value").and_then(|s| s.as_str()) {
+                                if val_str == old_uid {
+                                    inner.insert("value".to_string(), json!(new_uid));
+                                    swapped += 1;
+                                } else if val_str == new_uid {
+                                    inner.insert("value".to_string(), json!(old_uid));
+                                    swapped += 1;
+                                }
+                            }
+                        } else if let Some(s) = v.as_str() {
+                            if s == old_uid {
+                                *v = json!(new_uid);
+                                swapped += 1;
+                            } else if s == new_uid {
+                                *v = json!(old_uid);
+                                swapped += 1;
                             }
-                        }
-                    } else if let Some(s) = v.as_str() {
-                        if s == old_uid {
-                            *v = json!(new_uid);
-                        } else if s == new_uid {
-                            *v = json!(old_uid);
                         }
                     }
                 }
+                for (_, v) in map.iter_mut() {
+                    swapped += self.swap(v)?;
+                }
             }
-            for (_, v) in map.iter_mut() {
-                deep_swap_recursive(v, old_uid, new_uid, keys);
-            }
-        }
-        Value::Array(arr) => {
-            for v in arr.iter_mut() {
-                deep_swap_recursive(v, old_uid, new_uid, keys);
+            Value::Array(arr) => {
+                for v in arr.iter_mut() {
+                    swapped += self.swap(v)?;
+                }
             }
+            _ => {}
         }
-        _ => {}
+        Ok(swapped)
     }
 }
 
@@ -2422,6 +3094,86 @@ mod tests {
         eprintln!("Round-trip OK!");
     }
 
+    const ZERO: &str = "00000000-0000-0000-0000-000000000000";
+
+    fn container_world(slot_raw: Value) -> Map<String, Value> {
+        let slot = json!({
+            "SlotIndex": {"id": null, "value": 0, "type": "IntProperty"},
+            "RawData": {"array_type": "ByteProperty", "id": null, "value": slot_raw, "type": "ArrayProperty"},
+        });
+        let containers = json!({
+            "key_type": "StructProperty",
+            "value_type": "StructProperty",
+            "key_struct_type": "",
+            "value_struct_type": "",
+            "id": null,
+            "value": [{
+                "key": {"ID": {"struct_type": "Guid", "struct_id": ZERO, "id": null,
+                    "value": "c0000000-0000-0000-0000-000000000001", "type": "StructProperty"}},
+                "value": {"Slots": {"array_type": "StructProperty", "id": null, "value": {
+                    "prop_name": "Slots", "prop_type": "StructProperty",
+                    "type_name": "PalCharacterSlotSaveData", "id": ZERO, "values": [slot],
+                }, "type": "ArrayProperty"}},
+            }],
+            "type": "MapProperty",
+        });
+        let mut props = Map::new();
+        props.insert("worldSaveData".into(), json!({
+            "struct_type": "PalWorldSaveData", "struct_id": ZERO, "id": null,
+            "value": {"CharacterContainerSaveData": containers}, "type": "StructProperty",
+        }));
+        props
+    }
+
+    fn write_props(props: &Map<String, Value>) -> Vec<u8> {
+        let mut writer = GvasWriter::new();
+        writer.write_properties(props).expect("write_properties");
+        writer.buf
+    }
+
+    #[test]
+    fn test_character_container_roundtrip() {
+        let mut raw = vec![0u8; 33];
+        raw[16] = 0xAB;
+        let bytes = write_props(&container_world(json!({"values": raw})));
+
+        let props = GvasReader::new(&bytes).read_properties("").expect("read_properties");
+        let ccsd = &props["worldSaveData"]["value"]["CharacterContainerSaveData"];
+        assert!(ccsd.get("skip_type").is_none(), "container map should be decoded");
+        let slot = &ccsd["value"][0]["value"]["Slots"]["value"]["values"][0];
+        let slot_raw: Vec<u8> = slot["RawData"]["value"]["values"].as_array().unwrap()
+            .iter().map(|b| b.as_u64().unwrap() as u8).collect();
+        let (player, instance) = decode_container_slot_rawdata(&slot_raw).expect("slot rawdata");
+        assert_eq!(player, ZERO);
+        assert!(instance.starts_with("000000ab"), "unexpected instance id {instance}");
+
+        assert_eq!(write_props(&props), bytes, "round-trip must be byte-identical");
+    }
+
+    #[test]
+    fn test_character_container_falls_back_to_raw() {
+        // A slot array that claims more elements than it holds can't decode.
+        let mut bytes = write_props(&container_world(json!({"values": [1, 2, 3]})));
+        let pos = bytes.windows(6).position(|w| w == b"Slots\0").unwrap();
+        let count_at = pos + 6 + 18 + 8 + 19 + 1; // name, type, size, array type, id flag
+        bytes[count_at..count_at + 4].copy_from_slice(&99u32.to_le_bytes());
+
+        let props = GvasReader::new(&bytes).read_properties("").expect("read_properties");
+        let ccsd = &props["worldSaveData"]["value"]["CharacterContainerSaveData"];
+        assert!(ccsd.get("skip_type").is_some(), "expected raw fallback, got {ccsd}");
+        assert_eq!(write_props(&props), bytes);
+    }
+
+    #[test]
+    fn test_raw_gvas_passthrough() {
+        let gvas = b"GVAS\x00\x00\x00\x00raw stream".to_vec();
+        assert!(decompress_sav(&gvas).is_err(), "bare GVAS needs the opt-in");
+        let (decoded, st) = decompress_sav_with(&gvas, true).expect("bare GVAS");
+        assert_eq!(st, SAVE_TYPE_RAW_GVAS);
+        assert_eq!(decoded, gvas);
+        assert_eq!(compress_sav(&decoded, st).unwrap(), gvas);
+    }
+
     #[test]
     fn test_plz_roundtrip() {
         // Test that compress→decompress roundtrips for PLZ
@@ -2431,4 +3183,206 @@ mod tests {
         assert_eq!(st, 0x32);
         assert_eq!(&decompressed, original);
     }
+
+    #[test]
+    fn test_deep_swap_cancels_mid_walk() {
+        let (a, b) = ("00000001-0000-0000-0000-000000000000", "baab90a2-0000-0000-0000-000000000000");
+        let mut data = json!([{"OwnerPlayerUId": {"value": a}}, {"OwnerPlayerUId": {"value": b}}]);
+        let cancel = AtomicBool::new(true);
+        // Too few nodes to reach the default interval, so the walk finishes
+        assert_eq!(deep_swap_uids(&mut data.clone(), a, b, None, Some(&cancel), None), Ok(2));
+        assert!(deep_swap_uids(&mut data, a, b, None, Some(&cancel), Some(1)).is_err());
+        assert_eq!(data[0]["OwnerPlayerUId"]["value"], a, "nothing is swapped before the first check");
+    }
+
+    #[test]
+    fn test_deep_swap_counts_rewritten_fields() {
+        let (a, b) = ("00000001-0000-0000-0000-000000000000", "baab90a2-0000-0000-0000-000000000000");
+        let other = "cccccccc-0000-0000-0000-000000000000";
+        let mut data = json!({
+            "pal": {"OwnerPlayerUId": {"value": a}},
+            "wall": {"build_player_uid": b, "private_lock_player_uid": a},
+            "stranger": {"OwnerPlayerUId": {"value": other}},
+            "not_ownership": {"PlayerUId": {"value": a}},
+        });
+        assert_eq!(deep_swap_uids(&mut data, a, b, None, None, None), Ok(3));
+        assert_eq!(data["pal"]["OwnerPlayerUId"]["value"], b);
+        assert_eq!(data["wall"]["build_player_uid"], a);
+        assert_eq!(data["stranger"]["OwnerPlayerUId"]["value"], other);
+        assert_eq!(data["not_ownership"]["PlayerUId"]["value"], a);
+    }
+
+    #[test]
+    fn test_cnk_roundtrip() {
+        let original = b"GVAS\x00\x00\x00\x00test data for roundtrip";
+        let compressed = compress_sav_cnk(original).expect("compress_sav_cnk");
+        assert_eq!(&compressed[8..11], b"CNK");
+        assert_eq!(&compressed[20..24], b"PlZ\x30");
+        let (decompressed, st) = decompress_sav(&compressed).expect("decompress_sav CNK");
+        assert_eq!(st, 0x30);
+        assert_eq!(&decompressed, original);
+        let mut streamed = Vec::new();
+        open_gvas_stream(&compressed[..]).unwrap().0.read_to_end(&mut streamed).unwrap();
+        assert_eq!(&streamed, original);
+    }
+
+    #[test]
+    fn test_property_guid_occurrences() {
+        let base = "0a1b2c3d-4e5f-6071-8293-a4b5c6d7e8f9";
+        let other = "ffffffff-0000-0000-0000-000000000001";
+        let mut guid = Vec::new();
+        write_uuid(&mut guid, base).unwrap();
+        // Two map entries owned by `base`, with unrelated bytes around them
+        let mut raw = vec![0u8, 0, 0, 0, 2, 0, 0, 0];
+        for _ in 0..2 {
+            raw.extend_from_slice(&guid);
+            raw.extend_from_slice(&[7; 9]);
+        }
+        let skipped = json!({"skip_type": "MapProperty", "value": base64_encode(&raw)});
+        assert_eq!(property_guid_occurrences(&skipped, base), Some(2));
+        assert_eq!(property_guid_occurrences(&skipped, &base.to_ascii_uppercase()), Some(2));
+        assert_eq!(property_guid_occurrences(&skipped, other), Some(0));
+        assert_eq!(property_entry_count(&skipped), Some(2));
+
+        let decoded = json!({"value": [{"base": base}, {"base": other}, {"nested": [base]}]});
+        assert_eq!(property_guid_occurrences(&decoded, &base.to_ascii_uppercase()), Some(2));
+        assert_eq!(property_guid_occurrences(&decoded, "00000000-0000-0000-0000-000000000000"), Some(0));
+    }
+
+    #[test]
+    fn test_base_camp_group_fields_only() {
+        let (guild, other) = ("9a000000-0000-0000-0000-000000000001", "9a000000-0000-0000-0000-000000000002");
+        let palbox = "7a000000-0000-0000-0000-00000000b0c5";
+        // The guild's bytes also fill every transform; only group ids count
+        let mut camps = base_camp_test_property(&[(guild, palbox), (other, palbox)], guild);
+        assert!(property_guid_occurrences(&camps, guild).unwrap() > 1);
+        assert_eq!(base_camp_group_ids(&camps).unwrap(), [guild, other]);
+
+        let merge = |g: &str| (g == guild).then(|| other.to_string());
+        assert_eq!(map_base_camp_group_ids(&mut camps, merge).unwrap(), 1);
+        assert_eq!(base_camp_group_ids(&camps).unwrap(), [other, other]);
+        assert_eq!(camps, base_camp_test_property(&[(other, palbox), (other, palbox)], guild));
+        // The palbox id is a map object, never a guild to rewrite
+        let mut untouched = camps.clone();
+        assert_eq!(map_base_camp_group_ids(&mut untouched, |g| (g == palbox).then(|| guild.to_string())).unwrap(), 0);
+        assert_eq!(untouched, camps);
+
+        // A blob that doesn't decode to whole entries is refused
+        let mut raw = base64_decode(camps["value"].as_str().unwrap()).unwrap();
+        raw.push(0);
+        camps["value"] = json!(base64_encode(&raw));
+        assert!(base_camp_group_ids(&camps).is_err());
+        assert!(map_base_camp_group_ids(&mut camps, |_| Some(guild.to_string())).is_err());
+        assert!(base_camp_group_ids(&json!({"value": []})).is_err());
+    }
+}
+
+/// Round-trip harness: every `.sav` under `PALHOST_TEST_SAVES` must survive
+/// `sav_to_json` → `json_to_sav` → `sav_to_json` with identical JSON and an
+/// identical GVAS stream (the outer compression may differ).
+#[cfg(test)]
+mod roundtrip {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    const SAVES_ENV: &str = "PALHOST_TEST_SAVES";
+
+    /// JSON pointer of the first place `a` and `b` differ, `None` if equal.
+    pub(crate) fn first_divergence(a: &Value, b: &Value) -> Option<String> {
+        fn walk(a: &Value, b: &Value, at: &mut String) -> bool {
+            match (a, b) {
+                (Value::Object(x), Value::Object(y)) => {
+                    let keys: std::collections::BTreeSet<&String> = x.keys().chain(y.keys()).collect();
+                    keys.into_iter().any(|k| {
+                        let len = at.len();
+                        at.push('/');
+                        at.push_str(&k.replace('~', "~0").replace('/', "~1"));
+                        let differs = match (x.get(k), y.get(k)) {
+                            (Some(xv), Some(yv)) => walk(xv, yv, at),
+                            _ => true,
+                        };
+                        if !differs {
+                            at.truncate(len);
+                        }
+                        differs
+                    })
+                }
+                (Value::Array(x), Value::Array(y)) => {
+                    let shared = x.iter().zip(y).enumerate().any(|(i, (xv, yv))| {
+                        let len = at.len();
+                        at.push_str(&format!("/{i}"));
+                        let differs = walk(xv, yv, at);
+                        if !differs {
+                            at.truncate(len);
+                        }
+                        differs
+                    });
+                    if !shared && x.len() != y.len() {
+                        at.push_str(&format!("/{}", x.len().min(y.len())));
+                        return true;
+                    }
+                    shared
+                }
+                _ => a != b,
+            }
+        }
+        let mut at = String::new();
+        walk(a, b, &mut at).then_some(at)
+    }
+
+    /// Every `.sav` file under `dir`, in a stable order.
+    pub(crate) fn saves_under(dir: &Path) -> Vec<PathBuf> {
+        let mut saves: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+            .into_iter()
+            .flatten()
+            .map(|e| e.into_path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e.eq_ignore_ascii_case("sav")))
+            .collect();
+        saves.sort();
+        saves
+    }
+
+    /// Round-trip one `.sav` file's bytes; the error names what diverged.
+    pub(crate) fn check_round_trip(data: &[u8]) -> Result<(), String> {
+        let (json, save_type) = sav_to_json(data)?;
+        let rewritten = json_to_sav(&json, save_type)?;
+        let (json2, _) = sav_to_json(&rewritten).map_err(|e| format!("re-parse failed: {e}"))?;
+        if let Some(at) = first_divergence(&json, &json2) {
+            return Err(format!("JSON differs at {at}"));
+        }
+        let (gvas, _) = decompress_sav(data)?;
+        let (gvas2, _) = decompress_sav(&rewritten)?;
+        if gvas != gvas2 {
+            let at = gvas.iter().zip(&gvas2).position(|(x, y)| x != y).unwrap_or(gvas.len().min(gvas2.len()));
+            return Err(format!("GVAS stream differs at byte {at} ({} vs {} bytes)", gvas.len(), gvas2.len()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_divergence() {
+        let a = json!({"x": [1, {"a/b": true}], "y": "same"});
+        assert_eq!(first_divergence(&a, &a.clone()), None);
+        assert_eq!(first_divergence(&a, &json!({"x": [1, {"a/b": false}], "y": "same"})).as_deref(), Some("/x/1/a~1b"));
+        assert_eq!(first_divergence(&a, &json!({"x": [1], "y": "same"})).as_deref(), Some("/x/1"));
+        assert_eq!(first_divergence(&a, &json!({"x": [1, {"a/b": true}]})).as_deref(), Some("/y"));
+    }
+
+    #[test]
+    fn test_round_trip_test_saves() {
+        let Some(dir) = std::env::var_os(SAVES_ENV) else {
+            eprintln!("Skipping: {SAVES_ENV} not set");
+            return;
+        };
+        let saves = saves_under(Path::new(&dir));
+        let failures: Vec<String> = saves
+            .iter()
+            .filter_map(|path| {
+                let result = std::fs::read(path).map_err(|e| e.to_string()).and_then(|data| check_round_trip(&data));
+                result.err().map(|e| format!("{}: {e}", path.display()))
+            })
+            .collect();
+        eprintln!("Round-tripped {} of {} saves", saves.len() - failures.len(), saves.len());
+        assert!(failures.is_empty(), "round-trip failures:\n{}", failures.join("\n"));
+    }
 }
@@ -1,13 +1,17 @@
+mod cli;
 mod gvas;
 mod oodle;
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::io::{Read, Seek, Write};
 use std::path::{Path, PathBuf};
+#[cfg(windows)]
 use std::process::Command as StdCommand;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
 use walkdir::WalkDir;
 use zip::write::SimpleFileOptions;
@@ -20,6 +24,75 @@ const LEGACY_HOST_ID: &str = "00000000000000000000000000000001";
 /// Name of the per-world config file stored inside each world's Players folder.
 /// Travels with the world files when shared between users.
 const WORLD_CONFIG_FILE: &str = "host_switcher.json";
+/// Saves that live in the world root rather than Players/ (Level.sav first).
+const WORLD_ROOT_SAVS: &[&str] = &["Level.sav", "WorldOption.sav", "LocalData.sav"];
+
+/// Engine versions (major, minor) whose saves this app knows how to read.
+const SUPPORTED_ENGINE_VERSIONS: std::ops::RangeInclusive<(i64, i64)> = (5, 1)..=(5, 1);
+
+/// Checksum manifest written into exported ZIPs, at `<world>/palhost_manifest.json`.
+const EXPORT_MANIFEST_FILE: &str = "palhost_manifest.json";
+
+/// Default minimum change (in %) between two emitted progress events.
+const PROGRESS_STEP_PERCENT: u32 = 2;
+
+/// Default number of swaps kept in a world's `swap_history`.
+const DEFAULT_SWAP_HISTORY_LIMIT: usize = 50;
+
+/// Timestamp format of backup folder names (UTC).
+const BACKUP_STAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+/// Highest base camp level the game allows.
+const MAX_BASE_CAMP_LEVEL: i32 = 30;
+
+/// Default for `AppConfig::idle_window_ms`.
+const DEFAULT_IDLE_WINDOW_MS: u64 = 5_000;
+/// How often `wait_until_idle` polls the game and Level.sav.
+const IDLE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+/// How long a write waits for the game to go idle (see `wait_for_idle_if_enabled`).
+const IDLE_WAIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Highest player level the game allows.
+const MAX_PLAYER_LEVEL: u32 = 65;
+
+/// Total Exp needed to reach each player level, from level 1 (the `TotalEXP`
+/// column of the game's player exp table). Levels past the end of this
+/// table have no known threshold, so `set_player_level` refuses them.
+const PLAYER_TOTAL_EXP: &[i64] = &[
+  0, 25, 56, 93, 138, 207, 306, 440, 616, 843,
+  1131, 1492, 1941, 2495, 3175, 4007, 5021, 6253, 7747, 9555,
+  11740, 14378, 17559, 21392, 26007, 31561, 38241, 46272, 55925, 67524,
+  81458, 98195, 118294, 142429, 171406, 206194, 247955, 298134, 358269, 430525,
+  517325, 621590, 746832, 897268, 1077965, 1295016, 1555731, 1868896, 2245052, 2696856,
+];
+
+/// Exp a player of `level` has, having just reached it.
+fn player_exp_for_level(level: u32) -> Option<i64> {
+  PLAYER_TOTAL_EXP.get(level.checked_sub(1)? as usize).copied()
+}
+
+/// How many log entries `get_recent_logs` can return.
+const LOG_BUFFER_CAPACITY: usize = 500;
+
+static LOG_BUFFER: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+/// Level.sav files `prewarm_world` is parsing right now.
+static LEVEL_PREWARMS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Internal PlayerUId per player `.sav`, keyed by path and valid for one
+/// (mtime, size), so listing players doesn't re-parse unchanged files.
+static PLAYER_UID_CACHE: Mutex<std::collections::BTreeMap<PathBuf, (FileStamp, String)>> =
+  Mutex::new(std::collections::BTreeMap::new());
+
+/// A file's (mtime, size), as returned by `file_stamp`.
+type FileStamp = (std::time::SystemTime, u64);
+
+/// How many worlds' Level.sav player info `LEVEL_CACHE` keeps.
+const LEVEL_CACHE_CAPACITY: usize = 4;
+
+/// Player info per Level.sav, keyed by path and valid for one content hash.
+/// Most recently used last.
+static LEVEL_CACHE: Mutex<Vec<LevelCacheEntry>> = Mutex::new(Vec::new());
 
 // ── Data structures ──────────────────────────────────────
 
@@ -34,6 +107,159 @@ struct WorldConfig {
   original_names: HashMap<String, String>,
   /// Custom display name for this world (shown in the app UI)
   display_name: Option<String>,
+  /// Pinned in the world list. Lives here (not in `AppConfig`) so it survives
+  /// re-installs and travels with the world when shared.
+  favorite: bool,
+  /// Completed swaps, oldest first (capped at `swap_history_limit`)
+  swap_history: Vec<SwapRecord>,
+  /// Max entries kept in `swap_history` (default `DEFAULT_SWAP_HISTORY_LIMIT`)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  swap_history_limit: Option<usize>,
+  /// `roster_checksum` at save time; on load, if it no longer matches, names
+  /// of players whose file is gone are dropped
+  #[serde(skip_serializing_if = "Option::is_none")]
+  config_checksum: Option<String>,
+}
+
+/// One completed swap, recorded in the world's `host_switcher.json`.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+struct SwapRecord {
+  timestamp: String,
+  first_id: String,
+  second_id: String,
+  first_name: Option<String>,
+  second_name: Option<String>,
+  /// Ownership fields (OwnerPlayerUId, build_player_uid, …) rewritten by the
+  /// deep swap; 0 for records written before this was tracked
+  ownership_fields_swapped: usize,
+  /// How the two character entries were found in Level.sav
+  match_strategy: MatchStrategy,
+}
+
+/// How a swap finds the players' own entries in Level.sav. `InstanceId`
+/// (the default) is exact; `PlayerUId` survives lost or duplicated
+/// InstanceIds but trusts the UIDs; `Auto` uses InstanceId when it finds
+/// exactly the expected entries and falls back to PlayerUId otherwise.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum MatchStrategy {
+  #[default]
+  InstanceId,
+  PlayerUId,
+  Auto,
+}
+
+/// Outcome of `swap_uids_in_level`: both characters' names (read before the
+/// swap) and how many ownership fields the deep swap rewrote.
+struct LevelSwap {
+  first_name: Option<String>,
+  second_name: Option<String>,
+  ownership_fields_swapped: usize,
+  /// The strategy actually used (never `Auto`)
+  matched_by: MatchStrategy,
+}
+
+/// Worlds with a mutating command in flight, keyed `account/world`.
+#[derive(Default, Clone)]
+struct WorldLocks(std::sync::Arc<Mutex<std::collections::HashSet<String>>>);
+
+/// Held for the duration of a mutating command; frees the world on drop.
+struct WorldGuard {
+  locks: WorldLocks,
+  key: String,
+  /// Lock file that keeps other PalHost processes (GUI or CLI) out
+  lock_file: Option<PathBuf>,
+}
+
+impl Drop for WorldGuard {
+  fn drop(&mut self) {
+    if let Some(file) = &self.lock_file {
+      let _ = fs::remove_file(file);
+    }
+    if let Ok(mut busy) = self.locks.0.lock() {
+      busy.remove(&self.key);
+    }
+  }
+}
+
+/// Claim a world for a mutating command, or fail with "World is busy" if
+/// another one is still running against it.
+fn lock_world(app: &AppHandle, account_id: &str, world_id: &str) -> Result<WorldGuard, String> {
+  let locks = app.state::<WorldLocks>().inner().clone();
+  let data_dir = app.path().app_data_dir().map_err(|err| err.to_string())?;
+  lock_world_in(&locks, &data_dir, account_id, world_id)
+}
+
+/// `lock_world` for callers without an `AppHandle`: claims the world in
+/// `locks` and with a lock file under the app data dir, which the GUI and
+/// the CLI share.
+fn lock_world_in(locks: &WorldLocks, data_dir: &Path, account_id: &str, world_id: &str) -> Result<WorldGuard, String> {
+  let key = format!("{}/{}", account_id.trim(), world_id.trim());
+  if !locks.0.lock().map_err(|_| "World lock poisoned".to_string())?.insert(key.clone()) {
+    return Err("World is busy".into());
+  }
+  let mut guard = WorldGuard { locks: locks.clone(), key, lock_file: None };
+  guard.lock_file = Some(claim_lock_file(&world_lock_path(data_dir, &guard.key), process_alive)?);
+  Ok(guard)
+}
+
+fn world_lock_path(data_dir: &Path, key: &str) -> PathBuf {
+  use sha2::{Digest, Sha256};
+  let digest = Sha256::digest(key.to_ascii_lowercase());
+  let name: String = digest.iter().take(16).map(|b| format!("{b:02x}")).collect();
+  data_dir.join("palworld-host-switcher").join("locks").join(format!("{name}.lock"))
+}
+
+/// Create `path` holding our pid. An existing lock whose owner `is_alive`
+/// makes the world busy; one left behind by a process that has exited is
+/// taken over.
+fn claim_lock_file(path: &Path, is_alive: impl Fn(u32) -> bool) -> Result<PathBuf, String> {
+  if let Some(dir) = path.parent() {
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {e}", dir.display()))?;
+  }
+  for _ in 0..2 {
+    match fs::OpenOptions::new().write(true).create_new(true).open(path) {
+      Ok(mut file) => {
+        let _ = write!(file, "{}", std::process::id());
+        return Ok(path.to_path_buf());
+      }
+      Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+        let owner = fs::read_to_string(path).ok().and_then(|s| s.trim().parse::<u32>().ok());
+        match owner {
+          Some(pid) if pid == std::process::id() || !is_alive(pid) => {
+            let _ = fs::remove_file(path);
+          }
+          _ => return Err("World is busy".into()),
+        }
+      }
+      Err(e) => return Err(format!("Cannot create world lock {}: {e}", path.display())),
+    }
+  }
+  Err("World is busy".into())
+}
+
+#[cfg(windows)]
+fn process_alive(pid: u32) -> bool {
+  use std::os::windows::process::CommandExt;
+  const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+  StdCommand::new("tasklist")
+    .args(["/FI", &format!("PID eq {pid}"), "/NH", "/FO", "CSV"])
+    .creation_flags(CREATE_NO_WINDOW)
+    .output()
+    .map(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{pid}\"")))
+    .unwrap_or(true)
+}
+
+#[cfg(not(windows))]
+fn process_alive(pid: u32) -> bool {
+  std::process::Command::new("ps")
+    .args(["-p", &pid.to_string()])
+    .stdout(std::process::Stdio::null())
+    .status()
+    .map(|s| s.success())
+    .unwrap_or(true)
 }
 
 /// Lightweight global config (app data dir) – just remembers last session.
@@ -42,6 +268,36 @@ struct WorldConfig {
 struct AppConfig {
   account_id: Option<String>,
   world_id: Option<String>,
+  /// Back up automatically before every swap
+  auto_backup: bool,
+  /// Progress event step in % (1-10); `PROGRESS_STEP_PERCENT` when unset.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  progress_granularity: Option<u32>,
+  /// Keep backups under `<override>/<account>/<world>/` instead of `Players/backup`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  backup_root_override: Option<String>,
+  /// Game's `oo2core_*.dll` (or its install folder); PlM saves are written
+  /// back as PlM with it, as PlZ without.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  oodle_dll_path: Option<String>,
+  /// Epic's save-games root when it isn't the default one; its accounts
+  /// are listed alongside the default root's as `<id>@epic`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  epic_save_root: Option<String>,
+  /// Extra host slot ids (flat hex), checked before the built-in ones
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  custom_host_ids: Vec<String>,
+  /// Moving average of ms per MB of zlib Level.sav, per operation ("swap",
+  /// "export"); feeds `estimate_operation_time`
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  operation_ms_per_mb: HashMap<String, f64>,
+  /// Before writing saves, wait for the game to close or Level.sav to stop
+  /// changing (see `wait_for_game_idle`)
+  wait_for_idle: bool,
+  /// How long Level.sav must stay unchanged to count as idle;
+  /// `DEFAULT_IDLE_WINDOW_MS` when unset.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  idle_window_ms: Option<u64>,
   // ── Legacy fields for migration only ──
   #[serde(default, skip_serializing_if = "Option::is_none")]
   host_id: Option<String>,
@@ -53,6 +309,49 @@ struct AppConfig {
   worlds: HashMap<String, WorldConfig>,
 }
 
+/// The user-facing part of `AppConfig` (legacy migration fields omitted).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AppSettings {
+  account_id: Option<String>,
+  world_id: Option<String>,
+  auto_backup: bool,
+  progress_granularity: Option<u32>,
+  backup_root_override: Option<String>,
+  oodle_dll_path: Option<String>,
+  epic_save_root: Option<String>,
+  wait_for_idle: bool,
+  idle_window_ms: Option<u64>,
+}
+
+impl AppSettings {
+  fn from_config(c: &AppConfig) -> Self {
+    Self {
+      account_id: c.account_id.clone(),
+      world_id: c.world_id.clone(),
+      auto_backup: c.auto_backup,
+      progress_granularity: c.progress_granularity,
+      backup_root_override: c.backup_root_override.clone(),
+      oodle_dll_path: c.oodle_dll_path.clone(),
+      epic_save_root: c.epic_save_root.clone(),
+      wait_for_idle: c.wait_for_idle,
+      idle_window_ms: c.idle_window_ms,
+    }
+  }
+
+  fn apply_to(self, c: &mut AppConfig) {
+    c.account_id = self.account_id;
+    c.world_id = self.world_id;
+    c.auto_backup = self.auto_backup;
+    c.progress_granularity = self.progress_granularity;
+    c.backup_root_override = self.backup_root_override;
+    c.oodle_dll_path = self.oodle_dll_path;
+    c.epic_save_root = self.epic_save_root;
+    c.wait_for_idle = self.wait_for_idle;
+    c.idle_window_ms = self.idle_window_ms;
+  }
+}
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 struct Player {
@@ -64,6 +363,28 @@ struct Player {
   pals_count: usize,
   last_online: String,
   guild_name: String,
+  /// Shown as host only because no file is in a host slot (see `repair_missing_host`).
+  host_fallback: bool,
+  /// Character InstanceId in Level.sav ("" if not found).
+  instance_id: String,
+  group_id: Option<String>,
+  /// The file's internal PlayerUId doesn't match its filename (see `resync_player_id`).
+  id_mismatch: bool,
+  /// Raw `last_online_real_time` behind `last_online`, for sorting.
+  #[serde(skip)]
+  last_online_ticks: i64,
+}
+
+/// Server-side filtering and sorting for `get_players`.
+#[derive(Debug, Deserialize, Default, Clone)]
+#[serde(default, rename_all = "camelCase")]
+struct PlayerQuery {
+  /// "name", "level", "pals" or "lastSeen"; `None` keeps file order
+  sort_by: Option<String>,
+  descending: bool,
+  /// Only members of this guild (GroupSaveDataMap key)
+  guild_id: Option<String>,
+  exclude_host: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -71,1647 +392,9331 @@ struct Player {
 struct WorldInfo {
   id: String,
   player_count: usize,
+  /// Name the user gave the world, if any.
   display_name: Option<String>,
+  /// The world's own name from `LevelMeta.sav`, if it has one.
+  in_game_name: Option<String>,
+  /// Name of the newest PalHost backup folder, if any.
+  last_backup: Option<String>,
+  /// `action` recorded in that backup's snapshot (e.g. "manual"), if present.
+  last_backup_action: Option<String>,
+  favorite: bool,
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// Decoded GVAS header plus the raw bytes it came from, for diagnostics.
+#[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ValidatedFolder {
-  name: String,
-  path: String,
+struct HeaderInspection {
+  header: Value,
+  /// First 128 bytes of the decompressed GVAS stream, as lowercase hex.
+  header_hex: String,
+  save_type: u8,
 }
 
-#[derive(Debug, Serialize, Clone)]
+/// Player roster differences between two worlds (see `diff_worlds`).
+#[derive(Debug, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
-struct ProgressPayload {
-  percent: f64,
-  message: String,
+struct WorldDiff {
+  only_in_a: Vec<RosterEntry>,
+  only_in_b: Vec<RosterEntry>,
+  changed: Vec<RosterChange>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(default)]
-struct BackupSnapshot {
-  host_id: Option<String>,
-  players: HashMap<String, String>,
-  original_names: HashMap<String, String>,
-  display_name: Option<String>,
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RosterEntry {
+  uuid: String,
+  name: String,
+  level: u32,
+  guild_name: String,
 }
 
-impl Default for BackupSnapshot {
-  fn default() -> Self {
-    Self {
-      host_id: None,
-      players: HashMap::new(),
-      original_names: HashMap::new(),
-      display_name: None,
-    }
+impl From<LevelPlayerInfo> for RosterEntry {
+  fn from(p: LevelPlayerInfo) -> Self {
+    Self { uuid: p.uuid, name: p.name, level: p.level, guild_name: p.guild_name }
   }
 }
 
-fn normalize_id(value: &str) -> String {
-  value.trim().to_ascii_lowercase()
+/// A player present in both worlds whose level or guild differs.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RosterChange {
+  uuid: String,
+  name: String,
+  level_a: u32,
+  level_b: u32,
+  guild_a: String,
+  guild_b: String,
 }
 
-fn home_dir() -> Result<PathBuf, String> {
-  if let Ok(profile) = std::env::var("USERPROFILE") {
-    return Ok(PathBuf::from(profile));
-  }
-  if let Ok(home) = std::env::var("HOME") {
-    return Ok(PathBuf::from(home));
-  }
-  Err("Cannot find home directory.".to_string())
+/// A `worldSaveData` child from the streaming scan (see `list_world_data_keys`).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldDataKeyInfo {
+  name: String,
+  type_name: String,
+  size: u64,
+  entry_count: Option<u32>,
 }
 
-fn save_games_root() -> Result<PathBuf, String> {
-  let home = home_dir()?;
-  Ok(
-    home
-      .join("AppData")
-      .join("Local")
-      .join("Pal")
-      .join("Saved")
-      .join("SaveGames"),
-  )
+impl From<gvas::WorldDataKey> for WorldDataKeyInfo {
+  fn from(k: gvas::WorldDataKey) -> Self {
+    Self { name: k.name, type_name: k.type_name, size: k.size, entry_count: k.entry_count }
+  }
 }
 
-fn players_dir(account_id: &str, world_id: &str) -> Result<PathBuf, String> {
-  Ok(
-    save_games_root()?
-      .join(account_id)
-      .join(world_id)
-      .join("Players"),
-  )
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldVersion {
+  engine_version: String,
+  save_game_version: Option<i64>,
+  save_type: u8,
 }
 
-fn world_dir(account_id: &str, world_id: &str) -> Result<PathBuf, String> {
-  Ok(save_games_root()?.join(account_id).join(world_id))
+/// Outcome of `rehearse_swap` on a throwaway copy of the world.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SwapPreview {
+  /// The swap ran and every post-swap check passed.
+  success: bool,
+  /// Why the swap itself failed, if it did.
+  error: Option<String>,
+  /// Post-swap check failures.
+  issues: Vec<String>,
+  /// Level.sav roster before (a) vs after (b) the swap.
+  roster_diff: WorldDiff,
+  /// How the characters were matched; `PlayerUId` means the InstanceId
+  /// lookup failed and the fallback fired.
+  matched_by: Option<MatchStrategy>,
 }
 
-fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
-  let dir = app
-    .path()
-    .app_data_dir()
-    .map_err(|err| err.to_string())?
-    .join("palworld-host-switcher");
-  fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
-  Ok(dir.join("config.json"))
+/// Everything the world view needs, from one Level.sav parse.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldSummary {
+  players: Vec<Player>,
+  guilds: Vec<GuildSummary>,
+  /// e.g. "5.1.1-0+++UE5+Release-5.1" ("" if the header lacks it).
+  engine_version: String,
+  player_count: usize,
+  pal_count: usize,
+  disk_usage_bytes: u64,
+  last_backup: Option<BackupEntry>,
 }
 
-fn load_app_config(app: &AppHandle) -> Result<AppConfig, String> {
-  let path = config_path(app)?;
-  if !path.exists() {
-    return Ok(AppConfig::default());
-  }
-  let raw = fs::read_to_string(&path).map_err(|err| err.to_string())?;
-  serde_json::from_str(&raw).map_err(|err| err.to_string())
+/// World-wide totals for `get_world_population`.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+struct Population {
+  players: usize,
+  pals: usize,
+  /// Guilds and solo (IndependentGuild) players; organizations don't count.
+  guilds: usize,
+  bases: usize,
 }
 
-fn save_app_config(app: &AppHandle, config: &AppConfig) -> Result<(), String> {
-  let path = config_path(app)?;
-  let raw = serde_json::to_string_pretty(config).map_err(|err| err.to_string())?;
-  fs::write(path, raw).map_err(|err| err.to_string())
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GuildSummary {
+  id: String,
+  /// "Guild", "IndependentGuild" or "Organization".
+  group_type: String,
+  name: String,
+  member_count: usize,
+  base_count: usize,
+  base_camp_level: Option<i64>,
 }
 
-// ── Per-world config (stored in the world's Players folder) ──
-
-fn world_config_path(pdir: &Path) -> PathBuf {
-  pdir.join(WORLD_CONFIG_FILE)
+/// Structure counts for a guild's bases (see `get_base_structures`).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BaseStructures {
+  guild_id: String,
+  bases: Vec<BaseStructureCount>,
+  /// Entries in the whole world's MapObjectSaveData / WorkSaveData.
+  total_map_objects: Option<usize>,
+  total_work_items: Option<usize>,
 }
 
-fn load_world_config(pdir: &Path) -> WorldConfig {
-  let path = world_config_path(pdir);
-  if !path.exists() {
-    return WorldConfig::default();
-  }
-  match fs::read_to_string(&path) {
-    Ok(raw) => serde_json::from_str(&raw).unwrap_or_default(),
-    Err(_) => WorldConfig::default(),
-  }
+/// Per-base counts are estimates: how often the base id's bytes appear in
+/// the undecoded data, not decoded entries. `None` when it couldn't be read.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BaseStructureCount {
+  base_id: String,
+  estimated_map_objects: Option<usize>,
+  estimated_work_items: Option<usize>,
 }
 
-fn save_world_config(pdir: &Path, wc: &WorldConfig) -> Result<(), String> {
-  // Ensure directory exists (it should, but be safe)
-  if !pdir.exists() {
-    fs::create_dir_all(pdir).map_err(|err| err.to_string())?;
-  }
-  let path = world_config_path(pdir);
-  let raw = serde_json::to_string_pretty(wc).map_err(|err| err.to_string())?;
-  fs::write(path, raw).map_err(|err| err.to_string())
+/// SHA-256 of every file in an exported world, relative to the world folder.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct ExportManifest {
+  files: Vec<ManifestEntry>,
 }
 
-/// Prune stale player entries from WorldConfig that no longer have .sav files.
-#[allow(dead_code)]
-fn prune_world_config(wc: &mut WorldConfig, live_ids: &[String]) {
-  wc.players.retain(|id, _| live_ids.contains(id));
-  wc.original_names.retain(|id, _| live_ids.contains(id));
+#[derive(Debug, Serialize, Deserialize)]
+struct ManifestEntry {
+  path: String,
+  sha256: String,
 }
 
-// ── Migration: move old app-level configs into world folders ──
+/// An account folder under SaveGames, with a best guess at its platform.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountInfo {
+  id: String,
+  /// "steam", "epic", "xbox" or "unknown".
+  platform: String,
+  world_count: usize,
+}
 
-fn migrate_legacy_config(app: &AppHandle) -> Result<(), String> {
-  let mut config = load_app_config(app)?;
-  let mut migrated = false;
+/// An account with its worlds, for `get_full_tree`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountNode {
+  id: String,
+  /// Same guess as `AccountInfo::platform`.
+  platform: String,
+  worlds: Vec<WorldInfo>,
+  /// Why the worlds couldn't be listed; `worlds` is empty then.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+}
 
-  // 1. Migrate flat legacy fields (very old format)
-  if !config.players.is_empty() || !config.original_names.is_empty() || config.host_id.is_some() {
-    if let (Some(aid), Some(wid)) = (config.account_id.clone(), config.world_id.clone()) {
-      if let Ok(pdir) = players_dir(&aid, &wid) {
-        if pdir.exists() {
-          let mut wc = load_world_config(&pdir);
-          // Only migrate if the world config is empty (don't overwrite)
-          if wc.players.is_empty() {
-            wc.players = std::mem::take(&mut config.players);
-          } else {
-            config.players.clear();
-          }
-          if wc.original_names.is_empty() {
-            wc.original_names = std::mem::take(&mut config.original_names);
-          } else {
-            config.original_names.clear();
-          }
-          if wc.host_id.is_none() {
-            wc.host_id = config.host_id.take();
-          } else {
-            config.host_id = None;
-          }
-          let _ = save_world_config(&pdir, &wc);
-          migrated = true;
-        }
-      }
-    }
-  }
+/// Where the app looks for saves and whether anything is there.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RootStatus {
+  path: String,
+  exists: bool,
+  account_count: usize,
+}
 
-  // 2. Migrate per-world map entries (previous session format)
-  if !config.worlds.is_empty() {
-    for (key, wc_old) in std::mem::take(&mut config.worlds) {
-      // key format is "accountId/worldId"
-      let parts: Vec<&str> = key.splitn(2, '/').collect();
-      if parts.len() == 2 {
-        if let Ok(pdir) = players_dir(parts[0], parts[1]) {
-          if pdir.exists() {
-            let mut wc = load_world_config(&pdir);
-            // Merge: only fill in missing data
-            if wc.players.is_empty() {
-              wc.players = wc_old.players;
-            }
-            if wc.original_names.is_empty() {
-              wc.original_names = wc_old.original_names;
-            }
-            if wc.host_id.is_none() {
-              wc.host_id = wc_old.host_id;
-            }
-            let _ = save_world_config(&pdir, &wc);
-          }
-        }
-      }
-    }
-    migrated = true;
-  }
+/// One problem (or note) found by `scan_world`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanFinding {
+  severity: ScanSeverity,
+  /// "level", "player", "ids", "host" or "references"
+  category: &'static str,
+  message: String,
+  /// File or id the finding is about, if any
+  target: Option<String>,
+}
 
-  if migrated {
-    save_app_config(app, &config)?;
-  }
-  Ok(())
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+enum ScanSeverity {
+  Info,
+  Warning,
+  Error,
 }
 
-fn list_dirs(path: &Path) -> Vec<String> {
-  fs::read_dir(path)
-    .ok()
-    .into_iter()
-    .flatten()
-    .filter_map(|entry| entry.ok())
-    .filter(|entry| entry.file_type().map(|file| file.is_dir()).unwrap_or(false))
-    .filter_map(|entry| entry.file_name().into_string().ok())
-    .collect()
+/// A pal whose owner has no player file in the world.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OrphanPal {
+  instance_id: String,
+  character_id: String,
+  level: u32,
+  owner_uid: String,
 }
 
-fn is_hex_id(value: &str) -> bool {
-  value.len() == 32 && value.chars().all(|c| c.is_ascii_hexdigit())
+/// What `compact_world` may remove.
+#[derive(Debug, Deserialize, Default, Clone, Copy)]
+#[serde(default, rename_all = "camelCase")]
+struct CompactOptions {
+  /// Pals whose owner has no player file
+  remove_orphan_pals: bool,
+  /// Guilds with no members and no bases
+  remove_empty_guilds: bool,
 }
 
-/// Convert a GVAS UUID (with dashes) to a Palworld .sav filename (flat hex).
-fn uuid_to_filename(uuid: &str) -> String {
-  uuid.replace('-', "").to_ascii_lowercase()
+/// Outcome of `compact_world`.
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CompactReport {
+  orphan_pals_removed: usize,
+  guilds_removed: usize,
+  bytes_before: u64,
+  bytes_after: u64,
+  /// Negative if re-encoding made the file larger
+  bytes_saved: i64,
 }
 
-/// Convert a flat-hex filename to a GVAS UUID (with dashes).
-fn filename_to_uuid(filename: &str) -> String {
-  let s = filename.to_ascii_lowercase();
-  if s.len() != 32 {
-    return s;
-  }
-  format!(
-    "{}-{}-{}-{}-{}",
-    &s[0..8],
-    &s[8..12],
-    &s[12..16],
-    &s[16..20],
-    &s[20..32]
-  )
+/// A leftover PalHost file or folder in the temp dir (P2P ZIPs, extracts).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TempItem {
+  path: String,
+  size: u64,
+  /// RFC 3339, UTC (modification time where creation time isn't available)
+  created_at: String,
 }
 
-/// Check if a player ID (flat hex) is the host slot.
-#[allow(dead_code)]
-fn is_host_slot(id: &str) -> bool {
-  let n = normalize_id(id);
-  n == DEFAULT_HOST_ID || n == LEGACY_HOST_ID
+/// A backup folder with its creation time (RFC 3339, UTC).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupEntry {
+  name: String,
+  created_at: String,
+  /// User label from the backup's snapshot (see `rename_backup`)
+  label: Option<String>,
 }
 
-fn list_player_ids(players_dir: &Path) -> Vec<String> {
-  fs::read_dir(players_dir)
-    .ok()
-    .into_iter()
-    .flatten()
-    .filter_map(|entry| entry.ok())
-    .filter(|entry| entry.file_type().map(|file| file.is_file()).unwrap_or(false))
-    .filter_map(|entry| entry.file_name().into_string().ok())
-    .filter_map(|name| name.strip_suffix(".sav").map(|id| id.to_string()))
-    .map(|id| normalize_id(&id))
-    .filter(|id| is_hex_id(id))
-    .collect()
+/// A backup of some world in the account (see `list_all_backups`).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountBackupEntry {
+  world_id: String,
+  #[serde(flatten)]
+  backup: BackupEntry,
+  size_bytes: u64,
 }
 
-fn resolve_host_id(_wc: &WorldConfig, player_ids: &[String]) -> Option<String> {
-  // Host is always the player in the well-known slot 0001.
-  for &hid in &[DEFAULT_HOST_ID, LEGACY_HOST_ID] {
-    let normalized = normalize_id(hid);
-    if player_ids.contains(&normalized) {
-      return Some(normalized);
-    }
-  }
-  player_ids.first().cloned()
+/// One page of a world's backups (see `list_backups_paged`). `total` and
+/// `total_bytes` cover every backup, not just this page.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupPage {
+  entries: Vec<BackupEntry>,
+  total: usize,
+  total_bytes: u64,
 }
 
-// ── Level.sav player extraction ──────────────────────────
+/// Result of `verify_backup`: which files parsed and which didn't.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupVerification {
+  backup_name: String,
+  /// True when every `.sav` parsed and the snapshot (if any) deserialized.
+  ok: bool,
+  files_checked: usize,
+  snapshot_ok: bool,
+  unreadable: Vec<UnreadableFile>,
+}
 
-/// Information extracted from Level.sav about a single player.
-#[allow(dead_code)]
-struct LevelPlayerInfo {
-  uuid: String,      // GVAS UUID with dashes
-  filename: String,   // flat hex for .sav filename
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnreadableFile {
+  file: String,
+  error: String,
+}
+
+/// Identity read from a player `.sav` by `validate_player_sav`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayerSavInfo {
+  player_uid: String,
+  instance_id: Option<String>,
+  nick_name: Option<String>,
+  /// Compression magic byte (0x31 = PLM/Oodle, 0x32 = PLZ/zlib).
+  save_type: u8,
+}
+
+/// One player file's identity next to the Level.sav character it maps to.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CharMapping {
+  filename: String,
+  /// `None` when the file doesn't parse
+  file_player_uid: Option<String>,
+  file_instance_id: Option<String>,
+  /// The CSPM player entry found by InstanceId, else by PlayerUId
+  cspm_player_uid: Option<String>,
+  cspm_instance_id: Option<String>,
+  /// A CSPM entry has both the file's InstanceId and its PlayerUId
+  matched: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LogEntry {
+  timestamp: String,
+  level: String,
+  message: String,
+}
+
+/// One of a player's pal containers (party or palbox) from Level.sav.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PalContainer {
+  container_id: String,
+  /// "party" (OtomoCharacterContainerId) or "palbox" (PalStorageContainerId).
+  kind: String,
+  slots: Vec<PalContainerSlot>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PalContainerSlot {
+  slot_index: u32,
+  instance_id: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ValidatedFolder {
   name: String,
-  level: u32,
-  pals_count: usize,
-  last_online: String,
-  guild_name: String,
+  path: String,
+  completeness: WorldCompleteness,
 }
 
-/// Read Level.sav and extract player info (name, level, pals, etc.).
-fn extract_players_from_level(world_path: &Path) -> Result<Vec<LevelPlayerInfo>, String> {
-  let level_sav = world_path.join("Level.sav");
-  if !level_sav.exists() {
-    return Err("Level.sav not found.".into());
-  }
-  let data = fs::read(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
-  let (json, _save_type) = gvas::sav_to_json(&data)?;
+/// Result of `verify_shared_world_compatibility`. `compatible` is false when
+/// the world can't be hosted as is; `warnings` are worth showing either way.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompatReport {
+  compatible: bool,
+  engine_version: String,
+  engine_supported: bool,
+  host_id: Option<String>,
+  host_slot_present: bool,
+  /// Players in Level.sav without a .sav in Players/
+  missing_player_files: Vec<String>,
+  warnings: Vec<String>,
+}
 
-  let world_data = &json["properties"]["worldSaveData"]["value"];
+/// How much of a world a validated folder contains.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum WorldCompleteness {
+  /// Level.sav and Players/
+  Full,
+  /// Players/ without Level.sav
+  MissingLevel,
+  /// Level.sav without Players/ (inspection only)
+  LevelOnly,
+  /// Other root .sav files, no Level.sav or Players/
+  SavesOnly,
+}
 
-  // ── 1. Extract guild info from GroupSaveDataMap ──
-  // Maps: player_uuid → (player_name, last_online_ticks, guild_name)
-  let mut guild_info: HashMap<String, (String, i64, String)> = HashMap::new();
+/// How picky `validate_world_folder` is. `Strict` (the default) wants a
+/// Players/ folder or root .sav files; `Lenient` also accepts the path of a
+/// lone Level.sav file, for worlds shared as just that.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum ValidationMode {
+  #[default]
+  Strict,
+  Lenient,
+}
 
-  if let Some(gsm) = world_data.get("GroupSaveDataMap") {
-    if let Some(entries) = gsm.get("value").and_then(|v| v.as_array()) {
-      for entry in entries {
-        let group_type = entry
-          .pointer("/value/GroupType/value/value")
-          .and_then(|v| v.as_str())
-          .unwrap_or("");
-        if group_type != "EPalGroupType::Guild" {
-          continue;
-        }
-        let raw_data = entry.pointer("/value/RawData/value");
-        if raw_data.is_none() {
-          continue;
-        }
-        let rd = raw_data.unwrap();
-        let g_name = rd["guild_name"].as_str().unwrap_or("").to_string();
-        if let Some(players) = rd["players"].as_array() {
-          for p in players {
-            let puid = p["player_uid"].as_str().unwrap_or("").to_string();
-            let last_online = p["player_info"]["last_online_real_time"]
-              .as_i64()
-              .unwrap_or(0);
-            let pname = p["player_info"]["player_name"]
-              .as_str()
-              .unwrap_or("")
-              .to_string();
-            if !puid.is_empty() {
-              guild_info.insert(puid, (pname, last_online, g_name.clone()));
-            }
-          }
-        }
-      }
-    }
-  }
+/// One step of an import, as reported by `import_world` with `dry_run`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportAction {
+  kind: ImportActionKind,
+  path: String,
+}
 
-  // ── 2. Extract character info from CharacterSaveParameterMap ──
-  // Maps: player_uuid → level, counts pals per owner
-  let mut player_levels: HashMap<String, u32> = HashMap::new();
-  let mut player_names_cspm: HashMap<String, String> = HashMap::new();
-  let mut pals_count: HashMap<String, usize> = HashMap::new();
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum ImportActionKind {
+  Create,
+  Overwrite,
+  /// Removed by a "replace" import and not brought back by the copy
+  DeleteExisting,
+  /// Old game backup folder left out of the copy
+  SkipBackup,
+}
 
-  if let Some(cspm) = world_data.get("CharacterSaveParameterMap") {
-    if let Some(entries) = cspm.get("value").and_then(|v| v.as_array()) {
-      for entry in entries {
-        // Key has PlayerUId and InstanceId
-        let player_uid = entry
-          .pointer("/key/PlayerUId/value")
-          .and_then(|v| v.as_str())
-          .unwrap_or("")
-          .to_string();
+/// `import_world` returns the refreshed world list, or the plan on a dry run.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ImportResult {
+  Worlds(Vec<WorldInfo>),
+  Plan(Vec<ImportAction>),
+}
 
-        // Decoded RawData for the character
-        let raw_data = entry.pointer("/value/RawData");
-        if raw_data.is_none() {
-          continue;
-        }
-        let rd = raw_data.unwrap();
-        let save_param = &rd["value"]["object"]["SaveParameter"]["value"];
-
-        let is_player = save_param
-          .get("IsPlayer")
-          .and_then(|v| v.get("value"))
-          .and_then(|v| v.as_bool())
-          .unwrap_or(false);
-
-        if is_player {
-          // Level is a ByteProperty: {"value": {"type":"None","value":55}}
-          let level = save_param
-            .get("Level")
-            .and_then(|v| v.get("value"))
-            .and_then(|v| v.get("value"))
-            .and_then(|v| v.as_u64())
-            .unwrap_or(1) as u32;
-          let nick = save_param
-            .get("NickName")
-            .and_then(|v| v.get("value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-          player_levels.insert(player_uid.clone(), level);
-          if !nick.is_empty() {
-            player_names_cspm.insert(player_uid, nick);
-          }
-        } else {
-          // This is a pal – count under owner
-          let owner = save_param
-            .get("OwnerPlayerUId")
-            .and_then(|v| v.get("value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("");
-          if !owner.is_empty() && owner != "00000000-0000-0000-0000-000000000000" {
-            *pals_count.entry(owner.to_string()).or_insert(0) += 1;
-          }
-        }
-      }
-    }
-  }
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProgressPayload {
+  percent: f64,
+  message: String,
+}
 
-  // ── 3. Get current game time for "last seen" calculation ──
-  let current_ticks = world_data
-    .pointer("/GameTimeSaveData/value/RealDateTimeTicks/value")
-    .and_then(|v| v.as_u64())
-    .unwrap_or(0);
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(default)]
+struct BackupSnapshot {
+  host_id: Option<String>,
+  players: HashMap<String, String>,
+  original_names: HashMap<String, String>,
+  display_name: Option<String>,
+  /// What triggered the backup ("manual", …). Absent in older backups.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  action: Option<String>,
+  /// User-given label; the folder keeps its timestamp name for sorting.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  label: Option<String>,
+}
 
-  // ── 4. Build player list ──
-  // Combine guild_info + cspm data
-  let mut all_uuids: Vec<String> = Vec::new();
-  for uuid in guild_info.keys() {
-    if !all_uuids.contains(uuid) {
-      all_uuids.push(uuid.clone());
+// ── Logging ──────────────────────────────────────────────
+
+/// Record a message in the in-memory ring buffer (for `get_recent_logs`) and
+/// forward it to the `log` facade, or to stderr when no logger is installed.
+fn log_event(level: log::Level, msg: &str) {
+  if let Ok(mut buf) = LOG_BUFFER.lock() {
+    if buf.len() >= LOG_BUFFER_CAPACITY {
+      buf.pop_front();
     }
+    buf.push_back(LogEntry {
+      timestamp: chrono::Local::now().to_rfc3339(),
+      level: level.to_string(),
+      message: msg.to_string(),
+    });
   }
-  for uuid in player_levels.keys() {
-    if !all_uuids.contains(uuid) {
-      all_uuids.push(uuid.clone());
-    }
+  if log::log_enabled!(level) {
+    log::log!(level, "[palhost] {msg}");
+  } else {
+    eprintln!("[palhost] {msg}");
   }
+}
 
-  let mut result = Vec::new();
-  for uuid in &all_uuids {
-    let filename = uuid_to_filename(uuid);
-    let (guild_name_str, last_online_str, player_name) = if let Some((name, ticks, gname)) = guild_info.get(uuid) {
-      let last_seen = format_last_seen(*ticks, current_ticks);
-      (gname.clone(), last_seen, name.clone())
-    } else {
-      ("".to_string(), "Unknown".to_string(), "".to_string())
-    };
+// ── Progress throttling ──────────────────────────────────
 
-    let name = if !player_name.is_empty() {
-      player_name
-    } else if let Some(nick) = player_names_cspm.get(uuid) {
-      nick.clone()
-    } else {
-      filename.clone()
-    };
+/// Emits a progress percentage only when it has moved by at least `step`
+/// since the last emitted value (or the work is done).
+struct ProgressThrottle {
+  step: u32,
+  last_pct: u32,
+}
 
-    let level = player_levels.get(uuid).copied().unwrap_or(0);
-    let pals = pals_count.get(uuid).copied().unwrap_or(0);
+impl ProgressThrottle {
+  fn new(step: u32) -> Self {
+    Self { step: step.clamp(1, 10), last_pct: 0 }
+  }
 
-    result.push(LevelPlayerInfo {
-      uuid: uuid.clone(),
-      filename,
-      name,
-      level,
-      pals_count: pals,
-      last_online: last_online_str,
-      guild_name: guild_name_str,
-    });
+  /// Throttle using the user's `progress_granularity`, if set.
+  fn from_config(app: &AppHandle) -> Self {
+    let step = load_app_config(app)
+      .ok()
+      .and_then(|c| c.progress_granularity)
+      .unwrap_or(PROGRESS_STEP_PERCENT);
+    Self::new(step)
   }
 
-  Ok(result)
+  /// Returns the percentage to emit for `done` of `total`, if any.
+  fn tick(&mut self, done: usize, total: usize) -> Option<u32> {
+    let pct = (done * 100 / total.max(1)).min(100) as u32;
+    if pct >= self.last_pct + self.step || done == total {
+      self.last_pct = pct;
+      Some(pct)
+    } else {
+      None
+    }
+  }
 }
 
-/// Format last_online ticks relative to current game ticks into human-readable text.
-fn format_last_seen(last_online_ticks: i64, current_ticks: u64) -> String {
-  if last_online_ticks <= 0 {
-    return "Unknown".to_string();
-  }
-  let diff_ticks = current_ticks as i64 - last_online_ticks;
-  if diff_ticks < 0 {
-    return "Online now".to_string();
+/// On Windows, give an absolute path the `\\?\` prefix so file IO isn't cut
+/// off at MAX_PATH (deep OneDrive folders hit it). Elsewhere it's a no-op.
+fn long_path(path: &Path) -> PathBuf {
+  if cfg!(windows) {
+    PathBuf::from(extended_length_path(&path.to_string_lossy()))
+  } else {
+    path.to_path_buf()
   }
-  // 1 tick = 100 nanoseconds = 0.0000001 seconds
-  let seconds = diff_ticks / 10_000_000;
-  if seconds < 60 {
-    return "Online now".to_string();
+}
+
+/// `C:\x` → `\\?\C:\x`, `\\server\share` → `\\?\UNC\server\share`. Relative
+/// and already-prefixed paths are returned unchanged.
+fn extended_length_path(path: &str) -> String {
+  if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+    return path.to_string();
   }
-  let minutes = seconds / 60;
-  if minutes < 60 {
-    return format!("{minutes} min ago");
+  let win = path.replace('/', "\\");
+  if let Some(unc) = win.strip_prefix(r"\\") {
+    return format!(r"\\?\UNC\{unc}");
   }
-  let hours = minutes / 60;
-  if hours < 24 {
-    return format!("{hours}h ago");
+  let b = win.as_bytes();
+  if b.len() >= 3 && b[0].is_ascii_alphabetic() && b[1] == b':' && b[2] == b'\\' {
+    return format!(r"\\?\{win}");
   }
-  let days = hours / 24;
-  format!("{days}d ago")
+  path.to_string()
 }
 
-/// Modify a single player .sav file, swapping internal PlayerUId references.
-/// Read the InstanceId from a player .sav file (needed for InstanceId-based matching).
-fn read_player_instance_id(sav_path: &Path) -> Result<String, String> {
-  let data = fs::read(sav_path).map_err(|e| format!("read player sav: {e}"))?;
-  let (json, _) = gvas::sav_to_json(&data)?;
-  let inst = json
-    .pointer("/properties/SaveData/value/IndividualId/value/InstanceId/value")
-    .and_then(|v| v.as_str())
-    .unwrap_or("")
-    .to_string();
-  if inst.is_empty() {
-    return Err(format!("No InstanceId found in {:?}", sav_path));
-  }
-  Ok(inst)
+fn normalize_id(value: &str) -> String {
+  value.trim().to_ascii_lowercase()
 }
 
-fn modify_player_sav(sav_path: &Path, old_uid: &str, new_uid: &str) -> Result<(), String> {
-  let data = fs::read(sav_path).map_err(|e| format!("read player sav: {e}"))?;
-  let (mut json, save_type) = gvas::sav_to_json(&data)?;
-
-  // Update PlayerUId
-  if let Some(puid) = json.pointer_mut("/properties/SaveData/value/PlayerUId/value") {
-    if puid.as_str() == Some(old_uid) {
-      *puid = Value::String(new_uid.to_string());
+fn home_dir() -> Result<PathBuf, String> {
+  // USERPROFILE is the real profile on Windows (HOME there is often set by
+  // Git Bash/MSYS); elsewhere it's meaningless
+  #[cfg(windows)]
+  {
+    if let Ok(profile) = std::env::var("USERPROFILE") {
+      return Ok(PathBuf::from(profile));
     }
   }
-  // Update IndividualId → PlayerUId
-  if let Some(iid) = json.pointer_mut("/properties/SaveData/value/IndividualId/value/PlayerUId/value") {
-    if iid.as_str() == Some(old_uid) {
-      *iid = Value::String(new_uid.to_string());
-    }
+  if let Ok(home) = std::env::var("HOME") {
+    return Ok(PathBuf::from(home));
   }
+  Err("Cannot find home directory.".to_string())
+}
 
-  let sav_bytes = gvas::json_to_sav(&json, save_type)?;
-  fs::write(sav_path, &sav_bytes).map_err(|e| format!("write player sav: {e}"))?;
-  Ok(())
+fn save_games_root() -> Result<PathBuf, String> {
+  let home = home_dir()?;
+  Ok(
+    home
+      .join("AppData")
+      .join("Local")
+      .join("Pal")
+      .join("Saved")
+      .join("SaveGames"),
+  )
 }
 
-fn build_players(
-  player_ids: &[String],
-  host_id: &str,
-  level_info: &[LevelPlayerInfo],
-) -> Vec<Player> {
-  player_ids
-    .iter()
-    .map(|id| {
-      // Find matching info from Level.sav
-      let info = level_info.iter().find(|li| li.filename == *id);
-      let name = info.map(|i| i.name.clone()).unwrap_or_else(|| id.clone());
-      let level = info.map(|i| i.level).unwrap_or(0);
-      let pals_count = info.map(|i| i.pals_count).unwrap_or(0);
-      let last_online = info.map(|i| i.last_online.clone()).unwrap_or_default();
-      let guild_name = info.map(|i| i.guild_name.clone()).unwrap_or_default();
-      Player {
-        id: id.clone(),
-        name,
-        original_id: id.clone(),
-        is_host: id == host_id,
-        level,
-        pals_count,
-        last_online,
-        guild_name,
-      }
-    })
-    .collect()
+/// The store a save-games root belongs to.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Platform {
+  Steam,
+  Epic,
 }
 
-/// Swap .sav files + modify Level.sav with GVAS-based UID swap.
-/// Follows PalworldSaveTools fix_host_save logic:
-///   1. Read InstanceIds from both player .sav files
-///   2. Patch PlayerUId inside both player .sav files
-///   3. In Level.sav CharacterSaveParameterMap: swap PlayerUId only for the
-///      two entries matching by InstanceId (not all entries!)
-///   4. In Level.sav GroupSaveDataMap: swap admin, player_uid, and
-///      individual_character_handle_ids.guid matched by instance_id
-///   5. Deep-swap OwnerPlayerUId/build_player_uid/etc across all Level.sav
-///   6. Serialize Level.sav and write all files
-///   7. Rename .sav files (swap filenames)
-///
-/// Emits granular swap-progress events when `progress` is provided.
-fn swap_players_full(
-  world_path: &Path,
-  players_dir: &Path,
-  first_id: &str,
-  second_id: &str,
-  progress: Option<(&AppHandle, f64, f64)>, // (app, base%, range%)
-) -> Result<(), String> {
-  // progress helper: emit (base + fraction * range)
-  let emit = |frac: f64, msg: &str| {
-    if let Some((app, base, range)) = &progress {
-      let _ = app.emit("swap-progress", ProgressPayload {
-        percent: base + frac * range,
-        message: msg.to_string(),
-      });
+impl Platform {
+  fn tag(self) -> &'static str {
+    match self {
+      Platform::Steam => "steam",
+      Platform::Epic => "epic",
     }
-  };
+  }
+}
 
-  let first = normalize_id(first_id);
-  let second = normalize_id(second_id);
+/// The `epic_save_root` setting; `None` when Epic shares the default root.
+static EPIC_SAVE_ROOT: Mutex<Option<PathBuf>> = Mutex::new(None);
 
-  let first_sav = players_dir.join(format!("{first}.sav"));
-  let second_sav = players_dir.join(format!("{second}.sav"));
-  if !first_sav.exists() || !second_sav.exists() {
-    return Err("Missing .sav files for swap.".to_string());
+fn set_epic_save_root(path: Option<PathBuf>) {
+  if let Ok(mut slot) = EPIC_SAVE_ROOT.lock() {
+    *slot = path;
   }
+}
 
-  let uuid_first = filename_to_uuid(&first);
-  let uuid_second = filename_to_uuid(&second);
-
-  // ── 0. Read InstanceIds from player .sav files (needed for CSPM / guild matching) ──
-  emit(0.0, "Reading player saves…");
-  let inst_first = read_player_instance_id(&first_sav)?;
-  let inst_second = read_player_instance_id(&second_sav)?;
+/// Every save-games root in use, the default one first. Accounts under any
+/// other root are addressed as `<id>@<platform>` so ids can't collide.
+fn save_games_roots() -> Result<Vec<(Platform, PathBuf)>, String> {
+  let epic = EPIC_SAVE_ROOT.lock().ok().and_then(|slot| slot.clone());
+  Ok(roots_with_epic(save_games_root()?, epic))
+}
 
-  // ── 1. Modify player .sav files (patch PlayerUId + IndividualId.PlayerUId) ──
-  emit(0.05, "Patching player saves…");
-  if let Err(e) = modify_player_sav(&first_sav, &uuid_first, &uuid_second) {
-    eprintln!("[palhost] warn: could not modify {first}.sav internals: {e}");
-  }
-  if let Err(e) = modify_player_sav(&second_sav, &uuid_second, &uuid_first) {
-    eprintln!("[palhost] warn: could not modify {second}.sav internals: {e}");
+fn roots_with_epic(default: PathBuf, epic: Option<PathBuf>) -> Vec<(Platform, PathBuf)> {
+  let mut roots = vec![(Platform::Steam, default.clone())];
+  if let Some(epic) = epic.filter(|p| *p != default) {
+    roots.push((Platform::Epic, epic));
   }
+  roots
+}
 
-  // ── 2. Level.sav: read ──
-  emit(0.10, "Reading Level.sav…");
-  let level_sav = world_path.join("Level.sav");
-  if !level_sav.exists() {
-    return Err("Level.sav not found.".into());
+/// The account id as the UI sees it: bare for the default root, tagged with
+/// the platform for any other.
+fn qualified_account_id(root_index: usize, platform: Platform, id: &str) -> String {
+  if root_index == 0 {
+    id.to_string()
+  } else {
+    format!("{id}@{}", platform.tag())
   }
-  let data = fs::read(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
-
-  // ── 3. Level.sav: parse ──
-  emit(0.15, "Parsing Level.sav…");
-  let (mut json, save_type) = gvas::sav_to_json(&data)?;
-
-  // ── 4. Level.sav: modify UIDs ──
-  emit(0.40, "Swapping UIDs in Level.sav…");
-  {
-    let world_data = json
-      .get_mut("properties")
-      .and_then(|p| p.get_mut("worldSaveData"))
-      .and_then(|w| w.get_mut("value"))
-      .ok_or("Cannot navigate to worldSaveData")?;
-
-    // 4a. CharacterSaveParameterMap: swap PlayerUId ONLY for the two entries
-    //     that match by InstanceId (the player's own character entry).
-    //     All other entries (pals, other players) are left untouched.
-    if let Some(cspm) = world_data.get_mut("CharacterSaveParameterMap") {
-      if let Some(entries) = cspm.get_mut("value").and_then(|v| v.as_array_mut()) {
-        for entry in entries.iter_mut() {
-          if let Some(key) = entry.get_mut("key") {
-            let entry_inst = key
-              .pointer("/InstanceId/value")
-              .and_then(|v| v.as_str())
-              .unwrap_or("");
-            if entry_inst == inst_first {
-              if let Some(puid) = key.pointer_mut("/PlayerUId/value") {
-                *puid = Value::String(uuid_second.to_string());
-              }
-            } else if entry_inst == inst_second {
-              if let Some(puid) = key.pointer_mut("/PlayerUId/value") {
-                *puid = Value::String(uuid_first.to_string());
-              }
-            }
-          }
-        }
-      }
-    }
-
-    // 4b. GroupSaveDataMap: swap admin_player_uid, player_uid in member list,
-    //     and individual_character_handle_ids.guid matched by instance_id.
-    if let Some(gsm) = world_data.get_mut("GroupSaveDataMap") {
-      if let Some(entries) = gsm.get_mut("value").and_then(|v| v.as_array_mut()) {
-        for entry in entries.iter_mut() {
-          // Only process guilds
-          let is_guild = entry
-            .pointer("/value/GroupType/value/value")
-            .and_then(|v| v.as_str())
-            == Some("EPalGroupType::Guild");
-          if !is_guild {
-            continue;
-          }
-
-          let raw_data = entry.pointer_mut("/value/RawData/value");
-          if let Some(rd) = raw_data {
-            // Swap admin_player_uid
-            if let Some(admin) = rd.get_mut("admin_player_uid") {
-              if let Some(s) = admin.as_str().map(|s| s.to_string()) {
-                if s == uuid_first {
-                  *admin = Value::String(uuid_second.to_string());
-                } else if s == uuid_second {
-                  *admin = Value::String(uuid_first.to_string());
-                }
-              }
-            }
+}
 
-            // Swap player_uid in players list
-            if let Some(players) = rd.get_mut("players").and_then(|p| p.as_array_mut()) {
-              for p in players.iter_mut() {
-                if let Some(puid) = p.get_mut("player_uid") {
-                  if let Some(s) = puid.as_str().map(|s| s.to_string()) {
-                    if s == uuid_first {
-                      *puid = Value::String(uuid_second.to_string());
-                    } else if s == uuid_second {
-                      *puid = Value::String(uuid_first.to_string());
-                    }
-                  }
-                }
-              }
-            }
+/// Split a (possibly `@platform`-tagged) account id into its save-games root
+/// and account folder name.
+fn resolve_account(account_id: &str) -> Result<(PathBuf, String), String> {
+  Ok(resolve_account_in(save_games_roots()?, account_id))
+}
 
-            // Swap guid in individual_character_handle_ids — matched by instance_id
-            if let Some(handles) = rd.get_mut("individual_character_handle_ids").and_then(|h| h.as_array_mut()) {
-              for h in handles.iter_mut() {
-                let h_inst = h.get("instance_id")
-                  .and_then(|v| v.as_str())
-                  .unwrap_or("");
-                if h_inst == inst_first {
-                  if let Some(guid) = h.get_mut("guid") {
-                    *guid = Value::String(uuid_second.to_string());
-                  }
-                } else if h_inst == inst_second {
-                  if let Some(guid) = h.get_mut("guid") {
-                    *guid = Value::String(uuid_first.to_string());
-                  }
-                }
-              }
-            }
-          }
-        }
-      }
+/// Split `account_id` into its save-games root (from `roots`, default first)
+/// and the bare account folder name.
+fn resolve_account_in(mut roots: Vec<(Platform, PathBuf)>, account_id: &str) -> (PathBuf, String) {
+  if let Some((id, tag)) = account_id.rsplit_once('@') {
+    if let Some(i) = roots.iter().position(|(p, _)| p.tag() == tag) {
+      return (roots.swap_remove(i).1, id.to_string());
     }
-
-    // 4c. Deep-swap ownership UIDs (OwnerPlayerUId, build_player_uid, etc.)
-    //     across the entire worldSaveData. This is the same as PalworldSaveTools'
-    //     deep_swap() function applied to the full Level.sav.
-    gvas::deep_swap_uids(world_data, &uuid_first, &uuid_second);
   }
+  (roots.swap_remove(0).1, account_id.to_string())
+}
 
-  // ── 5. Level.sav: serialize ──
-  emit(0.50, "Serializing Level.sav…");
-  let sav_bytes = gvas::json_to_sav(&json, save_type)?;
+fn account_dir(account_id: &str) -> Result<PathBuf, String> {
+  let (root, id) = resolve_account(account_id)?;
+  Ok(root.join(id))
+}
 
-  // ── 6. Level.sav: write ──
-  emit(0.75, "Writing Level.sav…");
-  fs::write(&level_sav, &sav_bytes).map_err(|e| format!("Cannot write Level.sav: {e}"))?;
+fn world_dir_in(root: &Path, account: &str, world_id: &str) -> PathBuf {
+  root.join(account).join(world_id)
+}
 
-  // ── 7. Rename .sav files (swap filenames) ──
-  emit(0.96, "Renaming files…");
-  let stamp = std::time::SystemTime::now()
-    .duration_since(std::time::UNIX_EPOCH)
-    .map_err(|err| err.to_string())?
-    .as_millis();
-  let temp = players_dir.join(format!("swap-{stamp}.tmp"));
-  fs::rename(&first_sav, &temp).map_err(|err| err.to_string())?;
-  fs::rename(&second_sav, &first_sav).map_err(|err| err.to_string())?;
-  fs::rename(&temp, &second_sav).map_err(|err| err.to_string())?;
+fn players_dir_in(root: &Path, account: &str, world_id: &str) -> PathBuf {
+  world_dir_in(root, account, world_id).join("Players")
+}
 
-  emit(1.0, "Swap complete.");
-  Ok(())
+fn players_dir(account_id: &str, world_id: &str) -> Result<PathBuf, String> {
+  let (root, id) = resolve_account(account_id)?;
+  Ok(players_dir_in(&root, &id, world_id))
 }
 
-fn backup_files(players_dir: &Path, world_path: &Path, ids: &[String], snapshot: &BackupSnapshot) -> Result<PathBuf, String> {
-  let stamp = chrono::Utc::now().format("%Y-%m-%d_%H-%M-%S").to_string();
-  let backup_dir = players_dir.join("backup").join(stamp);
-  fs::create_dir_all(&backup_dir).map_err(|err| err.to_string())?;
-  for id in ids {
-    let src = players_dir.join(format!("{}.sav", normalize_id(id)));
-    if src.exists() {
-      let dest = backup_dir.join(format!("{}.sav", normalize_id(id)));
-      fs::copy(&src, &dest).map_err(|err| err.to_string())?;
-    }
-  }
-  // Backup Level.sav
-  let level_sav = world_path.join("Level.sav");
-  if level_sav.exists() {
-    let dest = backup_dir.join("Level.sav");
-    fs::copy(&level_sav, &dest).map_err(|err| err.to_string())?;
-  }
-  // Save config snapshot with names mapping
-  let snapshot_json = serde_json::to_string_pretty(snapshot).map_err(|err| err.to_string())?;
-  fs::write(backup_dir.join("config_snapshot.json"), snapshot_json).map_err(|err| err.to_string())?;
-  Ok(backup_dir)
+fn world_dir(account_id: &str, world_id: &str) -> Result<PathBuf, String> {
+  let (root, id) = resolve_account(account_id)?;
+  Ok(world_dir_in(&root, &id, world_id))
 }
 
-fn list_backups_dir(players_dir: &Path) -> Vec<String> {
-  let backup_root = players_dir.join("backup");
-  if !backup_root.exists() {
-    return Vec::new();
+/// Folder holding a world's backups: `<override>/<account>/<world>` when a
+/// backup root override is set, otherwise `Players/backup`.
+fn backup_root_for(override_root: Option<&str>, account_id: &str, world_id: &str) -> Result<PathBuf, String> {
+  match override_root {
+    Some(root) => Ok(PathBuf::from(root).join(account_id).join(world_id)),
+    None => Ok(players_dir(account_id, world_id)?.join("backup")),
   }
-  let mut items = list_dirs(&backup_root);
-  items.sort_by(|a, b| b.cmp(a));
-  items
 }
 
-#[tauri::command]
-fn get_accounts() -> Result<Vec<String>, String> {
-  Ok(list_dirs(&save_games_root()?))
+fn backup_root(app: &AppHandle, account_id: &str, world_id: &str) -> Result<PathBuf, String> {
+  let config = load_app_config(app)?;
+  backup_root_for(config.backup_root_override.as_deref(), account_id, world_id)
 }
 
-#[tauri::command]
-fn get_worlds(account_id: String) -> Result<Vec<String>, String> {
-  Ok(list_dirs(&save_games_root()?.join(account_id)))
+/// Make sure a backup root override exists and can be written to.
+fn validate_backup_root(root: &Path) -> Result<(), String> {
+  fs::create_dir_all(root).map_err(|e| format!("Cannot create backup folder {}: {e}", root.display()))?;
+  let probe = root.join(".palhost_write_test");
+  fs::write(&probe, b"").map_err(|e| format!("Backup folder {} is not writable: {e}", root.display()))?;
+  let _ = fs::remove_file(&probe);
+  Ok(())
 }
 
-#[tauri::command]
-fn get_worlds_with_counts(account_id: String) -> Result<Vec<WorldInfo>, String> {
-  let root = save_games_root()?.join(&account_id);
-  let world_ids = list_dirs(&root);
-  let result = world_ids
-    .into_iter()
-    .map(|wid| {
-      let pdir = root.join(&wid).join("Players");
-      let count = list_player_ids(&pdir).len();
-      let wc = load_world_config(&pdir);
-      WorldInfo { id: wid, player_count: count, display_name: wc.display_name }
-    })
-    .collect();
-  Ok(result)
-}
-
-#[tauri::command]
-fn set_world_name(account_id: String, world_id: String, name: String) -> Result<Vec<WorldInfo>, String> {
-  let pdir = players_dir(&account_id, &world_id)?;
-  let mut wc = load_world_config(&pdir);
-  let trimmed = name.trim().to_string();
-  if trimmed.is_empty() {
-    wc.display_name = None;
-  } else {
-    wc.display_name = Some(trimmed);
-  }
-  save_world_config(&pdir, &wc)?;
-  get_worlds_with_counts(account_id)
+fn config_path(app: &AppHandle) -> Result<PathBuf, String> {
+  config_path_in(&app.path().app_data_dir().map_err(|err| err.to_string())?)
 }
 
-#[tauri::command]
-fn reset_world_name(account_id: String, world_id: String) -> Result<Vec<WorldInfo>, String> {
-  let pdir = players_dir(&account_id, &world_id)?;
-  let mut wc = load_world_config(&pdir);
-  wc.display_name = None;
-  save_world_config(&pdir, &wc)?;
-  get_worlds_with_counts(account_id)
+fn config_path_in(data_dir: &Path) -> Result<PathBuf, String> {
+  let dir = data_dir.join("palworld-host-switcher");
+  fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+  Ok(dir.join("config.json"))
 }
 
-#[tauri::command]
-async fn get_players(app: AppHandle, account_id: String, world_id: String) -> Result<Vec<Player>, String> {
-  let a = app.clone();
-  tauri::async_runtime::spawn_blocking(move || {
-    get_players_sync(&a, &account_id, &world_id)
-  })
-  .await
-  .map_err(|e| format!("Task error: {e}"))?
+fn load_app_config(app: &AppHandle) -> Result<AppConfig, String> {
+  read_app_config(&config_path(app)?)
 }
 
-fn get_players_sync(app: &AppHandle, account_id: &str, world_id: &str) -> Result<Vec<Player>, String> {
-  let dir = players_dir(account_id, world_id)?;
-  let wpath = world_dir(account_id, world_id)?;
-  let player_ids = list_player_ids(&dir);
-  if player_ids.is_empty() {
-    return Ok(Vec::new());
+fn read_app_config(path: &Path) -> Result<AppConfig, String> {
+  if !path.exists() {
+    return Ok(AppConfig::default());
   }
-  let wc = load_world_config(&dir);
-  let host_id = resolve_host_id(&wc, &player_ids).ok_or("Host not found.")?;
-
-  // Read player info from Level.sav
-  let level_info = match extract_players_from_level(&wpath) {
-    Ok(info) => info,
-    Err(e) => {
-      eprintln!("[palhost] Failed to parse Level.sav: {e}");
-      Vec::new()
-    }
-  };
-
-  let players = build_players(&player_ids, &host_id, &level_info);
-
-  // Remember last-used account/world
-  let mut ac = load_app_config(app).unwrap_or_default();
-  ac.account_id = Some(account_id.to_string());
-  ac.world_id = Some(world_id.to_string());
-  let _ = save_app_config(app, &ac);
+  let raw = fs::read_to_string(path).map_err(|err| err.to_string())?;
+  serde_json::from_str(&raw).map_err(|err| err.to_string())
+}
 
-  Ok(players)
+/// Hand the settings the save code reads from globals (Oodle DLL, Epic
+/// root, custom host slots) over to it.
+fn apply_app_config(config: &AppConfig) {
+  oodle::set_dll_path(config.oodle_dll_path.as_ref().map(PathBuf::from));
+  set_epic_save_root(config.epic_save_root.as_ref().map(PathBuf::from));
+  set_custom_host_id_list(valid_custom_host_ids(&config.custom_host_ids));
 }
 
-#[tauri::command]
-async fn set_host_player(
-  app: AppHandle,
-  account_id: String,
-  world_id: String,
-  player_id: String,
-) -> Result<Vec<Player>, String> {
-  let a = app.clone();
-  tauri::async_runtime::spawn_blocking(move || {
-    set_host_player_sync(&a, &account_id, &world_id, &player_id)
-  })
-  .await
-  .map_err(|e| format!("Task error: {e}"))?
+/// A custom host slot id as flat hex (dashes dropped); `None` unless it is
+/// a 32-character hex id.
+fn normalize_host_id(id: &str) -> Option<String> {
+  let n = normalize_id(id).replace('-', "");
+  is_hex_id(&n).then_some(n)
 }
 
-fn set_host_player_sync(
-  app: &AppHandle,
-  account_id: &str,
-  world_id: &str,
-  player_id: &str,
-) -> Result<Vec<Player>, String> {
-  let dir = players_dir(account_id, world_id)?;
-  let wpath = world_dir(account_id, world_id)?;
-  let player_ids = list_player_ids(&dir);
-  let wc = load_world_config(&dir);
-  let host_id = resolve_host_id(&wc, &player_ids).ok_or("Host not found.")?;
-  let target_id = normalize_id(player_id);
-  if host_id == target_id {
-    return get_players_sync(app, account_id, world_id);
+/// The `custom_host_ids` of a settings file, normalized and deduplicated.
+/// The file may have been edited by hand, so bad ids are logged and dropped.
+fn valid_custom_host_ids(ids: &[String]) -> Vec<String> {
+  let mut valid: Vec<String> = Vec::new();
+  for id in ids {
+    match normalize_host_id(id) {
+      Some(n) if !valid.contains(&n) => valid.push(n),
+      Some(_) => {}
+      None => log_event(log::Level::Warn, &format!("ignoring custom host id '{id}': not a 32-character hex id")),
+    }
   }
-  swap_players_full(&wpath, &dir, &host_id, &target_id, Some((app, 0.0, 90.0)))?;
-  let _ = app.emit("swap-progress", ProgressPayload { percent: 95.0, message: "Reloading players…".into() });
-  get_players_sync(app, account_id, world_id)
+  valid
 }
 
-#[tauri::command]
-async fn swap_players(
-  app: AppHandle,
-  account_id: String,
-  world_id: String,
-  first_id: String,
-  second_id: String,
-) -> Result<Vec<Player>, String> {
-  let a = app.clone();
-  tauri::async_runtime::spawn_blocking(move || {
-    swap_players_sync(&a, &account_id, &world_id, &first_id, &second_id)
-  })
-  .await
-  .map_err(|e| format!("Task error: {e}"))?
+fn save_app_config(app: &AppHandle, config: &AppConfig) -> Result<(), String> {
+  let path = config_path(app)?;
+  let raw = serde_json::to_string_pretty(config).map_err(|err| err.to_string())?;
+  fs::write(path, raw).map_err(|err| err.to_string())
 }
 
-fn swap_players_sync(
-  app: &AppHandle,
-  account_id: &str,
-  world_id: &str,
-  first_id: &str,
-  second_id: &str,
-) -> Result<Vec<Player>, String> {
-  let dir = players_dir(account_id, world_id)?;
-  let wpath = world_dir(account_id, world_id)?;
-  let first = normalize_id(first_id);
-  let second = normalize_id(second_id);
-  swap_players_full(&wpath, &dir, &first, &second, Some((app, 0.0, 90.0)))?;
-  let _ = app.emit("swap-progress", ProgressPayload { percent: 95.0, message: "Reloading players…".into() });
-  get_players_sync(app, account_id, world_id)
+/// Merge a partial camelCase JSON object into the settings, rejecting
+/// unknown fields, wrong types and out-of-range values.
+fn merge_app_settings(current: AppSettings, patch: &Value) -> Result<AppSettings, String> {
+  let patch = patch.as_object().ok_or("Settings patch must be a JSON object.")?;
+  let mut merged = serde_json::to_value(current).map_err(|e| e.to_string())?;
+  for (key, value) in patch {
+    merged[key] = value.clone();
+  }
+  let settings: AppSettings = serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {e}"))?;
+  if let Some(g) = settings.progress_granularity {
+    if !(1..=10).contains(&g) {
+      return Err("progressGranularity must be between 1 and 10.".into());
+    }
+  }
+  Ok(settings)
 }
 
+// ── Per-world config (stored in the world's Players folder) ──
 
+fn world_config_path(pdir: &Path) -> PathBuf {
+  pdir.join(WORLD_CONFIG_FILE)
+}
 
-#[tauri::command]
-fn create_backup(
-  _app: AppHandle,
-  account_id: String,
-  world_id: String,
-  player_ids: Vec<String>,
-) -> Result<String, String> {
-  let dir = players_dir(&account_id, &world_id)?;
-  let wpath = world_dir(&account_id, &world_id)?;
-  let wc = load_world_config(&dir);
-  let snapshot = BackupSnapshot {
-    host_id: wc.host_id.clone(),
-    players: wc.players.clone(),
-    original_names: wc.original_names.clone(),
-    display_name: wc.display_name.clone(),
+fn load_world_config(pdir: &Path) -> WorldConfig {
+  let path = world_config_path(pdir);
+  if !path.exists() {
+    return WorldConfig::default();
+  }
+  let mut wc: WorldConfig = match fs::read_to_string(&path) {
+    Ok(raw) => serde_json::from_str(&raw).unwrap_or_default(),
+    Err(_) => WorldConfig::default(),
   };
-  let backup_dir = backup_files(&dir, &wpath, &player_ids, &snapshot)?;
-  Ok(backup_dir.to_string_lossy().to_string())
+  // Names are keyed by slot; if the roster changed behind our back (deleted
+  // file, re-import), forget the slots that are gone. Only in memory: the
+  // next save (made under the world lock) writes the pruned config.
+  if let Some(sum) = &wc.config_checksum {
+    if *sum != roster_checksum(pdir) {
+      log_event(log::Level::Warn, &format!("{:?}: roster changed since config was saved, dropping names of removed players", path));
+      prune_world_config(&mut wc, &list_player_ids(pdir));
+    }
+  }
+  wc
 }
 
-#[tauri::command]
-fn list_backups(account_id: String, world_id: String) -> Result<Vec<String>, String> {
-  let dir = players_dir(&account_id, &world_id)?;
-  Ok(list_backups_dir(&dir))
+/// Hash of the sorted player IDs plus the host slot, stored as
+/// `config_checksum` whenever the world config is saved.
+fn roster_checksum(pdir: &Path) -> String {
+  use sha2::{Digest, Sha256};
+  let mut ids = list_player_ids(pdir);
+  ids.sort();
+  let host = resolve_host_id(&WorldConfig::default(), &ids).unwrap_or_default();
+  let digest = Sha256::digest(format!("{}|{host}", ids.join(",")));
+  digest.iter().map(|b| format!("{b:02x}")).collect()
 }
 
-#[tauri::command]
-async fn restore_backup(
-  app: AppHandle,
-  account_id: String,
-  world_id: String,
-  backup_name: String,
-) -> Result<Vec<Player>, String> {
-  let a = app.clone();
-  tauri::async_runtime::spawn_blocking(move || {
-    restore_backup_sync(&a, &account_id, &world_id, &backup_name)
-  })
-  .await
-  .map_err(|e| format!("Task error: {e}"))?
+fn save_world_config(pdir: &Path, wc: &WorldConfig) -> Result<(), String> {
+  // Ensure directory exists (it should, but be safe)
+  if !pdir.exists() {
+    fs::create_dir_all(pdir).map_err(|err| err.to_string())?;
+  }
+  let path = world_config_path(pdir);
+  let wc = WorldConfig { config_checksum: Some(roster_checksum(pdir)), ..wc.clone() };
+  let raw = serde_json::to_string_pretty(&wc).map_err(|err| err.to_string())?;
+  fs::write(path, raw).map_err(|err| err.to_string())
 }
 
-fn restore_backup_sync(
-  app: &AppHandle,
-  account_id: &str,
-  world_id: &str,
-  backup_name: &str,
-) -> Result<Vec<Player>, String> {
-  let dir = players_dir(account_id, world_id)?;
-  let wpath = world_dir(account_id, world_id)?;
-  let backup_dir = dir.join("backup").join(backup_name);
-  if !backup_dir.exists() {
-    return Err("Backup not found.".to_string());
-  }
+/// Prune stale player entries from WorldConfig that no longer have .sav files.
+fn prune_world_config(wc: &mut WorldConfig, live_ids: &[String]) {
+  wc.players.retain(|id, _| live_ids.contains(id));
+  wc.original_names.retain(|id, _| live_ids.contains(id));
+}
 
-  // Restore .sav files
-  let entries = fs::read_dir(&backup_dir).map_err(|err| err.to_string())?;
-  for entry in entries.flatten() {
-    let file_path = entry.path();
-    if let Some(name) = file_path.file_name().and_then(|value| value.to_str()) {
-      if name.ends_with(".sav") {
-        if name == "Level.sav" {
-          // Restore Level.sav to world root
-          let dest = wpath.join(name);
-          fs::copy(&file_path, dest).map_err(|err| err.to_string())?;
-        } else {
-          // Restore player .sav to Players dir
-          let dest = dir.join(name);
-          fs::copy(&file_path, dest).map_err(|err| err.to_string())?;
+// ── Migration: move old app-level configs into world folders ──
+
+fn migrate_legacy_config(app: &AppHandle) -> Result<(), String> {
+  let mut config = load_app_config(app)?;
+  let mut migrated = false;
+
+  // 1. Migrate flat legacy fields (very old format)
+  if !config.players.is_empty() || !config.original_names.is_empty() || config.host_id.is_some() {
+    if let (Some(aid), Some(wid)) = (config.account_id.clone(), config.world_id.clone()) {
+      if let Ok(pdir) = players_dir(&aid, &wid) {
+        if pdir.exists() {
+          let mut wc = load_world_config(&pdir);
+          // Only migrate if the world config is empty (don't overwrite)
+          if wc.players.is_empty() {
+            wc.players = std::mem::take(&mut config.players);
+          } else {
+            config.players.clear();
+          }
+          if wc.original_names.is_empty() {
+            wc.original_names = std::mem::take(&mut config.original_names);
+          } else {
+            config.original_names.clear();
+          }
+          if wc.host_id.is_none() {
+            wc.host_id = config.host_id.take();
+          } else {
+            config.host_id = None;
+          }
+          let _ = save_world_config(&pdir, &wc);
+          migrated = true;
         }
       }
     }
   }
 
-  // Restore config snapshot into world-local config
-  let snapshot_path = backup_dir.join("config_snapshot.json");
-  if snapshot_path.exists() {
-    let raw = fs::read_to_string(&snapshot_path).map_err(|err| err.to_string())?;
-    if let Ok(snapshot) = serde_json::from_str::<BackupSnapshot>(&raw) {
-      let mut wc = load_world_config(&dir);
-      wc.players = snapshot.players;
-      wc.original_names = snapshot.original_names;
-      wc.host_id = snapshot.host_id;
-      wc.display_name = snapshot.display_name;
-      save_world_config(&dir, &wc)?;
+  // 2. Migrate per-world map entries (previous session format)
+  if !config.worlds.is_empty() {
+    for (key, wc_old) in std::mem::take(&mut config.worlds) {
+      // key format is "accountId/worldId"
+      let parts: Vec<&str> = key.splitn(2, '/').collect();
+      if parts.len() == 2 {
+        if let Ok(pdir) = players_dir(parts[0], parts[1]) {
+          if pdir.exists() {
+            let mut wc = load_world_config(&pdir);
+            // Merge: only fill in missing data
+            if wc.players.is_empty() {
+              wc.players = wc_old.players;
+            }
+            if wc.original_names.is_empty() {
+              wc.original_names = wc_old.original_names;
+            }
+            if wc.host_id.is_none() {
+              wc.host_id = wc_old.host_id;
+            }
+            let _ = save_world_config(&pdir, &wc);
+          }
+        }
+      }
     }
+    migrated = true;
   }
 
-  get_players_sync(app, account_id, world_id)
+  if migrated {
+    save_app_config(app, &config)?;
+  }
+  Ok(())
 }
 
-#[tauri::command]
-fn delete_backup(account_id: String, world_id: String, backup_name: String) -> Result<Vec<String>, String> {
-  let dir = players_dir(&account_id, &world_id)?;
-  let backup_dir = dir.join("backup").join(&backup_name);
-  if backup_dir.exists() {
-    fs::remove_dir_all(&backup_dir).map_err(|err| err.to_string())?;
-  }
-  Ok(list_backups_dir(&dir))
+fn list_dirs(path: &Path) -> Vec<String> {
+  fs::read_dir(path)
+    .ok()
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.file_type().map(|file| file.is_dir()).unwrap_or(false))
+    .filter_map(|entry| entry.file_name().into_string().ok())
+    .collect()
 }
 
-#[tauri::command]
-fn delete_all_backups(account_id: String, world_id: String) -> Result<Vec<String>, String> {
-  let dir = players_dir(&account_id, &world_id)?;
-  let backup_root = dir.join("backup");
-  if backup_root.exists() {
-    fs::remove_dir_all(&backup_root).map_err(|err| err.to_string())?;
+fn is_hex_id(value: &str) -> bool {
+  value.len() == 32 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// `filename_to_uuid` for ids coming from the UI: an error unless `id` is a
+/// 32-digit hex player id.
+fn player_id_to_uuid(id: &str) -> Result<String, String> {
+  if !is_hex_id(id) {
+    return Err(format!("Invalid player id: {id}"));
   }
-  Ok(Vec::new())
+  Ok(filename_to_uuid(id))
 }
 
-// ── World transfer ────────────────────────────────────────
+/// Convert a GVAS UUID (with dashes) to a Palworld .sav filename (flat hex).
+fn uuid_to_filename(uuid: &str) -> String {
+  uuid.replace('-', "").to_ascii_lowercase()
+}
 
-/// Export a world folder as a ZIP file (runs on background thread).
-#[tauri::command]
-async fn export_world(app: AppHandle, account_id: String, world_id: String, dest_path: String) -> Result<String, String> {
-  let app2 = app.clone();
-  tauri::async_runtime::spawn_blocking(move || {
-    export_world_sync(&app2, &account_id, &world_id, &dest_path)
-  })
-  .await
-  .map_err(|e| format!("Task error: {e}"))?
+/// Convert a flat-hex filename to a GVAS UUID (with dashes). Anything that
+/// isn't 32 hex digits comes back lowercased but otherwise unchanged.
+fn filename_to_uuid(filename: &str) -> String {
+  let s = filename.to_ascii_lowercase();
+  if !is_hex_id(&s) {
+    return s;
+  }
+  format!(
+    "{}-{}-{}-{}-{}",
+    &s[0..8],
+    &s[8..12],
+    &s[12..16],
+    &s[16..20],
+    &s[20..32]
+  )
 }
 
-fn export_world_sync(app: &AppHandle, account_id: &str, world_id: &str, dest_path: &str) -> Result<String, String> {
-  let wdir = world_dir(account_id, world_id)?;
-  if !wdir.exists() {
-    return Err("World folder does not exist.".to_string());
+/// The `custom_host_ids` setting, already normalized and validated.
+static CUSTOM_HOST_IDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn set_custom_host_id_list(ids: Vec<String>) {
+  if let Ok(mut slot) = CUSTOM_HOST_IDS.lock() {
+    *slot = ids;
   }
+}
 
-  let dest = PathBuf::from(dest_path);
+/// Host slot ids in priority order: custom ones first, then the built-ins.
+fn host_slot_ids() -> Vec<String> {
+  host_slot_ids_with(CUSTOM_HOST_IDS.lock().map(|c| c.clone()).unwrap_or_default())
+}
 
-  // Ensure destination directory exists
-  if let Some(parent) = dest.parent() {
-    if !parent.exists() {
-      fs::create_dir_all(parent).map_err(|e| format!("Cannot create destination folder: {e}"))?;
+fn host_slot_ids_with(mut ids: Vec<String>) -> Vec<String> {
+  for hid in [DEFAULT_HOST_ID, LEGACY_HOST_ID] {
+    if !ids.iter().any(|id| id == hid) {
+      ids.push(hid.to_string());
     }
   }
+  ids
+}
 
-  // ── Skip ALL backup directories for P2P export ──────────────────────
-  // Skip <worldDir>/backup/ (Palworld game backups: backup/world/ and backup/local/)
-  // and <worldDir>/Players/backup/ (PalHost swap backups).
-  // Backups are unnecessary for P2P transfer and can be 100MB+ each.
-  let skip_dirs: Vec<PathBuf> = vec![
-    wdir.join("backup"),
-    wdir.join("Players").join("backup"),
-  ];
+/// Check if a player ID (flat hex) is the host slot.
+fn is_host_slot(id: &str) -> bool {
+  let n = normalize_id(id);
+  host_slot_ids().contains(&n)
+}
 
-  // Count total files for progress (excluding skipped backup dirs)
-  let entries: Vec<_> = WalkDir::new(&wdir)
+fn list_player_ids(players_dir: &Path) -> Vec<String> {
+  let found: Vec<String> = fs::read_dir(players_dir)
+    .ok()
     .into_iter()
-    .filter_map(|e| e.ok())
-    .filter(|e| {
-      let p = e.path();
-      !skip_dirs.iter().any(|sk| p.starts_with(sk))
-    })
+    .flatten()
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.file_type().map(|file| file.is_file()).unwrap_or(false))
+    .filter_map(|entry| entry.file_name().into_string().ok())
+    .filter_map(|name| name.strip_suffix(".sav").map(|id| id.to_string()))
+    .map(|id| normalize_id(&id))
+    .filter(|id| is_hex_id(id))
     .collect();
-  let total = entries.iter().filter(|e| e.path().is_file()).count().max(1);
-  let mut done = 0usize;
-  let mut last_pct = 0u32;
+  // Case variants (e.g. from a case-sensitive share) collapse to one id;
+  // `scan_world` reports them
+  let mut seen = std::collections::HashSet::new();
+  found.into_iter().filter(|id| seen.insert(id.clone())).collect()
+}
 
-  let _ = app.emit("export-progress", ProgressPayload { percent: 0.0, message: "Starting export…".to_string() });
+/// Every `.sav` in `players_dir` whose name is `id` in any letter case.
+fn player_file_variants(players_dir: &Path, id: &str) -> Vec<PathBuf> {
+  fs::read_dir(players_dir)
+    .ok()
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| {
+      let name = entry.file_name().to_string_lossy().to_string();
+      name.strip_suffix(".sav").is_some_and(|stem| normalize_id(stem) == id)
+    })
+    .map(|entry| entry.path())
+    .collect()
+}
 
-  let file = fs::File::create(&dest)
-    .map_err(|e| format!("Cannot create ZIP file: {e}"))?;
-  let mut zip = zip::ZipWriter::new(file);
-  let options = SimpleFileOptions::default()
-    .compression_method(zip::CompressionMethod::Deflated)
-    .unix_permissions(0o644);
+/// Leave exactly one `<id>.sav` (lowercase) for `id`. With several case
+/// variants the newest wins; the others are renamed to `.case-dup-<stamp>`
+/// so they stop counting as player files but aren't lost. Returns the
+/// renames as (old, new) for `undo_renames`.
+fn canonicalize_player_file(players_dir: &Path, id: &str) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+  let canonical = format!("{id}.sav");
+  let mut variants = player_file_variants(players_dir, id);
+  let is_canonical = |p: &PathBuf| p.file_name().is_some_and(|n| n == canonical.as_str());
+  let mut renames = Vec::new();
+  if variants.len() <= 1 && variants.iter().all(is_canonical) {
+    return Ok(renames);
+  }
+  variants.sort_by_key(|p| fs::metadata(p).and_then(|m| m.modified()).ok());
+  let keep = variants.pop().ok_or("No player file to canonicalize.")?;
+  let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+  for stray in variants {
+    let mut aside = stray.clone().into_os_string();
+    aside.push(format!(".case-dup-{stamp}"));
+    fs::rename(&stray, &aside).map_err(|e| format!("Cannot move aside {}: {e}", stray.display()))?;
+    log_event(log::Level::Warn, &format!("moved case-variant duplicate {} aside", stray.display()));
+    renames.push((stray, PathBuf::from(aside)));
+  }
+  if !is_canonical(&keep) {
+    let target = players_dir.join(&canonical);
+    rename_via_temp(&keep, &target)?;
+    renames.push((keep, target));
+  }
+  Ok(renames)
+}
 
-  // Walk the world directory and add all files
-  for entry in &entries {
-    let abs_path = entry.path();
-    let rel_path = abs_path.strip_prefix(&wdir).map_err(|e| e.to_string())?;
+/// Rename through a temp name so a case-only rename also works on
+/// case-insensitive filesystems.
+fn rename_via_temp(from: &Path, to: &Path) -> Result<(), String> {
+  let mut temp = to.as_os_str().to_owned();
+  temp.push(".case-tmp");
+  fs::rename(from, &temp).map_err(|e| format!("Cannot rename {}: {e}", from.display()))?;
+  fs::rename(&temp, to).map_err(|e| format!("Cannot rename to {}: {e}", to.display()))
+}
 
-    // Use world_id as the root folder name inside the ZIP
-    let archive_path = PathBuf::from(world_id).join(rel_path);
-    let archive_name = archive_path.to_string_lossy().replace('\\', "/");
+/// Put files renamed by `canonicalize_player_file` back, newest rename first.
+fn undo_renames(renames: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+  renames.iter().rev().try_for_each(|(old, new)| rename_via_temp(new, old))
+}
 
-    if abs_path.is_dir() {
-      zip.add_directory(&archive_name, options)
-        .map_err(|e| format!("Error adding folder to ZIP: {e}"))?;
-    } else {
-      zip.start_file(&archive_name, options)
-        .map_err(|e| format!("Error adding file to ZIP: {e}"))?;
-      let mut f = fs::File::open(abs_path)
-        .map_err(|e| format!("Cannot read {}: {e}", abs_path.display()))?;
-      let mut buf = Vec::new();
-      f.read_to_end(&mut buf)
-        .map_err(|e| format!("File read error: {e}"))?;
-      zip.write_all(&buf)
-        .map_err(|e| format!("ZIP write error: {e}"))?;
-      done += 1;
-      let pct = (done as f64 / total as f64 * 100.0).min(100.0) as u32;
-      // Throttle: emit only when percentage changes by at least 2%
-      if pct >= last_pct + 2 || done == total {
-        last_pct = pct;
-        let _ = app.emit("export-progress", ProgressPayload { percent: pct as f64, message: format!("Compressing… {done}/{total}") });
-      }
-    }
-  }
+/// The player file in a host slot, i.e. the one the game loads as host. The
+/// world config's `host_id` only picks between slots (say a custom one and
+/// 0001 both present); a non-slot `host_id` is ignored.
+fn host_slot_in(wc: &WorldConfig, player_ids: &[String], slots: &[String]) -> Option<String> {
+  configured_host(wc, player_ids)
+    .filter(|hid| slots.contains(hid))
+    .or_else(|| slots.iter().find(|hid| player_ids.contains(hid)).cloned())
+}
 
-  zip.finish().map_err(|e| format!("Error finalizing ZIP: {e}"))?;
-  let _ = app.emit("export-progress", ProgressPayload { percent: 100.0, message: "Export complete.".to_string() });
-  Ok(dest.to_string_lossy().to_string())
+/// The world config's `host_id`, if that player has a file.
+fn configured_host(wc: &WorldConfig, player_ids: &[String]) -> Option<String> {
+  wc.host_id
+    .as_deref()
+    .map(|hid| uuid_to_filename(&normalize_id(hid)))
+    .filter(|hid| player_ids.contains(hid))
 }
 
-/// Validate a folder to check if it looks like a valid Palworld world.
-/// Returns the folder name (world ID).
-#[tauri::command]
-fn validate_world_folder(folder_path: String) -> Result<ValidatedFolder, String> {
-  let src = PathBuf::from(&folder_path);
-  if !src.exists() || !src.is_dir() {
-    return Err("The path is not a valid folder.".to_string());
-  }
+/// The host for display: the configured `host_id` when present, else the
+/// host slot's player, else the first player. Swaps and the delete guard use
+/// `host_slot_in`, which only trusts real slots.
+fn resolve_host_id(wc: &WorldConfig, player_ids: &[String]) -> Option<String> {
+  configured_host(wc, player_ids)
+    .or_else(|| host_slot_in(wc, player_ids, &host_slot_ids()))
+    .or_else(|| player_ids.first().cloned())
+}
 
-  // Helper: check if a directory looks like a valid Palworld world
-  let is_valid_world = |dir: &Path| -> bool {
-    let players_sub = dir.join("Players");
-    let has_players = players_sub.exists() && players_sub.is_dir();
-    let has_sav = fs::read_dir(dir)
-      .ok()
-      .into_iter()
-      .flatten()
-      .filter_map(|e| e.ok())
-      .any(|e| {
-        e.path()
-          .extension()
-          .map(|ext| ext == "sav")
-          .unwrap_or(false)
-      });
-    has_players || has_sav
-  };
+// ── Level.sav player extraction ──────────────────────────
 
-  // First, check the folder itself
-  if is_valid_world(&src) {
-    let folder_name = src
-      .file_name()
-      .and_then(|n| n.to_str())
-      .ok_or("Invalid folder name.")?
-      .to_string();
-    return Ok(ValidatedFolder { name: folder_name, path: folder_path });
+/// Information extracted from Level.sav about a single player.
+#[allow(dead_code)]
+#[derive(Clone)]
+struct LevelPlayerInfo {
+  uuid: String,      // GVAS UUID with dashes
+  filename: String,   // flat hex for .sav filename
+  name: String,
+  level: u32,
+  pals_count: usize,
+  last_online: String,
+  last_online_ticks: i64,
+  guild_name: String,
+  instance_id: String, // CSPM key InstanceId ("" if no character entry)
+  group_id: Option<String>, // character rawdata group_id
+}
+
+/// One decoded CharacterSaveParameterMap entry (a player or a pal).
+struct CharacterEntry<'a> {
+  player_uid: &'a str,
+  instance_id: &'a str,
+  /// Decoded RawData: {object, unknown_bytes, group_id, trailing_bytes}
+  raw: &'a Value,
+  save_param: &'a Value,
+}
+
+impl CharacterEntry<'_> {
+  fn is_player(&self) -> bool {
+    self.save_param["IsPlayer"]["value"].as_bool().unwrap_or(false)
   }
 
-  // Fallback: check for a subfolder with the same name (common after ZIP extraction)
-  let folder_name = src
-    .file_name()
-    .and_then(|n| n.to_str())
-    .ok_or("Invalid folder name.")?
-    .to_string();
-  let nested = src.join(&folder_name);
-  if nested.exists() && nested.is_dir() && is_valid_world(&nested) {
-    return Ok(ValidatedFolder {
-      name: folder_name,
-      path: nested.to_string_lossy().to_string(),
-    });
+  /// Level is a ByteProperty: {"value": {"type":"None","value":55}}
+  fn level(&self) -> Option<u32> {
+    self.save_param["Level"]["value"]["value"].as_u64().map(|l| l as u32)
   }
 
-  // Also check any single subfolder (in case name differs)
-  let sub_entries: Vec<_> = fs::read_dir(&src)
-    .ok()
+  /// Owning player's UID, if set (pals only).
+  fn owner(&self) -> Option<&str> {
+    self.save_param["OwnerPlayerUId"]["value"]
+      .as_str()
+      .filter(|o| !o.is_empty() && *o != "00000000-0000-0000-0000-000000000000")
+  }
+}
+
+/// Iterate the decoded entries of worldSaveData's CharacterSaveParameterMap.
+fn character_entries(world_data: &Value) -> impl Iterator<Item = CharacterEntry<'_>> {
+  world_data["CharacterSaveParameterMap"]["value"]
+    .as_array()
     .into_iter()
     .flatten()
-    .filter_map(|e| e.ok())
-    .filter(|e| e.path().is_dir())
-    .collect();
-  if sub_entries.len() == 1 {
-    let sub = &sub_entries[0];
-    let sub_path = sub.path();
-    if is_valid_world(&sub_path) {
-      let sub_name = sub_path
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(&folder_name)
-        .to_string();
-      return Ok(ValidatedFolder {
-        name: sub_name,
-        path: sub_path.to_string_lossy().to_string(),
-      });
-    }
-  }
+    .filter_map(|entry| {
+      let raw = entry.pointer("/value/RawData/value")?;
+      Some(CharacterEntry {
+        player_uid: entry.pointer("/key/PlayerUId/value").and_then(|v| v.as_str()).unwrap_or(""),
+        instance_id: entry.pointer("/key/InstanceId/value").and_then(|v| v.as_str()).unwrap_or(""),
+        raw,
+        save_param: &raw["object"]["SaveParameter"]["value"],
+      })
+    })
+}
 
-  Err("The folder does not appear to be a valid Palworld world (missing Players/ folder and .sav files).".to_string())
+struct LevelCacheEntry {
+  path: PathBuf,
+  /// SHA-256 of the Level.sav the players came from. Keyed on content, not
+  /// mtime: a same-size rewrite within the mtime resolution must miss.
+  digest: String,
+  players: Vec<LevelPlayerInfo>,
 }
 
-/// Check if a world folder already exists for the given account.
-#[tauri::command]
-fn check_world_exists(account_id: String, world_name: String) -> Result<bool, String> {
-  if account_id.trim().is_empty() || world_name.trim().is_empty() {
-    return Ok(false);
+fn level_digest(data: &[u8]) -> String {
+  use sha2::{Digest, Sha256};
+  Sha256::digest(data).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn cached_level_players(level_sav: &Path) -> Option<Vec<LevelPlayerInfo>> {
+  cached_level_players_for(level_sav, &sha256_file(level_sav).ok()?)
+}
+
+fn cached_level_players_for(level_sav: &Path, digest: &str) -> Option<Vec<LevelPlayerInfo>> {
+  let mut cache = LEVEL_CACHE.lock().ok()?;
+  let idx = cache.iter().position(|e| e.path == level_sav && e.digest == digest)?;
+  let entry = cache.remove(idx);
+  let players = entry.players.clone();
+  cache.push(entry);
+  Some(players)
+}
+
+/// Read Level.sav and extract player info (name, level, pals, etc.).
+/// Results are cached until the file's content changes.
+fn extract_players_from_level(world_path: &Path) -> Result<Vec<LevelPlayerInfo>, String> {
+  let level_sav = world_path.join("Level.sav");
+  if !level_sav.exists() {
+    return Err("Level.sav not found.".into());
   }
-  let target = save_games_root()?.join(&account_id).join(&world_name);
-  Ok(target.exists())
+  let data = fs::read(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+  let digest = level_digest(&data);
+  if let Some(players) = cached_level_players_for(&level_sav, &digest) {
+    return Ok(players);
+  }
+  let (json, _save_type) = gvas::sav_to_json(&data)?;
+  let players = players_from_level_json(&json);
+  if let Ok(mut cache) = LEVEL_CACHE.lock() {
+    cache.retain(|e| e.path != level_sav);
+    if cache.len() >= LEVEL_CACHE_CAPACITY {
+      cache.remove(0);
+    }
+    cache.push(LevelCacheEntry { path: level_sav, digest, players: players.clone() });
+  }
+  Ok(players)
 }
 
-/// Import a world folder into the account's save directory (runs on background thread).
-/// mode: "replace" | "new"
-/// new_name is used only when mode == "new"
+/// Parse a world's Level.sav in the background so the next `get_players` is
+/// instant. Returns before touching the save; the background task is a
+/// no-op when the cache is already warm or another prewarm is running.
 #[tauri::command]
-async fn import_world(
-  app: AppHandle,
-  account_id: String,
-  folder_path: String,
-  mode: String,
-  new_name: Option<String>,
-) -> Result<Vec<WorldInfo>, String> {
-  let app2 = app.clone();
+fn prewarm_world(account_id: String, world_id: String) -> Result<(), String> {
+  let wpath = world_dir(&account_id, &world_id)?;
   tauri::async_runtime::spawn_blocking(move || {
-    import_world_sync(&app2, &account_id, &folder_path, &mode, new_name.as_deref())
-  })
-  .await
-  .map_err(|e| format!("Task error: {e}"))?
+    let level_sav = wpath.join("Level.sav");
+    if cached_level_players(&level_sav).is_some() {
+      return;
+    }
+    // Another prewarm is already parsing this world
+    let Some(_claim) = PrewarmClaim::take(&level_sav) else {
+      return;
+    };
+    if let Err(e) = extract_players_from_level(&wpath) {
+      log_event(log::Level::Warn, &format!("prewarm {world_id}: {e}"));
+    }
+  });
+  Ok(())
 }
 
-fn import_world_sync(
-  app: &AppHandle,
-  account_id: &str,
-  folder_path: &str,
-  mode: &str,
-  new_name: Option<&str>,
-) -> Result<Vec<WorldInfo>, String> {
-  let src = PathBuf::from(folder_path);
-  if !src.exists() || !src.is_dir() {
-    return Err("Source folder does not exist.".to_string());
-  }
-
-  let folder_name = src
-    .file_name()
-    .and_then(|n| n.to_str())
-    .ok_or("Invalid source folder name.")?
-    .to_string();
+/// Marks a Level.sav in `LEVEL_PREWARMS` until dropped.
+struct PrewarmClaim(PathBuf);
 
-  let target_name = match mode {
-    "new" => {
-      let n = new_name.unwrap_or(&folder_name).to_string();
-      if n.trim().is_empty() {
-        return Err("World name cannot be empty.".to_string());
-      }
-      n
+impl PrewarmClaim {
+  /// `None` if a prewarm of `level_sav` is already running.
+  fn take(level_sav: &Path) -> Option<Self> {
+    let mut running = LEVEL_PREWARMS.lock().ok()?;
+    if running.iter().any(|p| p == level_sav) {
+      return None;
     }
-    _ => folder_name.clone(),
-  };
-
-  let account_root = save_games_root()?.join(account_id);
-  if !account_root.exists() {
-    return Err("Account folder does not exist.".to_string());
+    running.push(level_sav.to_path_buf());
+    Some(Self(level_sav.to_path_buf()))
   }
-  let target = account_root.join(&target_name);
+}
 
-  if mode == "new" && target.exists() {
-    return Err(format!("A world named '{}' already exists.", target_name));
-  }
-
-  if mode == "replace" {
-    if target.exists() {
-      // Remove everything EXCEPT backup/world and backup/local
-      remove_dir_except_backups(&target)
-        .map_err(|e| format!("Cannot clean existing world: {e}"))?;
+impl Drop for PrewarmClaim {
+  fn drop(&mut self) {
+    if let Ok(mut running) = LEVEL_PREWARMS.lock() {
+      running.retain(|p| p != &self.0);
     }
   }
+}
 
-  // ── Build skip-set for old backups in the SOURCE ──────────────────
-  // Keep only the most recent backup subfolder in each category
-  // so we don't bloat the destination with tons of old backup folders.
-  let mut skip_src_dirs: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
+/// Extract player info from an already-parsed Level.sav.
+fn players_from_level_json(json: &Value) -> Vec<LevelPlayerInfo> {
+  let world_data = &json["properties"]["worldSaveData"]["value"];
 
-  for sub in &["world", "local"] {
-    let bdir = src.join("backup").join(sub);
-    if bdir.is_dir() {
-      if let Ok(rd) = fs::read_dir(&bdir) {
-        let mut folders: Vec<PathBuf> = rd
-          .filter_map(|e| e.ok())
-          .filter(|e| e.path().is_dir())
-          .map(|e| e.path())
-          .collect();
-        // Sort descending by name (timestamp format sorts lexicographically)
-        folders.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
-        // Skip everything except the most recent
-        for old in folders.iter().skip(1) {
-          skip_src_dirs.insert(old.clone());
+  // ── 1. Extract guild info from GroupSaveDataMap ──
+  // Maps: player_uuid → (player_name, last_online_ticks, guild_name)
+  let mut guild_info: HashMap<String, (String, i64, String)> = HashMap::new();
+
+  // Solo players who never joined a multiplayer guild only appear in an
+  // IndependentGuild, so read those too but let a real Guild win.
+  if let Some(gsm) = world_data.get("GroupSaveDataMap") {
+    if let Some(entries) = gsm.get("value").and_then(|v| v.as_array()) {
+      for entry in entries {
+        let group_type = entry
+          .pointer("/value/GroupType/value/value")
+          .and_then(|v| v.as_str())
+          .unwrap_or("");
+        let raw_data = entry.pointer("/value/RawData/value");
+        if raw_data.is_none() {
+          continue;
+        }
+        let rd = raw_data.unwrap();
+        let g_name = rd["guild_name"].as_str().unwrap_or("").to_string();
+        let members: Vec<&Value> = match group_type {
+          "EPalGroupType::Guild" => rd["players"].as_array().map(|a| a.iter().collect()).unwrap_or_default(),
+          "EPalGroupType::IndependentGuild" => vec![rd],
+          _ => continue,
+        };
+        for p in members {
+          let puid = p["player_uid"].as_str().unwrap_or("").to_string();
+          let last_online = p["player_info"]["last_online_real_time"]
+            .as_i64()
+            .unwrap_or(0);
+          let pname = p["player_info"]["player_name"]
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+          if puid.is_empty() || (group_type != "EPalGroupType::Guild" && guild_info.contains_key(&puid)) {
+            continue;
+          }
+          guild_info.insert(puid, (pname, last_online, g_name.clone()));
         }
       }
     }
   }
 
-  // Count total files for progress (excluding skipped backup dirs)
-  let total_files = WalkDir::new(&src)
-    .into_iter()
-    .filter_map(|e| e.ok())
-    .filter(|e| {
-      let p = e.path();
-      !skip_src_dirs.iter().any(|sk| p.starts_with(sk))
-    })
-    .filter(|e| e.path().is_file())
-    .count()
-    .max(1);
-  let counter = std::sync::atomic::AtomicUsize::new(0);
-  let mut last_pct = 0u32;
-
-  let _ = app.emit("import-progress", ProgressPayload { percent: 0.0, message: "Starting import…".to_string() });
-
-  // Recursively copy src into target, merging backups and skipping old ones
-  copy_dir_recursive_merge(&src, &target, app, &counter, total_files, &mut last_pct, &skip_src_dirs)?;
-
-  let _ = app.emit("import-progress", ProgressPayload { percent: 100.0, message: "Import complete.".to_string() });
-
-  // Return updated world list
-  get_worlds_with_counts(account_id.to_string())
-}
-
-/// Remove all contents of a world directory EXCEPT backup/world and backup/local.
-/// This preserves existing game backups while replacing everything else.
-fn remove_dir_except_backups(dir: &Path) -> std::io::Result<()> {
-  for entry in fs::read_dir(dir)? {
-    let entry = entry?;
-    let path = entry.path();
-    let name = entry.file_name();
+  // ── 2. Extract character info from CharacterSaveParameterMap ──
+  // Maps: player_uuid → level, counts pals per owner
+  let mut player_levels: HashMap<String, u32> = HashMap::new();
+  let mut player_names_cspm: HashMap<String, String> = HashMap::new();
+  // player_uuid → (InstanceId, character group_id)
+  let mut player_ids_cspm: HashMap<String, (String, Option<String>)> = HashMap::new();
+  let mut pals_count: HashMap<String, usize> = HashMap::new();
 
-    if name == "backup" && path.is_dir() {
-      // Inside the backup folder, remove everything except "world" and "local"
-      for bentry in fs::read_dir(&path)? {
-        let bentry = bentry?;
-        let bname = bentry.file_name();
-        if bname != "world" && bname != "local" {
-          if bentry.path().is_dir() {
-            fs::remove_dir_all(bentry.path())?;
-          } else {
-            fs::remove_file(bentry.path())?;
-          }
-        }
+  for ch in character_entries(world_data) {
+    if ch.is_player() {
+      let nick = ch.save_param["NickName"]["value"].as_str().unwrap_or("").to_string();
+      let group_id = ch.raw["group_id"]
+        .as_str()
+        .filter(|g| *g != "00000000-0000-0000-0000-000000000000")
+        .map(String::from);
+      let player_uid = ch.player_uid.to_string();
+      player_ids_cspm.insert(player_uid.clone(), (ch.instance_id.to_string(), group_id));
+      player_levels.insert(player_uid.clone(), ch.level().unwrap_or(1));
+      if !nick.is_empty() {
+        player_names_cspm.insert(player_uid, nick);
       }
-    } else if path.is_dir() {
-      fs::remove_dir_all(&path)?;
-    } else {
-      fs::remove_file(&path)?;
+    } else if let Some(owner) = ch.owner() {
+      // This is a pal – count under owner
+      *pals_count.entry(owner.to_string()).or_insert(0) += 1;
     }
   }
-  Ok(())
-}
 
-/// Recursively copy src to dest, merging backup directories and skipping old backup folders.
-fn copy_dir_recursive_merge(
-  src: &Path,
-  dest: &Path,
-  app: &AppHandle,
-  counter: &std::sync::atomic::AtomicUsize,
-  total: usize,
-  last_pct: &mut u32,
-  skip_dirs: &std::collections::HashSet<PathBuf>,
-) -> Result<(), String> {
-  if !dest.exists() {
-    fs::create_dir_all(dest).map_err(|e| format!("Cannot create {}: {e}", dest.display()))?;
-  }
-  for entry in fs::read_dir(src).map_err(|e| format!("Cannot read {}: {e}", src.display()))? {
-    let entry = entry.map_err(|e| e.to_string())?;
-    let path = entry.path();
+  // ── 3. Get current game time for "last seen" calculation ──
+  let current_ticks = game_time_ticks(world_data, "RealDateTimeTicks");
 
-    // Skip old backup folders from the source
-    if skip_dirs.iter().any(|sk| path == *sk || path.starts_with(sk)) {
-      continue;
+  // ── 4. Build player list ──
+  // Combine guild_info + cspm data
+  let mut all_uuids: Vec<String> = Vec::new();
+  for uuid in guild_info.keys() {
+    if !all_uuids.contains(uuid) {
+      all_uuids.push(uuid.clone());
+    }
+  }
+  for uuid in player_levels.keys() {
+    if !all_uuids.contains(uuid) {
+      all_uuids.push(uuid.clone());
     }
+  }
 
-    let dest_path = dest.join(entry.file_name());
-    if path.is_dir() {
-      // For backup subdirs that already exist at destination, don't clear them — just merge
-      copy_dir_recursive_merge(&path, &dest_path, app, counter, total, last_pct, skip_dirs)?;
+  let mut result = Vec::new();
+  for uuid in &all_uuids {
+    let filename = uuid_to_filename(uuid);
+    let (guild_name_str, last_online_str, last_online_ticks, player_name) =
+      if let Some((name, ticks, gname)) = guild_info.get(uuid) {
+        let last_seen = format_last_seen(*ticks, current_ticks);
+        (gname.clone(), last_seen, *ticks, name.clone())
+      } else {
+        ("".to_string(), "Unknown".to_string(), 0, "".to_string())
+      };
+
+    let name = if !player_name.is_empty() {
+      player_name
+    } else if let Some(nick) = player_names_cspm.get(uuid) {
+      nick.clone()
     } else {
-      fs::copy(&path, &dest_path)
-        .map_err(|e| format!("Cannot copy {}: {e}", path.display()))?;
-      let done = counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
-      let pct = (done as f64 / total as f64 * 100.0).min(100.0) as u32;
-      if pct >= *last_pct + 2 || done == total {
-        *last_pct = pct;
-        let _ = app.emit("import-progress", ProgressPayload { percent: pct as f64, message: format!("Copying… {done}/{total}") });
-      }
-    }
+      filename.clone()
+    };
+
+    let level = player_levels.get(uuid).copied().unwrap_or(0);
+    let pals = pals_count.get(uuid).copied().unwrap_or(0);
+    let (instance_id, group_id) = player_ids_cspm.get(uuid).cloned().unwrap_or_default();
+
+    result.push(LevelPlayerInfo {
+      uuid: uuid.clone(),
+      filename,
+      name,
+      level,
+      pals_count: pals,
+      last_online: last_online_str,
+      last_online_ticks,
+      guild_name: guild_name_str,
+      instance_id,
+      group_id,
+    });
   }
-  Ok(())
+
+  result
 }
 
-#[tauri::command]
-fn is_palworld_running() -> bool {
-  use std::os::windows::process::CommandExt;
-  const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// A `GameTimeSaveData` clock ("RealDateTimeTicks" or "GameDateTimeTicks")
+/// from worldSaveData, in 100 ns ticks; 0 when missing.
+fn game_time_ticks(world_data: &Value, clock: &str) -> u64 {
+  world_data
+    .pointer(&format!("/GameTimeSaveData/value/{clock}/value"))
+    .and_then(|v| v.as_u64())
+    .unwrap_or(0)
+}
 
-  if let Ok(output) = StdCommand::new("tasklist")
-    .args(["/FI", "IMAGENAME eq Palworld-Win64-Shipping.exe", "/NH", "/FO", "CSV"])
-    .creation_flags(CREATE_NO_WINDOW)
-    .output()
-  {
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    stdout.contains("Palworld-Win64-Shipping.exe")
-  } else {
-    false
+/// Format last_online ticks relative to current game ticks into human-readable text.
+fn format_last_seen(last_online_ticks: i64, current_ticks: u64) -> String {
+  if last_online_ticks <= 0 {
+    return "Unknown".to_string();
+  }
+  let diff_ticks = current_ticks as i64 - last_online_ticks;
+  if diff_ticks < 0 {
+    return "Online now".to_string();
+  }
+  // 1 tick = 100 nanoseconds = 0.0000001 seconds
+  let seconds = diff_ticks / 10_000_000;
+  if seconds < 60 {
+    return "Online now".to_string();
+  }
+  let minutes = seconds / 60;
+  if minutes < 60 {
+    return format!("{minutes} min ago");
+  }
+  let hours = minutes / 60;
+  if hours < 24 {
+    return format!("{hours}h ago");
   }
+  let days = hours / 24;
+  format!("{days}d ago")
 }
 
-#[tauri::command]
-fn rescan_storage() -> Result<(), String> {
-  Ok(())
+/// Modify a single player .sav file, swapping internal PlayerUId references.
+/// Read the InstanceId from a player .sav file (needed for InstanceId-based matching).
+fn read_player_instance_id(sav_path: &Path) -> Result<String, String> {
+  let data = fs::read(sav_path).map_err(|e| format!("read player sav: {e}"))?;
+  let (json, _) = gvas::sav_to_json(&data)?;
+  let inst = json
+    .pointer("/properties/SaveData/value/IndividualId/value/InstanceId/value")
+    .and_then(|v| v.as_str())
+    .unwrap_or("")
+    .to_string();
+  if inst.is_empty() {
+    return Err(format!("No InstanceId found in {:?}", sav_path));
+  }
+  Ok(inst)
 }
 
-// ── P2P Transfer helper commands ──────────────────────────
+/// Cross-reference every player file's internal ids with the player
+/// entries in Level.sav's CharacterSaveParameterMap (read-only).
+fn character_instance_map(world_path: &Path, players_dir: &Path) -> Result<Vec<CharMapping>, String> {
+  let data = fs::read(world_path.join("Level.sav")).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+  let (json, _) = gvas::sav_to_json(&data)?;
+  let characters: Vec<(String, String)> = character_entries(&json["properties"]["worldSaveData"]["value"])
+    .filter(|c| c.is_player())
+    .map(|c| (c.player_uid.to_string(), c.instance_id.to_string()))
+    .collect();
 
-/// Export a world to a temporary ZIP file for P2P sharing.
-/// Returns the full path to the temp ZIP.
+  let mut ids = list_player_ids(players_dir);
+  ids.sort();
+  Ok(
+    ids
+      .into_iter()
+      .map(|id| {
+        let info = match read_player_sav_info(&players_dir.join(format!("{id}.sav"))) {
+          Ok(info) => Some(info),
+          Err(e) => {
+            log_event(log::Level::Warn, &format!("instance map: {id}.sav: {e}"));
+            None
+          }
+        };
+        let file_uid = info.as_ref().map(|i| i.player_uid.clone());
+        let file_inst = info.and_then(|i| i.instance_id);
+        let same = |a: &str, b: &Option<String>| b.as_deref().is_some_and(|b| a.eq_ignore_ascii_case(b));
+        let by_inst = characters.iter().find(|(_, inst)| same(inst, &file_inst));
+        let entry = by_inst.or_else(|| characters.iter().find(|(uid, _)| same(uid, &file_uid)));
+        CharMapping {
+          filename: format!("{id}.sav"),
+          matched: by_inst.is_some_and(|(uid, _)| same(uid, &file_uid)),
+          cspm_player_uid: entry.map(|(uid, _)| uid.clone()),
+          cspm_instance_id: entry.map(|(_, inst)| inst.clone()),
+          file_player_uid: file_uid,
+          file_instance_id: file_inst,
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Every player file's PlayerUId/InstanceId against Level.sav, for
+/// diagnosing swaps.
 #[tauri::command]
-async fn export_world_to_temp(app: AppHandle, account_id: String, world_id: String) -> Result<String, String> {
-  let temp_path = std::env::temp_dir()
-    .join(format!("palhost_share_{}.zip", &world_id))
-    .to_string_lossy()
-    .to_string();
-  let tp = temp_path.clone();
-  let app2 = app.clone();
+async fn get_character_instance_map(account_id: String, world_id: String) -> Result<Vec<CharMapping>, String> {
   tauri::async_runtime::spawn_blocking(move || {
-    export_world_sync(&app2, &account_id, &world_id, &tp)
+    character_instance_map(&world_dir(&account_id, &world_id)?, &players_dir(&account_id, &world_id)?)
   })
   .await
   .map_err(|e| format!("Task error: {e}"))?
 }
 
-/// Get the file size in bytes.
-#[tauri::command]
-fn get_file_size(path: String) -> Result<u64, String> {
-  let meta = fs::metadata(&path).map_err(|e| format!("Cannot read: {e}"))?;
-  Ok(meta.len())
+/// Parse a player `.sav` and read its identity without modifying anything.
+fn read_player_sav_info(path: &Path) -> Result<PlayerSavInfo, String> {
+  let data = fs::read(path).map_err(|e| format!("Cannot read {:?}: {e}", path))?;
+  let (json, save_type) = gvas::sav_to_json(&data)?;
+  let save_data = json
+    .pointer("/properties/SaveData/value")
+    .ok_or("Not a player save: SaveData is missing.")?;
+  let player_uid = save_data
+    .pointer("/PlayerUId/value")
+    .and_then(|v| v.as_str())
+    .ok_or("Not a player save: SaveData/PlayerUId is missing.")?
+    .to_string();
+  let str_at = |ptr: &str| save_data.pointer(ptr).and_then(|v| v.as_str()).map(String::from);
+  Ok(PlayerSavInfo {
+    player_uid,
+    instance_id: str_at("/IndividualId/value/InstanceId/value"),
+    nick_name: str_at("/NickName/value"),
+    save_type,
+  })
 }
 
-/// Read a binary chunk from a file. Returns Vec<u8> → ArrayBuffer on JS side.
-#[tauri::command]
-fn read_file_chunk(path: String, offset: u64, length: u64) -> Result<Vec<u8>, String> {
-  let mut f = fs::File::open(&path).map_err(|e| format!("Cannot open: {e}"))?;
-  f.seek(std::io::SeekFrom::Start(offset)).map_err(|e| format!("Seek error: {e}"))?;
-  let mut buf = vec![0u8; length as usize];
-  let n = f.read(&mut buf).map_err(|e| format!("Read error: {e}"))?;
-  buf.truncate(n);
-  Ok(buf)
+/// Lowercase party and palbox container ids of a parsed player save, with
+/// their kind ("party" / "palbox").
+fn pal_container_ids(player_json: &Value) -> Vec<(String, &'static str)> {
+  [("OtomoCharacterContainerId", "party"), ("PalStorageContainerId", "palbox")]
+    .into_iter()
+    .filter_map(|(prop, kind)| {
+      let id = player_json
+        .pointer(&format!("/properties/SaveData/value/{prop}/value/ID/value"))
+        .and_then(|v| v.as_str())?;
+      Some((id.to_ascii_lowercase(), kind))
+    })
+    .collect()
 }
 
-/// Decode a base64 string and append it to a file (creates if needed).
-#[tauri::command]
-fn append_file_chunk_b64(path: String, data_b64: String) -> Result<(), String> {
-  let data = base64_decode(&data_b64)
-    .map_err(|_| "Invalid base64 data".to_string())?;
-  let mut f = fs::OpenOptions::new()
-    .create(true)
-    .append(true)
-    .open(&path)
-    .map_err(|e| format!("Cannot open: {e}"))?;
-  f.write_all(&data).map_err(|e| format!("Write error: {e}"))?;
-  Ok(())
+/// Read a player's party and palbox containers and resolve their occupied
+/// slots from Level.sav's CharacterContainerSaveData. Read-only.
+fn read_pal_containers(world_path: &Path, sav_path: &Path) -> Result<Vec<PalContainer>, String> {
+  let data = fs::read(sav_path).map_err(|e| format!("read player sav: {e}"))?;
+  let (player_json, _) = gvas::sav_to_json(&data)?;
+  let wanted = pal_container_ids(&player_json);
+  if wanted.is_empty() {
+    return Err(format!("No pal container IDs found in {:?}", sav_path));
+  }
+
+  let level = fs::read(world_path.join("Level.sav")).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+  let (level_json, _) = gvas::sav_to_json(&level)?;
+  let ccsd = level_json
+    .pointer("/properties/worldSaveData/value/CharacterContainerSaveData")
+    .ok_or("CharacterContainerSaveData not found in Level.sav.")?;
+  if ccsd.get("skip_type").is_some() {
+    return Err("CharacterContainerSaveData could not be decoded.".into());
+  }
+  let entries = ccsd["value"].as_array().cloned().unwrap_or_default();
+
+  let mut containers = Vec::new();
+  for (id, kind) in wanted {
+    let Some(entry) = entries.iter().find(|e| {
+      e.pointer("/key/ID/value")
+        .and_then(|v| v.as_str())
+        .is_some_and(|v| v.eq_ignore_ascii_case(&id))
+    }) else {
+      continue;
+    };
+    let slots = entry
+      .pointer("/value/Slots/value/values")
+      .and_then(|v| v.as_array())
+      .map(|slots| {
+        slots
+          .iter()
+          .enumerate()
+          .filter_map(|(i, slot)| {
+            let raw: Vec<u8> = slot
+              .pointer("/RawData/value/values")?
+              .as_array()?
+              .iter()
+              .map(|b| b.as_u64().unwrap_or(0) as u8)
+              .collect();
+            let (_, instance_id) = gvas::decode_container_slot_rawdata(&raw)?;
+            if instance_id == "00000000-0000-0000-0000-000000000000" {
+              return None;
+            }
+            let slot_index = slot
+              .pointer("/SlotIndex/value")
+              .and_then(|v| v.as_u64())
+              .unwrap_or(i as u64) as u32;
+            Some(PalContainerSlot { slot_index, instance_id })
+          })
+          .collect()
+      })
+      .unwrap_or_default();
+    containers.push(PalContainer { container_id: id, kind: kind.to_string(), slots });
+  }
+  Ok(containers)
 }
 
-/// Get a path in the system temp directory for receiving P2P files.
-#[tauri::command]
-fn get_temp_path(filename: String) -> String {
-  std::env::temp_dir()
-    .join(&filename)
-    .to_string_lossy()
-    .to_string()
+/// NickName of the CharacterSaveParameterMap entry with the given InstanceId.
+fn character_nick_name(level_json: &Value, instance_id: &str) -> Option<String> {
+  level_json
+    .pointer("/properties/worldSaveData/value/CharacterSaveParameterMap/value")?
+    .as_array()?
+    .iter()
+    .find(|e| {
+      e.pointer("/key/InstanceId/value")
+        .and_then(|v| v.as_str())
+        .is_some_and(|id| id.eq_ignore_ascii_case(instance_id))
+    })?
+    .pointer("/value/RawData/value/object/SaveParameter/value/NickName/value")
+    .and_then(|v| v.as_str())
+    .map(String::from)
 }
 
-/// Delete a temporary file.
-#[tauri::command]
-fn delete_temp_file(path: String) -> Result<(), String> {
-  let p = Path::new(&path);
-  if p.exists() {
-    if p.is_dir() {
-      fs::remove_dir_all(p).map_err(|e| format!("Cannot delete: {e}"))?;
-    } else {
-      fs::remove_file(p).map_err(|e| format!("Cannot delete: {e}"))?;
+/// Find the InstanceId of a player's own character entry in Level.sav's
+/// CharacterSaveParameterMap (matched by key PlayerUId + `IsPlayer`).
+fn find_player_instance_id(level_json: &Value, player_uuid: &str) -> Option<String> {
+  let entries = level_json
+    .pointer("/properties/worldSaveData/value/CharacterSaveParameterMap/value")
+    .and_then(|v| v.as_array())?;
+  entries.iter().find_map(|entry| {
+    let puid = entry.pointer("/key/PlayerUId/value").and_then(|v| v.as_str())?;
+    if !puid.eq_ignore_ascii_case(player_uuid) {
+      return None;
+    }
+    let is_player = entry
+      .pointer("/value/RawData/value/object/SaveParameter/value/IsPlayer/value")
+      .and_then(|v| v.as_bool())
+      .unwrap_or(false);
+    if !is_player {
+      return None;
     }
+    entry
+      .pointer("/key/InstanceId/value")
+      .and_then(|v| v.as_str())
+      .map(|s| s.to_string())
+  })
+}
+
+/// Build the JSON for a minimal player save: just `SaveData.PlayerUId` and
+/// `SaveData.IndividualId` (PlayerUId + InstanceId). The header is borrowed
+/// from Level.sav so engine/custom versions match the world.
+fn build_player_sav_json(level_header: &Value, player_uuid: &str, instance_id: &str) -> Value {
+  const ZERO_GUID: &str = "00000000-0000-0000-0000-000000000000";
+  let guid = |v: &str| {
+    serde_json::json!({
+      "struct_type": "Guid",
+      "struct_id": ZERO_GUID,
+      "id": null,
+      "value": v,
+      "type": "StructProperty"
+    })
+  };
+  let mut header = level_header.clone();
+  header["save_game_class_name"] = Value::String("/Script/Pal.PalWorldPlayerSaveGame".into());
+  serde_json::json!({
+    "header": header,
+    "properties": {
+      "SaveData": {
+        "struct_type": "PalWorldPlayerSaveData",
+        "struct_id": ZERO_GUID,
+        "id": null,
+        "value": {
+          "PlayerUId": guid(player_uuid),
+          "IndividualId": {
+            "struct_type": "PalInstanceID",
+            "struct_id": ZERO_GUID,
+            "id": null,
+            "value": {
+              "PlayerUId": guid(player_uuid),
+              "InstanceId": guid(instance_id)
+            },
+            "type": "StructProperty"
+          }
+        },
+        "type": "StructProperty"
+      }
+    },
+    "trailer": "AAAAAA=="
+  })
+}
+
+/// Recreate a deleted player `.sav` from the character entry still present in
+/// Level.sav, so a swap involving that player can proceed.
+fn reconstruct_player_sav(world_path: &Path, sav_path: &Path, player_uuid: &str) -> Result<(), String> {
+  let level_sav = world_path.join("Level.sav");
+  if !level_sav.exists() {
+    return Err("Level.sav not found.".into());
   }
+  let data = fs::read(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+  let (level_json, save_type) = gvas::sav_to_json(&data)?;
+  let instance_id = find_player_instance_id(&level_json, player_uuid)
+    .ok_or_else(|| format!("No character for {player_uuid} in Level.sav, cannot reconstruct its .sav."))?;
+  let json = build_player_sav_json(&level_json["header"], player_uuid, &instance_id);
+  let sav_bytes = gvas::json_to_sav(&json, save_type)?;
+  fs::write(sav_path, &sav_bytes).map_err(|e| format!("write player sav: {e}"))?;
+  log_event(log::Level::Info, &format!("reconstructed missing {:?} from Level.sav", sav_path));
   Ok(())
 }
 
-/// Extract a ZIP file to a temp directory and return the extracted folder path.
-#[tauri::command]
-fn extract_zip_to_temp(zip_path: String) -> Result<String, String> {
-  let zip_file = fs::File::open(&zip_path)
-    .map_err(|e| format!("Cannot open ZIP: {e}"))?;
-  let mut archive = zip::ZipArchive::new(zip_file)
-    .map_err(|e| format!("Invalid ZIP: {e}"))?;
+fn modify_player_sav(sav_path: &Path, old_uid: &str, new_uid: &str) -> Result<(), String> {
+  let data = fs::read(sav_path).map_err(|e| format!("read player sav: {e}"))?;
+  let (mut json, save_type) = gvas::sav_to_json(&data)?;
 
-  let extract_dir = std::env::temp_dir().join("palhost_p2p_extract");
-  // Clean previous extraction
-  if extract_dir.exists() {
-    let _ = fs::remove_dir_all(&extract_dir);
+  // Update PlayerUId
+  if let Some(puid) = json.pointer_mut("/properties/SaveData/value/PlayerUId/value") {
+    if puid.as_str() == Some(old_uid) {
+      *puid = Value::String(new_uid.to_string());
+    }
+  }
+  // Update IndividualId → PlayerUId
+  if let Some(iid) = json.pointer_mut("/properties/SaveData/value/IndividualId/value/PlayerUId/value") {
+    if iid.as_str() == Some(old_uid) {
+      *iid = Value::String(new_uid.to_string());
+    }
   }
-  fs::create_dir_all(&extract_dir)
-    .map_err(|e| format!("Cannot create temp dir: {e}"))?;
 
-  for i in 0..archive.len() {
-    let mut file = archive.by_index(i)
-      .map_err(|e| format!("ZIP read error: {e}"))?;
-    let out_path = extract_dir.join(file.mangled_name());
+  let sav_bytes = gvas::json_to_sav(&json, save_type)?;
+  fs::write(sav_path, &sav_bytes).map_err(|e| format!("write player sav: {e}"))?;
+  Ok(())
+}
 
-    if file.is_dir() {
-      fs::create_dir_all(&out_path)
-        .map_err(|e| format!("Cannot create dir: {e}"))?;
-    } else {
-      if let Some(parent) = out_path.parent() {
-        fs::create_dir_all(parent)
-          .map_err(|e| format!("Cannot create parent: {e}"))?;
+fn build_players(
+  player_ids: &[String],
+  host_id: &str,
+  level_info: &[LevelPlayerInfo],
+) -> Vec<Player> {
+  player_ids
+    .iter()
+    .map(|id| {
+      // Find matching info from Level.sav
+      let info = level_info.iter().find(|li| li.filename == *id);
+      let name = info.map(|i| i.name.clone()).unwrap_or_else(|| id.clone());
+      let level = info.map(|i| i.level).unwrap_or(0);
+      let pals_count = info.map(|i| i.pals_count).unwrap_or(0);
+      let last_online = info.map(|i| i.last_online.clone()).unwrap_or_default();
+      let last_online_ticks = info.map(|i| i.last_online_ticks).unwrap_or(0);
+      let guild_name = info.map(|i| i.guild_name.clone()).unwrap_or_default();
+      let instance_id = info.map(|i| i.instance_id.clone()).unwrap_or_default();
+      let group_id = info.and_then(|i| i.group_id.clone());
+      Player {
+        id: id.clone(),
+        name,
+        original_id: id.clone(),
+        is_host: id == host_id,
+        level,
+        pals_count,
+        last_online,
+        guild_name,
+        host_fallback: id == host_id && !is_host_slot(id),
+        instance_id,
+        group_id,
+        id_mismatch: false,
+        last_online_ticks,
       }
-      let mut out_file = fs::File::create(&out_path)
-        .map_err(|e| format!("Cannot create file: {e}"))?;
-      std::io::copy(&mut file, &mut out_file)
-        .map_err(|e| format!("Extract error: {e}"))?;
-    }
-  }
+    })
+    .collect()
+}
 
-  // Find the world folder inside (should be the first directory)
-  let mut world_folder = extract_dir.clone();
-  if let Ok(entries) = fs::read_dir(&extract_dir) {
-    for entry in entries.flatten() {
-      if entry.path().is_dir() {
-        world_folder = entry.path();
-        break;
+/// True when a player file's internal PlayerUId differs from the UUID its
+/// filename implies. Unreadable files aren't flagged here.
+fn player_id_mismatch(sav_path: &Path, player_id: &str) -> bool {
+  cached_player_uid(sav_path)
+    .map(|uid| !uid.eq_ignore_ascii_case(&filename_to_uuid(&normalize_id(player_id))))
+    .unwrap_or(false)
+}
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+  let meta = fs::metadata(path).ok()?;
+  Some((meta.modified().ok()?, meta.len()))
+}
+
+/// `read_player_sav_info(..).player_uid`, served from `PLAYER_UID_CACHE`
+/// while the file is unchanged.
+fn cached_player_uid(sav_path: &Path) -> Result<String, String> {
+  let stamp = file_stamp(sav_path);
+  if let (Some(stamp), Ok(cache)) = (stamp, PLAYER_UID_CACHE.lock()) {
+    if let Some((cached, uid)) = cache.get(sav_path) {
+      if *cached == stamp {
+        return Ok(uid.clone());
       }
     }
   }
+  let uid = read_player_sav_info(sav_path)?.player_uid;
+  if let (Some(stamp), Ok(mut cache)) = (stamp, PLAYER_UID_CACHE.lock()) {
+    cache.insert(sav_path.to_path_buf(), (stamp, uid.clone()));
+  }
+  Ok(uid)
+}
 
-  Ok(world_folder.to_string_lossy().to_string())
+/// Rewrite a player file's internal PlayerUId to match its filename.
+/// Returns false if it already matched.
+fn resync_player_file(sav_path: &Path, player_id: &str) -> Result<bool, String> {
+  let info = read_player_sav_info(sav_path)?;
+  let expected = filename_to_uuid(&normalize_id(player_id));
+  if info.player_uid.eq_ignore_ascii_case(&expected) {
+    return Ok(false);
+  }
+  modify_player_sav(sav_path, &info.player_uid, &expected)?;
+  log_event(log::Level::Info, &format!("resynced {:?}: {} -> {expected}", sav_path, info.player_uid));
+  Ok(true)
 }
 
-/// Simple base64 decoder (no extra crate needed).
-fn base64_decode(input: &str) -> Result<Vec<u8>, ()> {
-  let table: [u8; 128] = {
-    let mut t = [255u8; 128];
-    for (i, &c) in b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/".iter().enumerate() {
-      t[c as usize] = i as u8;
+/// Point both `SaveData.PlayerUId` and `SaveData.IndividualId.PlayerUId` of
+/// a parsed player save at `expected`, whatever they held. Returns the
+/// values that were replaced (empty when both already matched).
+fn repair_player_ids_in_json(json: &mut Value, expected: &str) -> Result<Vec<String>, String> {
+  let mut replaced = Vec::new();
+  for ptr in ["/properties/SaveData/value/PlayerUId/value", "/properties/SaveData/value/IndividualId/value/PlayerUId/value"] {
+    let field = json.pointer_mut(ptr).ok_or_else(|| format!("Not a player save: {ptr} is missing."))?;
+    let current = field.as_str().unwrap_or("").to_string();
+    if !current.eq_ignore_ascii_case(expected) {
+      *field = Value::String(expected.to_string());
+      replaced.push(current);
     }
-    t
-  };
-  let input = input.as_bytes();
-  let mut out = Vec::with_capacity(input.len() * 3 / 4);
-  let mut buf = 0u32;
-  let mut bits = 0u32;
-  for &b in input {
-    if b == b'=' || b == b'\n' || b == b'\r' || b == b' ' { continue; }
-    let val = if (b as usize) < 128 { table[b as usize] } else { 255 };
-    if val == 255 { return Err(()); }
-    buf = (buf << 6) | val as u32;
-    bits += 6;
-    if bits >= 8 {
-      bits -= 8;
-      out.push((buf >> bits) as u8);
-      buf &= (1 << bits) - 1;
+  }
+  Ok(replaced)
+}
+
+/// Optional behaviour for `swap_players_full`.
+#[derive(Default)]
+struct SwapOptions<'a> {
+  /// Rebuild a player's deleted `.sav` from their character in Level.sav.
+  reconstruct_missing: bool,
+  /// Back up both players and Level.sav once the swap is known to happen.
+  auto_backup: bool,
+  /// Emit swap-progress events as (app, base%, range%).
+  progress: Option<(&'a AppHandle, f64, f64)>,
+  /// Print progress to stderr instead (headless CLI).
+  print_progress: bool,
+  /// After a host change, hand the old host's base camps to the new host
+  /// through their guild's admin. Ignored unless `first_id` is the host slot.
+  fix_base_camps: bool,
+  /// Where auto-backups go; `Players/backup` when unset.
+  backup_root: Option<&'a Path>,
+  match_strategy: MatchStrategy,
+  /// Checked during the deep swap; once set the swap stops and is rolled back.
+  cancel: Option<&'a AtomicBool>,
+  /// Nodes between deep-swap progress reports and `cancel` checks;
+  /// `gvas::DEEP_SWAP_REPORT_EVERY` when unset.
+  deep_swap_report_every: Option<usize>,
+}
+
+/// Cancel flags of the swaps in progress, keyed by world folder. Each swap
+/// registers a fresh flag for its duration (see `SwapCancels::register`), so
+/// `cancel_swap` only ever stops the swap in the world it names.
+#[derive(Default, Clone)]
+struct SwapCancels(std::sync::Arc<Mutex<HashMap<PathBuf, std::sync::Arc<AtomicBool>>>>);
+
+/// A swap's cancel flag; unregisters it on drop.
+struct SwapCancelToken {
+  cancels: SwapCancels,
+  world_path: PathBuf,
+  flag: std::sync::Arc<AtomicBool>,
+}
+
+impl SwapCancels {
+  fn register(&self, world_path: &Path) -> SwapCancelToken {
+    let flag = std::sync::Arc::new(AtomicBool::new(false));
+    if let Ok(mut running) = self.0.lock() {
+      running.insert(world_path.to_path_buf(), flag.clone());
     }
+    SwapCancelToken { cancels: self.clone(), world_path: world_path.to_path_buf(), flag }
+  }
+
+  /// Flag the swap running in `world_path`; false if there is none.
+  fn cancel(&self, world_path: &Path) -> bool {
+    let Ok(running) = self.0.lock() else { return false };
+    running.get(world_path).map(|flag| flag.store(true, Ordering::Relaxed)).is_some()
   }
-  Ok(out)
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-  tauri::Builder::default()
-    .setup(|app| {
-      if cfg!(debug_assertions) {
-        app.handle().plugin(
-          tauri_plugin_log::Builder::default()
-            .level(log::LevelFilter::Info)
-            .filter(|metadata| {
-              // Suppress noisy tao event-loop warnings on Windows
-              !metadata.target().starts_with("tao::")
-            })
-            .build(),
-        )?;
+impl Drop for SwapCancelToken {
+  fn drop(&mut self) {
+    if let Ok(mut running) = self.cancels.0.lock() {
+      if running.get(&self.world_path).is_some_and(|f| std::sync::Arc::ptr_eq(f, &self.flag)) {
+        running.remove(&self.world_path);
       }
-      app.handle().plugin(tauri_plugin_dialog::init())?;
-      // Migrate old app-level config data into per-world files
-      let _ = migrate_legacy_config(app.handle());
-      Ok(())
-    })
-    .invoke_handler(tauri::generate_handler![
-      get_accounts,
-      get_worlds,
-      get_worlds_with_counts,
-      get_players,
-      set_host_player,
-      swap_players,
-      create_backup,
-      list_backups,
-      restore_backup,
-      delete_backup,
-      delete_all_backups,
-      export_world,
-      validate_world_folder,
-      check_world_exists,
-      import_world,
-      set_world_name,
-      reset_world_name,
-      is_palworld_running,
-      rescan_storage,
-      export_world_to_temp,
-      get_file_size,
-      read_file_chunk,
-      append_file_chunk_b64,
-      get_temp_path,
-      delete_temp_file,
-      extract_zip_to_temp,
-    ])
-    .run(tauri::generate_context!())
-    .expect("error while running tauri application");
+    }
+  }
 }
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use std::path::Path;
+/// Returned when a swap was stopped through `cancel_swap`.
+const SWAP_CANCELLED: &str = "Swap cancelled.";
+
+/// Ask the swap running in a world to stop. Only the deep-swap phase checks
+/// this; the swap is rolled back and fails with "Swap cancelled.". Returns
+/// false if no swap is running there.
+#[tauri::command]
+fn cancel_swap(app: AppHandle, account_id: String, world_id: String) -> Result<bool, String> {
+  Ok(app.state::<SwapCancels>().cancel(&world_dir(&account_id, &world_id)?))
+}
+
+/// Swap .sav files + modify Level.sav with GVAS-based UID swap.
+/// Follows PalworldSaveTools fix_host_save logic:
+///   1. Read InstanceIds from both player .sav files
+///   2. Patch PlayerUId inside both player .sav files
+///   3. In Level.sav CharacterSaveParameterMap: swap PlayerUId only for the
+///      two entries matching by InstanceId (not all entries!)
+///   4. In Level.sav GroupSaveDataMap: swap admin, player_uid, and
+///      individual_character_handle_ids.guid matched by instance_id
+///   5. Deep-swap OwnerPlayerUId/build_player_uid/etc across all Level.sav
+///   6. Serialize Level.sav and write all files
+///   7. Rename .sav files (swap filenames)
+///
+/// See `SwapOptions` for reconstruction, auto-backup and progress reporting.
+fn swap_players_full(
+  world_path: &Path,
+  players_dir: &Path,
+  first_id: &str,
+  second_id: &str,
+  opts: &SwapOptions,
+) -> Result<(), String> {
+  // progress helper: emit (base + fraction * range)
+  let emit = |frac: f64, msg: &str| {
+    if let Some((app, base, range)) = &opts.progress {
+      let _ = app.emit("swap-progress", ProgressPayload {
+        percent: base + frac * range,
+        message: msg.to_string(),
+      });
+    } else if opts.print_progress {
+      eprintln!("[{:>3.0}%] {msg}", frac * 100.0);
+    }
+  };
+
+  let first = normalize_id(first_id);
+  let second = normalize_id(second_id);
+  if first == second {
+    return Err("Cannot swap a player with themselves".into());
+  }
+
+  let first_sav = players_dir.join(format!("{first}.sav"));
+  let second_sav = players_dir.join(format!("{second}.sav"));
+  let missing = |id: &str| player_file_variants(players_dir, id).is_empty();
+  if (missing(&first) || missing(&second)) && !opts.reconstruct_missing {
+    return Err("Missing .sav files for swap.".to_string());
+  }
+
+  // Read the config before reconstruction changes the roster
+  let mut wc = load_world_config(players_dir);
+
+  // The swap is going ahead: back up before the first write
+  let backup = if opts.auto_backup {
+    emit(0.0, "Backing up…");
+    let default_root = players_dir.join("backup");
+    let root = opts.backup_root.unwrap_or(&default_root);
+    Some(backup_files(players_dir, world_path, root, &[first.clone(), second.clone()], &backup_snapshot(&wc, "pre-swap"), false)?)
+  } else {
+    None
+  };
+  // Only after the backup, which keeps the case variants under their own names
+  let mut renames = Vec::new();
+  for id in [&first, &second] {
+    renames.extend(canonicalize_player_file(players_dir, id)?);
+  }
+
+  let uuid_first = filename_to_uuid(&first);
+  let uuid_second = filename_to_uuid(&second);
+
+  let mut reconstructed = Vec::new();
+  for (sav, uuid) in [(&first_sav, &uuid_first), (&second_sav, &uuid_second)] {
+    if !sav.exists() {
+      emit(0.0, "Reconstructing missing player save…");
+      reconstruct_player_sav(world_path, sav, uuid)?;
+      reconstructed.push(sav);
+    }
+  }
+
+  // ── 0. Read InstanceIds from player .sav files (needed for CSPM / guild matching) ──
+  emit(0.0, "Reading player saves…");
+  // Without InstanceId matching a missing InstanceId isn't fatal
+  let read_inst = |sav: &Path| match read_player_instance_id(sav) {
+    Err(e) if opts.match_strategy != MatchStrategy::InstanceId => {
+      log_event(log::Level::Warn, &format!("{e}; matching by PlayerUId"));
+      Ok(String::new())
+    }
+    other => other,
+  };
+  let inst_first = read_inst(&first_sav)?;
+  let inst_second = read_inst(&second_sav)?;
+
+  // ── 1. Modify player .sav files (patch PlayerUId + IndividualId.PlayerUId) ──
+  emit(0.05, "Patching player saves…");
+  if let Err(e) = modify_player_sav(&first_sav, &uuid_first, &uuid_second) {
+    log_event(log::Level::Warn, &format!("could not modify {first}.sav internals: {e}"));
+  }
+  if let Err(e) = modify_player_sav(&second_sav, &uuid_second, &uuid_first) {
+    log_event(log::Level::Warn, &format!("could not modify {second}.sav internals: {e}"));
+  }
+
+  let level = match swap_uids_in_level(world_path, &uuid_first, &uuid_second, &inst_first, &inst_second, opts, &emit) {
+    Ok(level) => level,
+    Err(e) => {
+      // Cancelled or failed: put the two player files back so they never
+      // disagree with Level.sav
+      emit(0.0, if e == SWAP_CANCELLED { "Cancelling…" } else { "Rolling back…" });
+      let rollback = || -> Result<(), String> {
+        match &backup {
+          Some(dir) => {
+            undo_renames(&renames)?;
+            restore_backup_files(dir, players_dir, world_path, true)?;
+          }
+          None => {
+            modify_player_sav(&first_sav, &uuid_second, &uuid_first)?;
+            modify_player_sav(&second_sav, &uuid_first, &uuid_second)?;
+            undo_renames(&renames)?;
+          }
+        }
+        // Files rebuilt for this swap weren't there before it
+        for sav in &reconstructed {
+          fs::remove_file(sav).map_err(|e| format!("Cannot remove reconstructed {:?}: {e}", sav))?;
+        }
+        Ok(())
+      };
+      if let Err(undo) = rollback() {
+        return Err(format!("{e} Rolling back the player saves also failed: {undo}"));
+      }
+      log_event(log::Level::Info, &format!("swap {first} <-> {second} rolled back: {e}"));
+      return Err(e);
+    }
+  };
+  if level.matched_by == MatchStrategy::PlayerUId {
+    log_event(log::Level::Warn, &format!("swap {first} <-> {second}: characters matched by PlayerUId, not InstanceId"));
+  }
+  log_event(
+    log::Level::Info,
+    &format!("deep swap {first} <-> {second}: {} ownership fields rewritten", level.ownership_fields_swapped),
+  );
+
+  // ── 7. Rename .sav files (swap filenames) ──
+  emit(0.96, "Renaming files…");
+  let stamp = std::time::SystemTime::now()
+    .duration_since(std::time::UNIX_EPOCH)
+    .map_err(|err| err.to_string())?
+    .as_millis();
+  let temp = players_dir.join(format!("swap-{stamp}.tmp"));
+  fs::rename(&first_sav, &temp).map_err(|err| err.to_string())?;
+  fs::rename(&second_sav, &first_sav).map_err(|err| err.to_string())?;
+  fs::rename(&temp, &second_sav).map_err(|err| err.to_string())?;
+
+  // ── 8. Record the swap in the world's history ──
+  wc.swap_history.push(SwapRecord {
+    timestamp: chrono::Local::now().to_rfc3339(),
+    first_id: first.clone(),
+    second_id: second.clone(),
+    first_name: level.first_name,
+    second_name: level.second_name,
+    ownership_fields_swapped: level.ownership_fields_swapped,
+    match_strategy: level.matched_by,
+  });
+  trim_swap_history(&mut wc);
+  if let Err(e) = save_world_config(players_dir, &wc) {
+    log_event(log::Level::Warn, &format!("could not record swap history: {e}"));
+  }
+
+  let fallback_note = if level.matched_by == MatchStrategy::PlayerUId { ", matched by PlayerUId" } else { "" };
+  emit(
+    1.0,
+    &format!("Swap complete ({} ownership fields updated{fallback_note}).", level.ownership_fields_swapped),
+  );
+  Ok(())
+}
+
+/// Steps 2-6 of the swap: move `uuid_first` ↔ `uuid_second` through Level.sav.
+/// With an empty `inst_second` (no character for `uuid_second`) this is a
+/// one-way move.
+fn swap_uids_in_level(
+  world_path: &Path,
+  uuid_first: &str,
+  uuid_second: &str,
+  inst_first: &str,
+  inst_second: &str,
+  opts: &SwapOptions,
+  emit: &dyn Fn(f64, &str),
+) -> Result<LevelSwap, String> {
+  // ── 2. Level.sav: read ──
+  emit(0.10, "Reading Level.sav…");
+  let level_sav = world_path.join("Level.sav");
+  if !level_sav.exists() {
+    return Err("Level.sav not found.".into());
+  }
+  let data = fs::read(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+
+  // ── 3. Level.sav: parse ──
+  emit(0.15, "Parsing Level.sav…");
+  let (mut json, save_type) = gvas::sav_to_json(&data)?;
+
+  // Names for the swap history, read before the UIDs move around
+  let first_name = character_nick_name(&json, inst_first);
+  let second_name = character_nick_name(&json, inst_second);
+
+  let matched_by = resolve_match_strategy(&json, opts.match_strategy, inst_first, inst_second);
+  let by_uid = matched_by == MatchStrategy::PlayerUId;
+  // New PlayerUId for a character entry / handle, if it's one of the two players
+  let swap_to = |inst: &str, uid: &str| -> Option<String> {
+    let (first_hit, second_hit) = if by_uid {
+      (uid.eq_ignore_ascii_case(uuid_first), uid.eq_ignore_ascii_case(uuid_second))
+    } else {
+      (!inst.is_empty() && inst == inst_first, !inst_second.is_empty() && inst == inst_second)
+    };
+    if first_hit {
+      Some(uuid_second.to_string())
+    } else if second_hit {
+      Some(uuid_first.to_string())
+    } else {
+      None
+    }
+  };
+
+  // ── 4. Level.sav: modify UIDs ──
+  emit(0.40, "Swapping UIDs in Level.sav…");
+  let ownership_fields_swapped = {
+    let world_data = json
+      .get_mut("properties")
+      .and_then(|p| p.get_mut("worldSaveData"))
+      .and_then(|w| w.get_mut("value"))
+      .ok_or("Cannot navigate to worldSaveData")?;
+
+    // 4a. CharacterSaveParameterMap: swap PlayerUId ONLY for the two entries
+    //     that match (by InstanceId unless falling back to PlayerUId): the
+    //     players' own character entries. All other entries are left untouched.
+    if let Some(cspm) = world_data.get_mut("CharacterSaveParameterMap") {
+      if let Some(entries) = cspm.get_mut("value").and_then(|v| v.as_array_mut()) {
+        for entry in entries.iter_mut() {
+          if let Some(key) = entry.get_mut("key") {
+            let str_at = |ptr: &str| key.pointer(ptr).and_then(|v| v.as_str()).unwrap_or("").to_string();
+            if let Some(new_uid) = swap_to(&str_at("/InstanceId/value"), &str_at("/PlayerUId/value")) {
+              if let Some(puid) = key.pointer_mut("/PlayerUId/value") {
+                *puid = Value::String(new_uid);
+              }
+            }
+          }
+        }
+      }
+    }
+
+    // 4b. GroupSaveDataMap: swap admin_player_uid, player_uid in member list,
+    //     and individual_character_handle_ids.guid matched by instance_id.
+    if let Some(gsm) = world_data.get_mut("GroupSaveDataMap") {
+      if let Some(entries) = gsm.get_mut("value").and_then(|v| v.as_array_mut()) {
+        for entry in entries.iter_mut() {
+          // Only process guilds
+          let is_guild = entry
+            .pointer("/value/GroupType/value/value")
+            .and_then(|v| v.as_str())
+            == Some("EPalGroupType::Guild");
+          if !is_guild {
+            continue;
+          }
+
+          let raw_data = entry.pointer_mut("/value/RawData/value");
+          if let Some(rd) = raw_data {
+            // Swap admin_player_uid
+            if let Some(admin) = rd.get_mut("admin_player_uid") {
+              if let Some(s) = admin.as_str().map(|s| s.to_string()) {
+                if s == uuid_first {
+                  *admin = Value::String(uuid_second.to_string());
+                } else if s == uuid_second {
+                  *admin = Value::String(uuid_first.to_string());
+                }
+              }
+            }
+
+            // Swap player_uid in players list
+            if let Some(players) = rd.get_mut("players").and_then(|p| p.as_array_mut()) {
+              for p in players.iter_mut() {
+                if let Some(puid) = p.get_mut("player_uid") {
+                  if let Some(s) = puid.as_str().map(|s| s.to_string()) {
+                    if s == uuid_first {
+                      *puid = Value::String(uuid_second.to_string());
+                    } else if s == uuid_second {
+                      *puid = Value::String(uuid_first.to_string());
+                    }
+                  }
+                }
+              }
+            }
+
+            // Swap guid in individual_character_handle_ids — matched like 4a
+            if let Some(handles) = rd.get_mut("individual_character_handle_ids").and_then(|h| h.as_array_mut()) {
+              for h in handles.iter_mut() {
+                let str_at = |field: &str| h.get(field).and_then(|v| v.as_str()).unwrap_or("").to_string();
+                if let Some(new_uid) = swap_to(&str_at("instance_id"), &str_at("guid")) {
+                  if let Some(guid) = h.get_mut("guid") {
+                    *guid = Value::String(new_uid);
+                  }
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+
+    // 4c. Deep-swap ownership UIDs (OwnerPlayerUId, build_player_uid, etc.)
+    //     across the entire worldSaveData. This is the same as PalworldSaveTools'
+    //     deep_swap() function applied to the full Level.sav.
+    let progress = |visited: usize, total: usize| {
+      emit(0.40 + 0.10 * visited as f64 / total.max(1) as f64, "Swapping ownership UIDs…");
+    };
+    let swapped = gvas::deep_swap_uids(world_data, uuid_first, uuid_second, Some(&progress), opts.cancel, opts.deep_swap_report_every)
+      .map_err(|_| SWAP_CANCELLED.to_string())?;
+
+    // 4d. Camps belong to a guild, whose admin 4b kept with the old host's
+    //     character, which now carries uuid_second. Only a host change
+    //     moves camps; a guild the new host isn't in keeps its admin.
+    if opts.fix_base_camps && is_host_slot(&uuid_to_filename(uuid_first)) {
+      let (camps, skipped) = hand_base_camps_to(world_data, uuid_second, uuid_first);
+      log_event(log::Level::Info, &format!("{camps} base camp(s) handed to the new host"));
+      for guild in skipped {
+        log_event(
+          log::Level::Warn,
+          &format!("new host is not a member of guild {guild}; its base camps stay with the old host"),
+        );
+      }
+    }
+    swapped
+  };
+
+  if opts.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+    return Err(SWAP_CANCELLED.into());
+  }
+
+  // ── 5. Level.sav: serialize ──
+  emit(0.50, "Serializing Level.sav…");
+  let sav_bytes = gvas::json_to_sav(&json, save_type)?;
+
+  // ── 6. Level.sav: write ──
+  emit(0.75, "Writing Level.sav…");
+  fs::write(&level_sav, &sav_bytes).map_err(|e| format!("Cannot write Level.sav: {e}"))?;
+
+  Ok(LevelSwap { first_name, second_name, ownership_fields_swapped, matched_by })
+}
+
+/// The guild of every base camp, one entry per camp. Read from the camps'
+/// `group_id_belong_to`; when BaseCampSaveData doesn't decode, each guild's
+/// own `base_ids` list stands in for it.
+fn base_camp_guilds(world_data: &Value) -> Vec<String> {
+  let Some(camps) = world_data.get("BaseCampSaveData") else {
+    return Vec::new();
+  };
+  match gvas::base_camp_group_ids(camps) {
+    Ok(ids) => ids,
+    Err(e) => {
+      log_event(log::Level::Warn, &format!("{e}; counting camps from the guilds' base_ids"));
+      guilds_from_world_data(world_data)
+        .into_iter()
+        .filter(|g| g.group_type == "Guild")
+        .flat_map(|g| std::iter::repeat(g.id).take(g.base_count))
+        .collect()
+    }
+  }
+}
+
+/// Number of base camps owned by guilds that `uid` is the admin of.
+fn base_camps_run_by(world_data: &Value, uid: &str) -> usize {
+  let camps = base_camp_guilds(world_data);
+  world_data["GroupSaveDataMap"]["value"]
+    .as_array()
+    .into_iter()
+    .flatten()
+    .filter(|g| g["value"]["RawData"]["value"]["admin_player_uid"].as_str().is_some_and(|a| a.eq_ignore_ascii_case(uid)))
+    .filter_map(|g| g["key"].as_str())
+    .map(|key| camps.iter().filter(|c| c.eq_ignore_ascii_case(key)).count())
+    .sum()
+}
+
+/// Make `to` the admin of every guild that owns a base camp and is run by
+/// `from`. A camp belongs to its guild (`group_id_belong_to`), not to a
+/// player, so this is how camps change hands. Guilds `to` isn't a member of
+/// are left alone. Returns how many camps changed hands and the guilds that
+/// were skipped.
+fn hand_base_camps_to(world_data: &mut Value, from: &str, to: &str) -> (usize, Vec<String>) {
+  let camps = base_camp_guilds(world_data);
+  let Some(groups) = world_data.pointer_mut("/GroupSaveDataMap/value").and_then(|v| v.as_array_mut()) else {
+    return (0, Vec::new());
+  };
+  let mut handed = 0;
+  let mut skipped = Vec::new();
+  for group in groups {
+    if group.pointer("/value/GroupType/value/value").and_then(|v| v.as_str()) != Some("EPalGroupType::Guild") {
+      continue;
+    }
+    let key = group["key"].as_str().unwrap_or("").to_string();
+    let owned = camps.iter().filter(|c| c.eq_ignore_ascii_case(&key)).count();
+    let Some(rd) = group.pointer_mut("/value/RawData/value") else { continue };
+    if owned == 0 || !rd["admin_player_uid"].as_str().is_some_and(|a| a.eq_ignore_ascii_case(from)) {
+      continue;
+    }
+    let is_member = rd["players"]
+      .as_array()
+      .into_iter()
+      .flatten()
+      .any(|p| p["player_uid"].as_str().is_some_and(|u| u.eq_ignore_ascii_case(to)));
+    if !is_member {
+      skipped.push(key);
+      continue;
+    }
+    rd["admin_player_uid"] = Value::String(to.to_string());
+    handed += owned;
+  }
+  (handed, skipped)
+}
+
+/// Hand `from_id`'s base camps to `to_id`: every guild that owns a camp and
+/// is run by `from_id` gets `to_id` as its admin. `to_id` must already be a
+/// member of those guilds. Backs up and re-parses Level.sav before writing;
+/// nothing is written when `from_id` runs no camp. Returns how many camps
+/// changed hands.
+#[tauri::command]
+async fn fix_base_camp_ownership(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  from_id: String,
+  to_id: String,
+) -> Result<usize, String> {
+  let from = player_id_to_uuid(&normalize_id(&from_id).replace('-', ""))?;
+  let to = player_id_to_uuid(&normalize_id(&to_id).replace('-', ""))?;
+  if from == to {
+    return Err("Source and target are the same player.".into());
+  }
+  tauri::async_runtime::spawn_blocking(move || {
+    let edit = |json: &mut Value| {
+      let world_data = json
+        .pointer_mut("/properties/worldSaveData/value")
+        .ok_or("worldSaveData not found in Level.sav.")?;
+      let to_before = base_camps_run_by(world_data, &to);
+      let (handed, skipped) = hand_base_camps_to(world_data, &from, &to);
+      if !skipped.is_empty() {
+        return Err(format!("{to} is not a member of guild {}, which owns {from}'s base camps.", skipped.join(", ")));
+      }
+      Ok((handed > 0).then_some((handed, to_before)))
+    };
+    let check = |sav: &SavRewrite, (handed, to_before)| {
+      let world_data = &sav.reparsed["properties"]["worldSaveData"]["value"];
+      if base_camps_run_by(world_data, &from) != 0 || base_camps_run_by(world_data, &to) != to_before + handed {
+        return Err("Edited Level.sav does not round-trip to the expected base camp owners.".to_string());
+      }
+      Ok(handed)
+    };
+    let Some(handed) = edit_level_sav(&app, &account_id, &world_id, "pre-base-camp", edit, check)? else {
+      return Ok(0);
+    };
+    log_event(log::Level::Info, &format!("handed {handed} base camp(s) from {from} to {to}"));
+    Ok(handed)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+// ── Operation time estimates ─────────────────────────────
+
+/// Weight of the newest run in the moving average.
+const TIMING_SMOOTHING: f64 = 0.3;
+
+/// How much longer a PlM (Oodle) save takes to process than a zlib one.
+const OODLE_WORKLOAD_FACTOR: f64 = 1.6;
+
+/// Uncalibrated ms per MB of a zlib (PlZ) Level.sav.
+fn default_ms_per_mb(op: &str) -> Option<f64> {
+  match op {
+    "swap" => Some(900.0),
+    "export" => Some(120.0),
+    _ => None,
+  }
+}
+
+/// Level.sav's size in MB and how much slower its compression is to handle
+/// than zlib (Oodle decodes in pure Rust and may fall back to re-encoding).
+fn level_sav_workload(world_path: &Path) -> Result<(f64, f64), String> {
+  let level_sav = world_path.join("Level.sav");
+  let size = fs::metadata(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?.len();
+  let mut magic = [0u8; 12];
+  let _ = fs::File::open(&level_sav).and_then(|mut f| f.read_exact(&mut magic));
+  Ok((size as f64 / (1024.0 * 1024.0), compression_factor(&magic)))
+}
+
+/// Workload factor for a save starting with `header` (its first 12 bytes).
+fn compression_factor(header: &[u8]) -> f64 {
+  if header.get(8..11) == Some(&b"PlM"[..]) {
+    OODLE_WORKLOAD_FACTOR
+  } else {
+    1.0
+  }
+}
+
+/// The ms-per-MB average after a run that took `elapsed_ms` on `mb` MB with
+/// compression `factor`; the first run sets it outright. `None` for an
+/// empty workload, which says nothing about speed.
+fn updated_ms_per_mb(previous: Option<f64>, elapsed_ms: f64, mb: f64, factor: f64) -> Option<f64> {
+  if mb <= 0.0 || factor <= 0.0 {
+    return None;
+  }
+  let sample = elapsed_ms / (mb * factor);
+  Some(previous.map_or(sample, |avg| avg + TIMING_SMOOTHING * (sample - avg)))
+}
+
+/// Fold one real run of `op` on `world_path` into the stored average.
+fn record_operation_time(app: &AppHandle, op: &str, world_path: &Path, elapsed: std::time::Duration) {
+  let Ok((mb, factor)) = level_sav_workload(world_path) else { return };
+  let Ok(mut config) = load_app_config(app) else { return };
+  let previous = config.operation_ms_per_mb.get(op).copied();
+  let Some(avg) = updated_ms_per_mb(previous, elapsed.as_secs_f64() * 1000.0, mb, factor) else { return };
+  config.operation_ms_per_mb.insert(op.to_string(), avg);
+  let _ = save_app_config(app, &config);
+}
+
+/// Check the save codecs work on this machine: a known pattern must
+/// survive a PlZ round trip and the embedded literal-only Oodle block must
+/// decode. That block is hand-built, so this does not vouch for decoding
+/// real Mermaid output.
+#[tauri::command]
+fn verify_oodle() -> Result<bool, String> {
+  let pattern: Vec<u8> = b"GVAS".iter().copied().chain((0..16_384u32).map(|i| (i * 31 % 251) as u8)).collect();
+  let (round_trip, _) = gvas::decompress_sav(&gvas::compress_sav(&pattern, 0x32)?)?;
+  if round_trip != pattern {
+    log_event(log::Level::Error, "Codec self-test: PlZ round trip changed the data");
+    return Ok(false);
+  }
+  let ok = oodle::literal_block_self_test()?;
+  if !ok {
+    log_event(log::Level::Error, "Codec self-test: literal-only Oodle block decoded to the wrong bytes");
+  }
+  Ok(ok)
+}
+
+/// Rough duration of `op` ("swap", "set-host" or "export") on this world
+/// in ms, from Level.sav's size and the average of recent runs.
+#[tauri::command]
+fn estimate_operation_time(app: AppHandle, account_id: String, world_id: String, op: String) -> Result<u64, String> {
+  let op = if op == "set-host" { "swap".to_string() } else { op };
+  let default = default_ms_per_mb(&op).ok_or_else(|| format!("Unknown operation '{op}'."))?;
+  let (mb, factor) = level_sav_workload(&world_dir(&account_id, &world_id)?)?;
+  let ms_per_mb = load_app_config(&app)?.operation_ms_per_mb.get(&op).copied().unwrap_or(default);
+  Ok((mb * factor * ms_per_mb).round() as u64)
+}
+
+/// Turn `Auto` into a concrete strategy: InstanceId when each non-empty
+/// InstanceId matches exactly one character entry, PlayerUId otherwise.
+fn resolve_match_strategy(level_json: &Value, strategy: MatchStrategy, inst_first: &str, inst_second: &str) -> MatchStrategy {
+  if strategy != MatchStrategy::Auto {
+    return strategy;
+  }
+  let entries = level_json
+    .pointer("/properties/worldSaveData/value/CharacterSaveParameterMap/value")
+    .and_then(|v| v.as_array());
+  let count = |inst: &str| {
+    entries
+      .into_iter()
+      .flatten()
+      .filter(|e| e.pointer("/key/InstanceId/value").and_then(|v| v.as_str()) == Some(inst))
+      .count()
+  };
+  let unique = !inst_first.is_empty() && count(inst_first) == 1 && (inst_second.is_empty() || count(inst_second) == 1);
+  if unique {
+    MatchStrategy::InstanceId
+  } else {
+    MatchStrategy::PlayerUId
+  }
+}
+
+/// Move a player into an empty slot: patch their .sav and Level.sav from
+/// `from_id`'s UID to `to_id`'s, then rename the file. If Level.sav can't be
+/// updated the player .sav is put back as it was.
+fn move_player_to_slot(world_path: &Path, players_dir: &Path, from_id: &str, to_id: &str) -> Result<(), String> {
+  let from = normalize_id(from_id);
+  let to = normalize_id(to_id);
+  let from_sav = players_dir.join(format!("{from}.sav"));
+  let to_sav = players_dir.join(format!("{to}.sav"));
+  if !from_sav.exists() {
+    return Err(format!("{from}.sav not found."));
+  }
+  if to_sav.exists() {
+    return Err(format!("Slot {to} is already taken."));
+  }
+  let uuid_from = filename_to_uuid(&from);
+  let uuid_to = filename_to_uuid(&to);
+  let inst = read_player_instance_id(&from_sav)?;
+  let original = fs::read(&from_sav).map_err(|e| format!("read player sav: {e}"))?;
+  modify_player_sav(&from_sav, &uuid_from, &uuid_to)?;
+  if let Err(e) = swap_uids_in_level(world_path, &uuid_from, &uuid_to, &inst, "", &SwapOptions::default(), &|_, _| {}) {
+    fs::write(&from_sav, &original).map_err(|err| format!("{e} (and restoring {from}.sav failed: {err})"))?;
+    return Err(e);
+  }
+  fs::rename(&from_sav, &to_sav).map_err(|err| err.to_string())
+}
+
+/// Snapshot of the world's names/host mapping to store alongside a backup.
+fn backup_snapshot(wc: &WorldConfig, action: &str) -> BackupSnapshot {
+  BackupSnapshot {
+    host_id: wc.host_id.clone(),
+    players: wc.players.clone(),
+    original_names: wc.original_names.clone(),
+    display_name: wc.display_name.clone(),
+    action: Some(action.to_string()),
+    label: None,
+  }
+}
+
+fn backup_files(
+  players_dir: &Path,
+  world_path: &Path,
+  backup_root: &Path,
+  ids: &[String],
+  snapshot: &BackupSnapshot,
+  full: bool,
+) -> Result<PathBuf, String> {
+  // A second backup within the same second gets a `-<n>` counter
+  let stamp = chrono::Utc::now().format(BACKUP_STAMP_FORMAT).to_string();
+  let mut backup_dir = backup_root.join(&stamp);
+  for n in 2.. {
+    if !long_path(&backup_dir).exists() {
+      break;
+    }
+    backup_dir = backup_root.join(format!("{stamp}-{n}"));
+  }
+  let (players_dir, world_path, out) = (long_path(players_dir), long_path(world_path), long_path(&backup_dir));
+  fs::create_dir_all(&out).map_err(|err| err.to_string())?;
+  // Every letter-case variant of each player file, under its own name
+  for id in ids {
+    for src in player_file_variants(&players_dir, &normalize_id(id)) {
+      let name = src.file_name().ok_or("Player file has no name")?;
+      fs::copy(&src, out.join(name)).map_err(|err| err.to_string())?;
+    }
+  }
+  // Backup Level.sav (plus WorldOption.sav / LocalData.sav for a full backup)
+  let root_savs: &[&str] = if full { WORLD_ROOT_SAVS } else { &WORLD_ROOT_SAVS[..1] };
+  for name in root_savs {
+    let src = world_path.join(name);
+    if src.exists() {
+      fs::copy(&src, out.join(name)).map_err(|err| err.to_string())?;
+    }
+  }
+  // Save config snapshot with names mapping
+  let snapshot_json = serde_json::to_string_pretty(snapshot).map_err(|err| err.to_string())?;
+  fs::write(out.join("config_snapshot.json"), snapshot_json).map_err(|err| err.to_string())?;
+  Ok(backup_dir)
+}
+
+/// Parse a backup folder name: a `BACKUP_STAMP_FORMAT` timestamp, optionally
+/// preceded by a `<label>_` such as `pre-swap_` and followed by the `-<n>`
+/// counter of a same-second backup.
+fn parse_backup_timestamp(name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+  let without_counter = name
+    .rsplit_once('-')
+    .filter(|(_, n)| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+    .map(|(rest, _)| rest);
+  [Some(name), without_counter].into_iter().flatten().find_map(|name| {
+    let split = name.len().checked_sub("2024-01-02_10-00-00".len())?;
+    let (label, stamp) = (name.get(..split)?, name.get(split..)?);
+    if !label.is_empty() && (label.len() < 2 || !label.ends_with('_')) {
+      return None;
+    }
+    chrono::NaiveDateTime::parse_from_str(stamp, BACKUP_STAMP_FORMAT).ok().map(|dt| dt.and_utc())
+  })
+}
+
+/// Backups sorted newest first, by the name's timestamp or, for names that
+/// don't parse, the folder's modification time.
+fn list_backup_entries(backup_root: &Path) -> Vec<BackupEntry> {
+  if !backup_root.exists() {
+    return Vec::new();
+  }
+  let mut items: Vec<(chrono::DateTime<chrono::Utc>, String)> = list_dirs(backup_root)
+    .into_iter()
+    .map(|name| {
+      let created = parse_backup_timestamp(&name).unwrap_or_else(|| {
+        fs::metadata(backup_root.join(&name))
+          .and_then(|m| m.modified())
+          .map(chrono::DateTime::<chrono::Utc>::from)
+          .unwrap_or_default()
+      });
+      (created, name)
+    })
+    .collect();
+  items.sort_by(|a, b| b.cmp(a));
+  items
+    .into_iter()
+    .map(|(created, name)| BackupEntry {
+      label: read_backup_snapshot(&backup_root.join(&name)).and_then(|s| s.label),
+      name,
+      created_at: created.to_rfc3339(),
+    })
+    .collect()
+}
+
+fn list_backups_dir(backup_root: &Path) -> Vec<String> {
+  list_backup_entries(backup_root).into_iter().map(|b| b.name).collect()
+}
+
+/// Read a backup's `config_snapshot.json`, if present and well-formed.
+fn read_backup_snapshot(backup_dir: &Path) -> Option<BackupSnapshot> {
+  let raw = fs::read_to_string(backup_dir.join("config_snapshot.json")).ok()?;
+  serde_json::from_str(&raw).ok()
+}
+
+#[tauri::command]
+fn get_app_config(app: AppHandle) -> Result<AppSettings, String> {
+  Ok(AppSettings::from_config(&load_app_config(&app)?))
+}
+
+/// Replace the custom host slot ids. Each must be a 32-char hex id (dashes
+/// allowed); an empty list restores the built-in slots only.
+#[tauri::command]
+fn set_custom_host_ids(app: AppHandle, ids: Vec<String>) -> Result<Vec<String>, String> {
+  let mut normalized: Vec<String> = Vec::new();
+  for id in &ids {
+    let n = normalize_host_id(id).ok_or_else(|| format!("'{id}' is not a 32-character hex id."))?;
+    if !normalized.contains(&n) {
+      normalized.push(n);
+    }
+  }
+  let mut config = load_app_config(&app)?;
+  config.custom_host_ids = normalized.clone();
+  save_app_config(&app, &config)?;
+  set_custom_host_id_list(normalized.clone());
+  log_event(log::Level::Info, &format!("custom host ids: {normalized:?}"));
+  Ok(normalized)
+}
+
+#[tauri::command]
+fn update_app_config(app: AppHandle, patch: Value) -> Result<AppSettings, String> {
+  let mut config = load_app_config(&app)?;
+  let settings = merge_app_settings(AppSettings::from_config(&config), &patch)?;
+  if let Some(root) = &settings.backup_root_override {
+    validate_backup_root(Path::new(root))?;
+  }
+  settings.apply_to(&mut config);
+  save_app_config(&app, &config)?;
+  oodle::set_dll_path(config.oodle_dll_path.as_ref().map(PathBuf::from));
+  set_epic_save_root(config.epic_save_root.as_ref().map(PathBuf::from));
+  Ok(AppSettings::from_config(&config))
+}
+
+#[tauri::command]
+fn get_accounts() -> Result<Vec<String>, String> {
+  // A missing root yields an empty list; `save_root_status` tells the UI why.
+  Ok(
+    save_games_roots()?
+      .into_iter()
+      .enumerate()
+      .flat_map(|(i, (platform, root))| {
+        list_dirs(&root).into_iter().map(move |id| qualified_account_id(i, platform, &id))
+      })
+      .collect(),
+  )
+}
+
+/// The save-games root shown to the user: the first of `roots` that exists
+/// (the default one first), else the default one.
+fn primary_save_games_root(roots: &[(Platform, PathBuf)]) -> PathBuf {
+  roots.iter().find(|(_, root)| root.is_dir()).unwrap_or(&roots[0]).1.clone()
+}
+
+/// `save_root_status` over `roots`: accounts are counted across all of them.
+fn root_status_in(roots: &[(Platform, PathBuf)]) -> RootStatus {
+  RootStatus {
+    path: primary_save_games_root(roots).to_string_lossy().to_string(),
+    exists: roots.iter().any(|(_, root)| root.is_dir()),
+    account_count: roots.iter().map(|(_, root)| list_dirs(root).len()).sum(),
+  }
+}
+
+#[tauri::command]
+fn get_save_games_root() -> Result<String, String> {
+  Ok(primary_save_games_root(&save_games_roots()?).to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn save_root_status() -> Result<RootStatus, String> {
+  Ok(root_status_in(&save_games_roots()?))
+}
+
+/// Keeps the save-root watcher alive for the app's lifetime.
+#[derive(Default)]
+struct SaveRootWatcher(Mutex<Option<notify::RecommendedWatcher>>);
+
+/// Whether the save-games root exists, and its account folders (sorted).
+/// The watcher emits when this changes.
+fn root_snapshot(root: &Path) -> (bool, Vec<String>) {
+  let mut accounts = list_dirs(root);
+  accounts.sort();
+  (root.is_dir(), accounts)
+}
+
+/// Only the root and its direct children (accounts) matter; world saves
+/// deeper down change constantly while the game runs.
+fn touches_save_root(root: &Path, paths: &[PathBuf]) -> bool {
+  paths.iter().any(|p| p == root || p.parent() == Some(root))
+}
+
+fn emit_root_status(app: &AppHandle) {
+  if let Ok(status) = save_root_status() {
+    let _ = app.emit("save-root-changed", status);
+  }
+}
+
+/// Watch the parent of the save-games root and the root itself (neither
+/// recursively) and emit `save-root-changed` whenever the root or its set of
+/// account folders changes. A no-op if the watcher is already running.
+fn start_save_root_watcher(app: &AppHandle) -> Result<(), String> {
+  use notify::Watcher;
+
+  let state = app.state::<SaveRootWatcher>();
+  let mut slot = state.0.lock().map_err(|_| "Save-root watcher lock poisoned.")?;
+  if slot.is_some() {
+    return Ok(());
+  }
+  let root = save_games_root()?;
+  let parent = root.parent().ok_or("Save-games root has no parent folder.")?.to_path_buf();
+  if !parent.is_dir() {
+    return Err(format!("{} does not exist yet.", parent.display()));
+  }
+
+  let last = Mutex::new(root_snapshot(&root));
+  let handle = app.clone();
+  let watched_root = root.clone();
+  let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+    let Ok(event) = res else { return };
+    if !touches_save_root(&watched_root, &event.paths) {
+      return;
+    }
+    let current = root_snapshot(&watched_root);
+    let Ok(mut last) = last.lock() else { return };
+    if *last != current {
+      if current.0 && !last.0 {
+        // The root (re)appeared: watch it too. Not from this thread, which
+        // is the one `watch` waits on.
+        let (handle, root) = (handle.clone(), watched_root.clone());
+        std::thread::spawn(move || {
+          let state = handle.state::<SaveRootWatcher>();
+          let Ok(mut slot) = state.0.lock() else { return };
+          if let Some(watcher) = slot.as_mut() {
+            let _ = watcher.watch(&root, notify::RecursiveMode::NonRecursive);
+          }
+        });
+      }
+      *last = current;
+      emit_root_status(&handle);
+    }
+  })
+  .map_err(|e| format!("Cannot create save-root watcher: {e}"))?;
+  watcher
+    .watch(&parent, notify::RecursiveMode::NonRecursive)
+    .map_err(|e| format!("Cannot watch {}: {e}", parent.display()))?;
+  if root.is_dir() {
+    watcher
+      .watch(&root, notify::RecursiveMode::NonRecursive)
+      .map_err(|e| format!("Cannot watch {}: {e}", root.display()))?;
+  }
+  *slot = Some(watcher);
+  Ok(())
+}
+
+/// Quiet period before a burst of save writes is reported as one change.
+const WORLD_CHANGE_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(750);
+
+/// The single world being watched; replacing or clearing it drops the old
+/// watcher, which also ends its debounce thread.
+#[derive(Default)]
+struct WorldWatcher(Mutex<Option<notify::RecommendedWatcher>>);
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct WorldChangedPayload {
+  account_id: String,
+  world_id: String,
+  /// Changed `.sav` files, relative to the world folder
+  paths: Vec<String>,
+}
+
+/// Whether a change under the world folder is the game writing a save
+/// (Level.sav or a player file), as opposed to our own config or backups.
+fn is_world_save_change(world_path: &Path, path: &Path) -> bool {
+  let Ok(rel) = path.strip_prefix(world_path) else { return false };
+  let is_sav = rel.extension().is_some_and(|e| e.eq_ignore_ascii_case("sav"));
+  let in_backup = rel.components().any(|c| c.as_os_str().eq_ignore_ascii_case("backup"));
+  is_sav && !in_backup
+}
+
+/// Watch one world's folder and emit `world-changed` (debounced) when the
+/// game rewrites Level.sav or a player save. Replaces any previous watch.
+#[tauri::command]
+fn watch_world(app: AppHandle, account_id: String, world_id: String) -> Result<(), String> {
+  use notify::Watcher;
+
+  let world_path = world_dir(&account_id, &world_id)?;
+  if !world_path.is_dir() {
+    return Err("World folder not found.".into());
+  }
+  let state = app.state::<WorldWatcher>();
+  let mut slot = state.0.lock().map_err(|_| "World watcher lock poisoned.")?;
+  *slot = None;
+
+  let (tx, rx) = std::sync::mpsc::channel::<PathBuf>();
+  let filter_root = world_path.clone();
+  let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+    let Ok(event) = res else { return };
+    if event.kind.is_access() {
+      return;
+    }
+    for path in event.paths.into_iter().filter(|p| is_world_save_change(&filter_root, p)) {
+      let _ = tx.send(path);
+    }
+  })
+  .map_err(|e| format!("Cannot create world watcher: {e}"))?;
+  watcher
+    .watch(&world_path, notify::RecursiveMode::Recursive)
+    .map_err(|e| format!("Cannot watch {}: {e}", world_path.display()))?;
+
+  // Ends once the watcher (and with it the sender) is dropped
+  let handle = app.clone();
+  std::thread::spawn(move || {
+    while let Ok(first) = rx.recv() {
+      let mut changed = std::collections::BTreeSet::from([first]);
+      while let Ok(path) = rx.recv_timeout(WORLD_CHANGE_DEBOUNCE) {
+        changed.insert(path);
+      }
+      let paths = changed
+        .iter()
+        .map(|p| p.strip_prefix(&world_path).unwrap_or(p).to_string_lossy().replace('\\', "/"))
+        .collect();
+      let _ = handle.emit(
+        "world-changed",
+        WorldChangedPayload { account_id: account_id.clone(), world_id: world_id.clone(), paths },
+      );
+    }
+  });
+  *slot = Some(watcher);
+  Ok(())
+}
+
+/// Stop the `watch_world` watcher, if any.
+#[tauri::command]
+fn stop_watching(app: AppHandle) -> Result<(), String> {
+  let state = app.state::<WorldWatcher>();
+  *state.0.lock().map_err(|_| "World watcher lock poisoned.")? = None;
+  Ok(())
+}
+
+/// When Level.sav last changed (RFC 3339), or `None` if there is none.
+/// A polling fallback for `watch_world`; never parses the save.
+#[tauri::command]
+fn get_level_sav_mtime(account_id: String, world_id: String) -> Result<Option<String>, String> {
+  let level_sav = world_dir(&account_id, &world_id)?.join("Level.sav");
+  let Ok(meta) = fs::metadata(&level_sav) else {
+    return Ok(None);
+  };
+  let modified = meta.modified().map_err(|e| format!("Cannot read Level.sav time: {e}"))?;
+  Ok(Some(chrono::DateTime::<chrono::Utc>::from(modified).to_rfc3339()))
+}
+
+/// Guess which store an account folder belongs to: Steam uses numeric
+/// SteamID64s, Epic uses 32-hex (or dashed GUID) account ids, and Xbox /
+/// Game Pass saves live under a `Packages`/`wgs` container path.
+fn classify_account(account_dir: &Path) -> &'static str {
+  if account_dir.components().any(|c| {
+    let c = c.as_os_str().to_string_lossy();
+    c.eq_ignore_ascii_case("Packages") || c.eq_ignore_ascii_case("wgs")
+  }) {
+    return "xbox";
+  }
+  let id = account_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+  classify_account_id(&id)
+}
+
+/// "steam" for an all-digit id, "epic" for a (dashed) hex one, else "unknown".
+fn classify_account_id(id: &str) -> &'static str {
+  if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+    "steam"
+  } else if is_hex_id(&id.replace('-', "")) {
+    "epic"
+  } else {
+    "unknown"
+  }
+}
+
+#[tauri::command]
+fn get_accounts_detailed() -> Result<Vec<AccountInfo>, String> {
+  Ok(
+    account_folders()?
+      .into_iter()
+      .map(|(id, platform, dir)| AccountInfo {
+        world_count: list_dirs(&dir).into_iter().filter(|w| is_world_dir(&dir.join(w))).count(),
+        platform: platform.to_string(),
+        id,
+      })
+      .collect(),
+  )
+}
+
+/// Every account folder under every save-games root as (qualified id,
+/// platform guess, folder).
+fn account_folders() -> Result<Vec<(String, &'static str, PathBuf)>, String> {
+  let mut accounts = Vec::new();
+  for (i, (platform, root)) in save_games_roots()?.into_iter().enumerate() {
+    for id in list_dirs(&root) {
+      let dir = root.join(&id);
+      // The default root is shared, so guess per account there
+      let guess = if i == 0 { classify_account(&dir) } else { platform.tag() };
+      accounts.push((qualified_account_id(i, platform, &id), guess, dir));
+    }
+  }
+  Ok(accounts)
+}
+
+#[tauri::command]
+fn get_worlds(account_id: String) -> Result<Vec<String>, String> {
+  Ok(list_dirs(&account_dir(&account_id)?))
+}
+
+#[tauri::command]
+fn get_worlds_with_counts(app: AppHandle, account_id: String, include_invalid: Option<bool>) -> Result<Vec<WorldInfo>, String> {
+  worlds_with_counts(&app, &account_id, include_invalid.unwrap_or(false))
+}
+
+fn worlds_with_counts(app: &AppHandle, account_id: &str, include_invalid: bool) -> Result<Vec<WorldInfo>, String> {
+  let root = account_dir(account_id)?;
+  let backup_override = load_app_config(app)?.backup_root_override.map(|o| PathBuf::from(o).join(account_id));
+  Ok(list_worlds(&root, include_invalid, backup_override.as_deref()))
+}
+
+/// Every account and its worlds (with counts) in one call, emitting
+/// `tree-progress` after each account.
+#[tauri::command]
+async fn get_full_tree(app: AppHandle) -> Result<Vec<AccountNode>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let accounts = account_folders()?;
+    let total = accounts.len();
+    let mut tree = Vec::with_capacity(total);
+    for (done, (id, platform, _)) in accounts.into_iter().enumerate() {
+      // One unreadable account shouldn't hide all the others
+      let (worlds, error) = match worlds_with_counts(&app, &id, false) {
+        Ok(worlds) => (worlds, None),
+        Err(e) => {
+          log_event(log::Level::Warn, &format!("could not list worlds of {id}: {e}"));
+          (Vec::new(), Some(e))
+        }
+      };
+      let _ = app.emit("tree-progress", ProgressPayload {
+        percent: (done + 1) as f64 / total as f64 * 100.0,
+        message: format!("Loaded account {}/{total}", done + 1),
+      });
+      tree.push(AccountNode { id, platform: platform.to_string(), worlds, error });
+    }
+    Ok(tree)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Cheap check that a folder looks like a world (no parsing).
+fn is_world_dir(path: &Path) -> bool {
+  path.join("Level.sav").is_file() || path.join("Players").is_dir()
+}
+
+/// `backup_override` is the account folder under a backup root override.
+fn list_worlds(root: &Path, include_invalid: bool, backup_override: Option<&Path>) -> Vec<WorldInfo> {
+  list_dirs(root)
+    .into_iter()
+    .filter(|wid| include_invalid || is_world_dir(&root.join(wid)))
+    .map(|wid| {
+      let pdir = root.join(&wid).join("Players");
+      let count = list_player_ids(&pdir).len();
+      let wc = load_world_config(&pdir);
+      let broot = backup_override.map(|o| o.join(&wid)).unwrap_or_else(|| pdir.join("backup"));
+      let last_backup = list_backups_dir(&broot).into_iter().next();
+      let last_backup_action = last_backup
+        .as_ref()
+        .and_then(|name| read_backup_snapshot(&broot.join(name)))
+        .and_then(|snap| snap.action);
+      let in_game_name = read_level_meta_name(&root.join(&wid)).ok().flatten();
+      WorldInfo {
+        id: wid,
+        player_count: count,
+        display_name: wc.display_name,
+        in_game_name,
+        last_backup,
+        last_backup_action,
+        favorite: wc.favorite,
+      }
+    })
+    .collect()
+}
+
+/// The world's in-game name from `LevelMeta.sav`; `None` when the file or
+/// the `WorldName` property is missing.
+fn read_level_meta_name(world_path: &Path) -> Result<Option<String>, String> {
+  let meta = world_path.join("LevelMeta.sav");
+  if !meta.is_file() {
+    return Ok(None);
+  }
+  let data = fs::read(&meta).map_err(|e| format!("Cannot read LevelMeta.sav: {e}"))?;
+  let (json, _) = gvas::sav_to_json(&data)?;
+  Ok(json
+    .pointer("/properties/SaveData/value/WorldName/value")
+    .and_then(|v| v.as_str())
+    .map(str::trim)
+    .filter(|n| !n.is_empty())
+    .map(String::from))
+}
+
+#[tauri::command]
+fn get_world_real_name(account_id: String, world_id: String) -> Result<Option<String>, String> {
+  read_level_meta_name(&world_dir(&account_id, &world_id)?)
+}
+
+const THUMBNAIL_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp"];
+const THUMBNAIL_STEMS: &[&str] = &["thumbnail", "screenshot", "preview", "savethumbnail"];
+
+/// An image saved next to the world's files, preferring well-known thumbnail
+/// names over any other image in the folder.
+fn find_world_thumbnail(world_path: &Path) -> Option<PathBuf> {
+  let mut images: Vec<PathBuf> = fs::read_dir(world_path)
+    .ok()?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| p.is_file())
+    .filter(|p| {
+      p.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| THUMBNAIL_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+    })
+    .collect();
+  images.sort();
+  let known = |p: &PathBuf| {
+    p.file_stem()
+      .and_then(|s| s.to_str())
+      .is_some_and(|s| THUMBNAIL_STEMS.contains(&s.to_ascii_lowercase().as_str()))
+  };
+  images.iter().find(|p| known(p)).or(images.first()).cloned()
+}
+
+/// The world's save thumbnail, if the game left one in the world folder.
+#[tauri::command]
+fn get_world_thumbnail(account_id: String, world_id: String) -> Result<Option<Vec<u8>>, String> {
+  match find_world_thumbnail(&world_dir(&account_id, &world_id)?) {
+    Some(path) => fs::read(&path).map(Some).map_err(|e| format!("Cannot read {}: {e}", path.display())),
+    None => Ok(None),
+  }
+}
+
+/// Estimate the map objects and work entries tied to each of a guild's bases.
+///
+/// MapObjectSaveData and WorkSaveData stay undecoded; each base is counted by
+/// how often its id appears in those blobs, which is about once per object
+/// that belongs to it. The world totals are exact entry counts.
+#[tauri::command]
+async fn get_base_structures(account_id: String, world_id: String, guild_id: String) -> Result<BaseStructures, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let wpath = world_dir(&account_id, &world_id)?;
+    let data = fs::read(wpath.join("Level.sav")).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (json, _) = gvas::sav_to_json(&data)?;
+    let world_data = &json["properties"]["worldSaveData"]["value"];
+
+    let base_ids: Vec<String> = world_data["GroupSaveDataMap"]["value"]
+      .as_array()
+      .and_then(|groups| {
+        groups.iter().find(|g| g["key"].as_str().is_some_and(|k| k.eq_ignore_ascii_case(&guild_id)))
+      })
+      .ok_or_else(|| format!("Guild {guild_id} not found."))?
+      .pointer("/value/RawData/value/base_ids")
+      .and_then(|v| v.as_array())
+      .map(|ids| ids.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+      .unwrap_or_default();
+
+    let map_objects = world_data.get("MapObjectSaveData");
+    let work = world_data.get("WorkSaveData");
+    let bases = base_ids
+      .into_iter()
+      .map(|base_id| BaseStructureCount {
+        estimated_map_objects: map_objects.and_then(|p| gvas::property_guid_occurrences(p, &base_id)),
+        estimated_work_items: work.and_then(|p| gvas::property_guid_occurrences(p, &base_id)),
+        base_id,
+      })
+      .collect();
+    Ok(BaseStructures {
+      guild_id,
+      bases,
+      total_map_objects: map_objects.and_then(gvas::property_entry_count),
+      total_work_items: work.and_then(gvas::property_entry_count),
+    })
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Check everything about a world in one pass: Level.sav and every player
+/// file parse, ids agree, the host slot is filled and no pal points at a
+/// missing player. Emits `scan-progress` per file.
+#[tauri::command]
+async fn scan_world(app: AppHandle, account_id: String, world_id: String) -> Result<Vec<ScanFinding>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let dir = players_dir(&account_id, &world_id)?;
+    let wpath = world_dir(&account_id, &world_id)?;
+    let emit = |pct: f64, msg: &str| {
+      let _ = app.emit("scan-progress", ProgressPayload { percent: pct * 100.0, message: msg.to_string() });
+    };
+    Ok(scan_world_dir(&wpath, &dir, &emit))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn scan_world_dir(world_path: &Path, players_dir: &Path, emit: &dyn Fn(f64, &str)) -> Vec<ScanFinding> {
+  let mut findings = Vec::new();
+  let mut add = |severity, category, message: String, target: Option<&str>| {
+    findings.push(ScanFinding { severity, category, message, target: target.map(String::from) });
+  };
+  let ids = list_player_ids(players_dir);
+  let total = ids.len() as f64 + 1.0;
+
+  // Level.sav
+  emit(0.0, "Checking Level.sav…");
+  let level = fs::read(world_path.join("Level.sav"))
+    .map_err(|e| format!("Cannot read Level.sav: {e}"))
+    .and_then(|data| gvas::sav_to_json(&data));
+  let level = match level {
+    Ok((json, _)) => Some(json),
+    Err(e) => {
+      add(ScanSeverity::Error, "level", format!("Level.sav doesn't parse: {e}"), Some("Level.sav"));
+      None
+    }
+  };
+  let level_players = level.as_ref().map(players_from_level_json).unwrap_or_default();
+
+  // Player files
+  for (i, id) in ids.iter().enumerate() {
+    let file = format!("{id}.sav");
+    emit((i as f64 + 1.0) / total, &format!("Checking {file}…"));
+    let info = match read_player_sav_info(&players_dir.join(&file)) {
+      Ok(info) => info,
+      Err(e) => {
+        add(ScanSeverity::Error, "player", format!("{file} doesn't parse: {e}"), Some(&file));
+        continue;
+      }
+    };
+    if !info.player_uid.eq_ignore_ascii_case(&filename_to_uuid(id)) {
+      add(ScanSeverity::Warning, "ids", format!("{file} contains PlayerUId {}", info.player_uid), Some(&file));
+    }
+    if level.is_none() {
+      continue;
+    }
+    match level_players.iter().find(|p| p.filename == *id) {
+      None => add(ScanSeverity::Warning, "references", format!("{file} has no character in Level.sav"), Some(&file)),
+      Some(lp) if !lp.instance_id.is_empty()
+        && info.instance_id.as_deref().is_some_and(|inst| !inst.eq_ignore_ascii_case(&lp.instance_id)) =>
+      {
+        add(ScanSeverity::Warning, "ids", format!("{file}'s InstanceId differs from its Level.sav character"), Some(&file));
+      }
+      Some(_) => {}
+    }
+  }
+
+  for id in &ids {
+    if player_file_variants(players_dir, id).len() > 1 {
+      let message = format!("{id}.sav exists in more than one letter case; the next swap keeps the newest (by mtime)");
+      add(ScanSeverity::Warning, "ids", message, Some(id));
+    }
+  }
+
+  // Host slot
+  if !ids.is_empty() && !ids.iter().any(|id| is_host_slot(id)) {
+    add(ScanSeverity::Warning, "host", "No player file is in the host slot".into(), None);
+  }
+
+  // Level.sav references without files
+  if let Some(json) = &level {
+    for lp in level_players.iter().filter(|lp| !ids.contains(&lp.filename)) {
+      add(ScanSeverity::Info, "references", format!("{} ({}) has no player file", lp.name, lp.filename), Some(&lp.filename));
+    }
+    let orphans = character_entries(&json["properties"]["worldSaveData"]["value"])
+      .filter(|ch| !ch.is_player())
+      .filter(|ch| ch.owner().is_some_and(|o| !ids.contains(&uuid_to_filename(o))))
+      .count();
+    if orphans > 0 {
+      add(ScanSeverity::Info, "references", format!("{orphans} pals belong to players with no file"), None);
+    }
+  }
+  emit(1.0, "Scan complete.");
+  findings.sort_by_key(|f| std::cmp::Reverse(f.severity));
+  findings
+}
+
+/// List pals whose `OwnerPlayerUId` matches no player file (read-only).
+#[tauri::command]
+async fn list_orphan_pals(account_id: String, world_id: String) -> Result<Vec<OrphanPal>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let present = list_player_ids(&players_dir(&account_id, &world_id)?);
+    let data = fs::read(world_dir(&account_id, &world_id)?.join("Level.sav"))
+      .map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (json, _) = gvas::sav_to_json(&data)?;
+    Ok(
+      character_entries(&json["properties"]["worldSaveData"]["value"])
+        .filter(|ch| !ch.is_player())
+        .filter_map(|ch| {
+          let owner = ch.owner()?;
+          if present.contains(&uuid_to_filename(owner)) {
+            return None;
+          }
+          Some(OrphanPal {
+            instance_id: ch.instance_id.to_string(),
+            character_id: ch.save_param["CharacterID"]["value"].as_str().unwrap_or("").to_string(),
+            level: ch.level().unwrap_or(1),
+            owner_uid: owner.to_string(),
+          })
+        })
+        .collect(),
+    )
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Species id → how many pals of that species `owner_uid` owns.
+fn pal_dex(world_data: &Value, owner_uid: &str) -> HashMap<String, usize> {
+  let mut dex = HashMap::new();
+  for ch in character_entries(world_data).filter(|ch| !ch.is_player()) {
+    if !ch.owner().is_some_and(|o| o.eq_ignore_ascii_case(owner_uid)) {
+      continue;
+    }
+    let species = ch.save_param["CharacterID"]["value"].as_str().unwrap_or("").to_string();
+    *dex.entry(species).or_insert(0) += 1;
+  }
+  dex
+}
+
+/// Pals owned by `player_id`, counted per species (read-only). Fails if the
+/// player has no file in the world.
+#[tauri::command]
+async fn get_pal_dex(account_id: String, world_id: String, player_id: String) -> Result<HashMap<String, usize>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let id = normalize_id(&player_id);
+    if !list_player_ids(&players_dir(&account_id, &world_id)?).contains(&id) {
+      return Err("Player not found.".into());
+    }
+    let data = fs::read(world_dir(&account_id, &world_id)?.join("Level.sav"))
+      .map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (json, _) = gvas::sav_to_json(&data)?;
+    let owner = filename_to_uuid(&id);
+    Ok(pal_dex(&json["properties"]["worldSaveData"]["value"], &owner))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// How old a world is (see `get_world_age`).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorldAge {
+  /// Days on the in-game clock
+  in_game_days: u64,
+  /// Oldest modification time of any file in the world folder (RFC 3339)
+  real_first_modified: String,
+}
+
+/// 100 ns ticks in a day.
+const TICKS_PER_DAY: u64 = 24 * 60 * 60 * 10_000_000;
+
+/// Whole days on the world's in-game clock; 0 when it isn't there.
+fn in_game_days(world_data: &Value) -> u64 {
+  game_time_ticks(world_data, "GameDateTimeTicks") / TICKS_PER_DAY
+}
+
+/// In-game days from the world's `GameDateTimeTicks` and the oldest file
+/// time in its folder.
+#[tauri::command]
+async fn get_world_age(account_id: String, world_id: String) -> Result<WorldAge, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let wpath = world_dir(&account_id, &world_id)?;
+    let data = fs::read(wpath.join("Level.sav")).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (json, _) = gvas::sav_to_json(&data)?;
+    let oldest = WalkDir::new(&wpath)
+      .into_iter()
+      .flatten()
+      .filter(|e| e.file_type().is_file())
+      .filter_map(|e| e.metadata().ok()?.modified().ok())
+      .min()
+      .ok_or("World folder has no files.")?;
+    Ok(WorldAge {
+      in_game_days: in_game_days(&json["properties"]["worldSaveData"]["value"]),
+      real_first_modified: chrono::DateTime::<chrono::Utc>::from(oldest).to_rfc3339(),
+    })
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Level.sav sizes further apart than this fraction never count as duplicates.
+const DUPLICATE_SIZE_TOLERANCE: f64 = 0.05;
+
+/// Group `(world id, sorted player uuids, Level.sav size)` into clusters with
+/// the same roster and sizes within `DUPLICATE_SIZE_TOLERANCE` of the
+/// cluster's smallest. Only clusters of two or more are returned; worlds
+/// without players (e.g. fresh ones) are never candidates.
+fn duplicate_candidates(mut worlds: Vec<(String, Vec<String>, u64)>) -> Vec<Vec<String>> {
+  worlds.retain(|(_, roster, _)| !roster.is_empty());
+  worlds.sort_by(|a, b| (&a.1, a.2, &a.0).cmp(&(&b.1, b.2, &b.0)));
+  let mut clusters: Vec<(Vec<String>, u64, Vec<String>)> = Vec::new();
+  for (id, roster, size) in worlds {
+    match clusters.last_mut() {
+      Some((r, smallest, ids)) if *r == roster && (size - *smallest) as f64 <= *smallest as f64 * DUPLICATE_SIZE_TOLERANCE => {
+        ids.push(id)
+      }
+      _ => clusters.push((roster, size, vec![id])),
+    }
+  }
+  clusters.into_iter().map(|(_, _, ids)| ids).filter(|ids| ids.len() > 1).collect()
+}
+
+/// Worlds in the account that look like copies of each other: same player
+/// roster, similar Level.sav size and the same in-game day. Read-only; emits
+/// `duplicate-scan-progress` per world in each pass (the first half of the
+/// range for the roster pass, the second for re-parsing candidates).
+#[tauri::command]
+async fn find_duplicate_worlds(app: AppHandle, account_id: String) -> Result<Vec<Vec<String>>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let root = account_dir(&account_id)?;
+    let world_ids = account_world_ids(&account_id)?;
+    let total = world_ids.len();
+    let mut fingerprints = Vec::new();
+    for (i, world_id) in world_ids.into_iter().enumerate() {
+      let wpath = root.join(&world_id);
+      match (extract_players_from_level(&wpath), fs::metadata(wpath.join("Level.sav"))) {
+        (Ok(players), Ok(meta)) => {
+          let mut roster: Vec<String> = players.into_iter().map(|p| p.uuid.to_ascii_lowercase()).collect();
+          roster.sort();
+          fingerprints.push((world_id.clone(), roster, meta.len()));
+        }
+        (Err(e), _) => log_event(log::Level::Warn, &format!("duplicate scan skipped {world_id}: {e}")),
+        (_, Err(e)) => log_event(log::Level::Warn, &format!("duplicate scan skipped {world_id}: {e}")),
+      }
+      let _ = app.emit("duplicate-scan-progress", ProgressPayload {
+        percent: (i + 1) as f64 / total as f64 * 50.0,
+        message: format!("Scanned {world_id} ({}/{total})", i + 1),
+      });
+    }
+
+    // Only candidates are parsed again, for their in-game day
+    let groups = duplicate_candidates(fingerprints);
+    let to_parse: usize = groups.iter().map(Vec::len).sum();
+    let mut parsed = 0;
+    let mut duplicates = Vec::new();
+    for candidates in groups {
+      let mut by_day: HashMap<u64, Vec<String>> = HashMap::new();
+      for world_id in candidates {
+        parsed += 1;
+        let _ = app.emit("duplicate-scan-progress", ProgressPayload {
+          percent: 50.0 + (parsed - 1) as f64 / to_parse as f64 * 50.0,
+          message: format!("Reading the in-game day of {world_id} ({parsed}/{to_parse})"),
+        });
+        let day = fs::read(root.join(&world_id).join("Level.sav"))
+          .map_err(|e| format!("Cannot read Level.sav: {e}"))
+          .and_then(|data| gvas::sav_to_json(&data))
+          .map(|(json, _)| in_game_days(&json["properties"]["worldSaveData"]["value"]));
+        match day {
+          Ok(day) => by_day.entry(day).or_default().push(world_id),
+          Err(e) => log_event(log::Level::Warn, &format!("duplicate scan skipped {world_id}: {e}")),
+        }
+      }
+      duplicates.extend(by_day.into_values().filter(|ids| ids.len() > 1));
+    }
+    let _ = app.emit("duplicate-scan-progress", ProgressPayload { percent: 100.0, message: "Duplicate scan complete.".into() });
+    duplicates.sort();
+    Ok(duplicates)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Guilds, independent (solo) guilds and organizations in an already-parsed
+/// Level.sav's worldSaveData. Neutral/other group types are skipped.
+fn guilds_from_world_data(world_data: &Value) -> Vec<GuildSummary> {
+  world_data["GroupSaveDataMap"]["value"]
+    .as_array()
+    .into_iter()
+    .flatten()
+    .filter_map(|g| {
+      let group_type = g.pointer("/value/GroupType/value/value")?.as_str()?.strip_prefix("EPalGroupType::")?;
+      let rd = &g["value"]["RawData"]["value"];
+      let len = |field: &str| rd[field].as_array().map_or(0, |a| a.len());
+      let member_count = match group_type {
+        "Guild" => len("players"),
+        "IndependentGuild" => rd["player_uid"].is_string() as usize,
+        "Organization" => len("individual_character_handle_ids"),
+        _ => return None,
+      };
+      Some(GuildSummary {
+        id: g["key"].as_str().unwrap_or("").to_string(),
+        group_type: group_type.to_string(),
+        name: rd["guild_name"].as_str().or(rd["group_name"].as_str()).unwrap_or("").to_string(),
+        member_count,
+        base_count: len("base_ids"),
+        base_camp_level: rd["base_camp_level"].as_i64(),
+      })
+    })
+    .collect()
+}
+
+/// List the world's guilds, including solo (IndependentGuild) players and
+/// organizations.
+#[tauri::command]
+async fn get_guilds(account_id: String, world_id: String) -> Result<Vec<GuildSummary>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let data = fs::read(world_dir(&account_id, &world_id)?.join("Level.sav"))
+      .map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (json, _) = gvas::sav_to_json(&data)?;
+    Ok(guilds_from_world_data(&json["properties"]["worldSaveData"]["value"]))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn engine_version_string(header: &Value) -> String {
+  match header["engine_version_major"].as_i64() {
+    Some(major) => format!(
+      "{major}.{}.{}-{}+{}",
+      header["engine_version_minor"].as_i64().unwrap_or(0),
+      header["engine_version_patch"].as_i64().unwrap_or(0),
+      header["engine_version_changelist"].as_i64().unwrap_or(0),
+      header["engine_version_branch"].as_str().unwrap_or(""),
+    ),
+    None => String::new(),
+  }
+}
+
+/// List Level.sav's worldSaveData keys with sizes and entry counts. Uses the
+/// streaming scan, so even huge worlds are never fully decoded.
+#[tauri::command]
+async fn list_world_data_keys(account_id: String, world_id: String) -> Result<Vec<WorldDataKeyInfo>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let file = fs::File::open(world_dir(&account_id, &world_id)?.join("Level.sav"))
+      .map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (_, keys) = gvas::scan_world_data_keys(std::io::BufReader::new(file))?;
+    Ok(keys.into_iter().map(WorldDataKeyInfo::from).collect())
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Engine/save version from Level.sav's header (streamed; nothing else is read).
+#[tauri::command]
+async fn get_world_version(account_id: String, world_id: String) -> Result<WorldVersion, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let file = fs::File::open(world_dir(&account_id, &world_id)?.join("Level.sav"))
+      .map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (header, save_type) = gvas::scan_sav_header(std::io::BufReader::new(file))?;
+    Ok(WorldVersion {
+      engine_version: engine_version_string(&header),
+      save_game_version: header["save_game_version"].as_i64(),
+      save_type,
+    })
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Players, guilds, version, totals, disk usage and last backup in one call.
+#[tauri::command]
+async fn get_world_summary(app: AppHandle, account_id: String, world_id: String) -> Result<WorldSummary, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let broot = backup_root(&app, &account_id, &world_id)?;
+    world_summary_in(&world_dir(&account_id, &world_id)?, &players_dir(&account_id, &world_id)?, &broot)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn world_summary_in(world_path: &Path, players_dir: &Path, backup_root: &Path) -> Result<WorldSummary, String> {
+  let data = fs::read(world_path.join("Level.sav")).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+  let (json, _) = gvas::sav_to_json(&data)?;
+  let world_data = &json["properties"]["worldSaveData"]["value"];
+
+  let player_ids = list_player_ids(players_dir);
+  let host_id = resolve_host_id(&load_world_config(players_dir), &player_ids).unwrap_or_default();
+  let players = build_players(&player_ids, &host_id, &players_from_level_json(&json));
+
+  Ok(WorldSummary {
+    player_count: players.len(),
+    pal_count: character_entries(world_data).filter(|c| !c.is_player()).count(),
+    guilds: guilds_from_world_data(world_data),
+    engine_version: engine_version_string(&json["header"]),
+    disk_usage_bytes: dir_size(world_path),
+    last_backup: list_backup_entries(backup_root).into_iter().next(),
+    players,
+  })
+}
+
+/// The save `rewrite_sav` edits.
+enum SavTarget<'a> {
+  /// Level.sav, backed up together with these player files
+  Level(&'a [String]),
+  /// One player's `.sav`
+  Player(&'a str),
+}
+
+/// What `rewrite_sav` hands its `check`: the edited JSON, the re-parse of
+/// its encoding and the file size before and after.
+struct SavRewrite<'a> {
+  edited: &'a Value,
+  reparsed: &'a Value,
+  bytes_before: u64,
+  bytes_after: u64,
+}
+
+/// Lock the world, wait for the game to go idle and `rewrite_sav` its
+/// Level.sav.
+fn edit_level_sav<T, U>(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  action: &str,
+  edit: impl FnOnce(&mut Value) -> Result<Option<T>, String>,
+  check: impl FnOnce(&SavRewrite, T) -> Result<U, String>,
+) -> Result<Option<U>, String> {
+  let _guard = lock_world(app, account_id, world_id)?;
+  wait_for_idle_if_enabled(app, account_id, world_id)?;
+  rewrite_sav(app, account_id, world_id, SavTarget::Level(&[]), action, edit, check)
+}
+
+/// Parse `target`, apply `edit` and re-encode it; the encoded save must
+/// re-parse and pass `check` before the world is backed up (as `action`)
+/// and the file written. `edit` returning `None` writes nothing. Callers
+/// hold the world's lock.
+fn rewrite_sav<T, U>(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  target: SavTarget,
+  action: &str,
+  edit: impl FnOnce(&mut Value) -> Result<Option<T>, String>,
+  check: impl FnOnce(&SavRewrite, T) -> Result<U, String>,
+) -> Result<Option<U>, String> {
+  let dir = players_dir(account_id, world_id)?;
+  let wpath = world_dir(account_id, world_id)?;
+  let broot = backup_root(app, account_id, world_id)?;
+  rewrite_sav_in(&wpath, &dir, &broot, target, action, edit, check)
+}
+
+/// `rewrite_sav` on explicit world, Players and backup folders.
+fn rewrite_sav_in<T, U>(
+  wpath: &Path,
+  dir: &Path,
+  backup_root: &Path,
+  target: SavTarget,
+  action: &str,
+  edit: impl FnOnce(&mut Value) -> Result<Option<T>, String>,
+  check: impl FnOnce(&SavRewrite, T) -> Result<U, String>,
+) -> Result<Option<U>, String> {
+  let (path, backup_ids) = match target {
+    SavTarget::Level(ids) => (wpath.join("Level.sav"), ids.to_vec()),
+    SavTarget::Player(id) => (dir.join(format!("{id}.sav")), vec![id.to_string()]),
+  };
+  let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+  let data = fs::read(&path).map_err(|e| format!("Cannot read {name}: {e}"))?;
+  let (mut json, save_type) = gvas::sav_to_json(&data)?;
+  let Some(edited) = edit(&mut json)? else {
+    return Ok(None);
+  };
+  let sav_bytes = gvas::json_to_sav(&json, save_type)?;
+  let (reparsed, _) = gvas::sav_to_json(&sav_bytes).map_err(|e| format!("Edited {name} failed to re-parse: {e}"))?;
+  let rewrite = SavRewrite {
+    edited: &json,
+    reparsed: &reparsed,
+    bytes_before: data.len() as u64,
+    bytes_after: sav_bytes.len() as u64,
+  };
+  let out = check(&rewrite, edited)?;
+
+  let wc = load_world_config(dir);
+  backup_files(dir, wpath, backup_root, &backup_ids, &backup_snapshot(&wc, action), false)?;
+  fs::write(&path, &sav_bytes).map_err(|e| format!("Cannot write {name}: {e}"))?;
+  Ok(Some(out))
+}
+
+fn population_from_world_data(world_data: &Value) -> Population {
+  let (players, pals) = character_entries(world_data).fold((0, 0), |(players, pals), c| {
+    if c.is_player() {
+      (players + 1, pals)
+    } else {
+      (players, pals + 1)
+    }
+  });
+  let guilds: Vec<GuildSummary> = guilds_from_world_data(world_data)
+    .into_iter()
+    .filter(|g| g.group_type != "Organization")
+    .collect();
+  Population { players, pals, bases: guilds.iter().map(|g| g.base_count).sum(), guilds: guilds.len() }
+}
+
+/// Player, pal, guild and base totals from one Level.sav parse.
+#[tauri::command]
+async fn get_world_population(account_id: String, world_id: String) -> Result<Population, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let data = fs::read(world_dir(&account_id, &world_id)?.join("Level.sav"))
+      .map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+    let (json, _) = gvas::sav_to_json(&data)?;
+    Ok(population_from_world_data(&json["properties"]["worldSaveData"]["value"]))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Fold guild `source` into guild `target`: members, character handles and
+/// bases move over, characters and base camps are re-pointed at `target`,
+/// and the source entry is removed. Both guilds need decoded RawData.
+/// Returns the source's RawData, for `check_guild_merge`.
+fn merge_guilds_in_level(json: &mut Value, source: &str, target: &str) -> Result<Value, String> {
+  if source.eq_ignore_ascii_case(target) {
+    return Err("Cannot merge a guild into itself.".into());
+  }
+  let world_data = json
+    .pointer_mut("/properties/worldSaveData/value")
+    .ok_or("worldSaveData not found in Level.sav.")?;
+  let groups = world_data
+    .pointer_mut("/GroupSaveDataMap/value")
+    .and_then(|v| v.as_array_mut())
+    .ok_or("GroupSaveDataMap not found in Level.sav.")?;
+  let find_guild = |groups: &[Value], id: &str| {
+    groups.iter().position(|g| {
+      g["key"].as_str().is_some_and(|k| k.eq_ignore_ascii_case(id))
+        && g.pointer("/value/GroupType/value/value").and_then(|v| v.as_str()) == Some("EPalGroupType::Guild")
+    })
+  };
+  let src_idx = find_guild(groups, source).ok_or_else(|| format!("Guild {source} not found."))?;
+  let dst_idx = find_guild(groups, target).ok_or_else(|| format!("Guild {target} not found."))?;
+  let source_id = groups[src_idx]["key"].as_str().unwrap_or(source).to_string();
+  let target_id = groups[dst_idx]["key"].as_str().unwrap_or(target).to_string();
+  // RawData that failed to decode is still a byte array: no players list
+  let decoded = |g: &Value| g.pointer("/value/RawData/value/players").is_some_and(Value::is_array);
+  if !decoded(&groups[src_idx]) {
+    return Err("Source guild has no decoded RawData.".into());
+  }
+  if !decoded(&groups[dst_idx]) {
+    return Err("Target guild has no decoded RawData.".into());
+  }
+
+  let src_raw = groups.remove(src_idx).pointer("/value/RawData/value").cloned().unwrap_or(Value::Null);
+  let dst_idx = if src_idx < dst_idx { dst_idx - 1 } else { dst_idx };
+  let dst_raw = groups[dst_idx]
+    .pointer_mut("/value/RawData/value")
+    .ok_or("Target guild has no decoded RawData.")?;
+  for field in ["individual_character_handle_ids", "base_ids", "map_object_instance_ids_base_camp_points", "players"] {
+    let moved = src_raw[field].as_array().cloned().unwrap_or_default();
+    if let Some(list) = dst_raw.get_mut(field).and_then(|v| v.as_array_mut()) {
+      for item in moved {
+        if !list.contains(&item) {
+          list.push(item);
+        }
+      }
+    }
+  }
+
+  // Characters carry their guild in the CSPM rawdata
+  if let Some(entries) = world_data.pointer_mut("/CharacterSaveParameterMap/value").and_then(|v| v.as_array_mut()) {
+    for entry in entries {
+      if let Some(gid) = entry.pointer_mut("/value/RawData/value/group_id") {
+        if gid.as_str().is_some_and(|g| g.eq_ignore_ascii_case(&source_id)) {
+          *gid = Value::String(target_id.clone());
+        }
+      }
+    }
+  }
+  // Base camps keep an undecoded group_id_belong_to
+  if let Some(camps) = world_data.get_mut("BaseCampSaveData") {
+    gvas::map_base_camp_group_ids(camps, |id| id.eq_ignore_ascii_case(&source_id).then(|| target_id.clone()))?;
+  }
+  Ok(src_raw)
+}
+
+/// Verify a (re-parsed) merge of guild `source` into `target`: the source
+/// entry is gone, the target holds every member, base and character handle
+/// of `source_raw`, and no character or base camp still names the source.
+fn check_guild_merge(world_data: &Value, source: &str, target: &str, source_raw: &Value) -> Result<(), String> {
+  let groups = world_data["GroupSaveDataMap"]["value"].as_array().ok_or("GroupSaveDataMap not found in Level.sav.")?;
+  let find = |id: &str| groups.iter().find(|g| g["key"].as_str().is_some_and(|k| k.eq_ignore_ascii_case(id)));
+  if find(source).is_some() {
+    return Err("Merged Level.sav still contains the source guild.".into());
+  }
+  let target_raw = &find(target).ok_or("Merged Level.sav lost the target guild.")?["value"]["RawData"]["value"];
+  // What identifies an entry of each moved list
+  let id_of = |field: &str, item: &Value| {
+    let id = match field {
+      "players" => &item["player_uid"],
+      "individual_character_handle_ids" => &item["instance_id"],
+      _ => item,
+    };
+    id.as_str().unwrap_or("").to_ascii_lowercase()
+  };
+  for field in ["players", "base_ids", "individual_character_handle_ids"] {
+    let held: Vec<String> = target_raw[field].as_array().into_iter().flatten().map(|i| id_of(field, i)).collect();
+    if source_raw[field].as_array().into_iter().flatten().any(|i| !held.contains(&id_of(field, i))) {
+      return Err(format!("Merged Level.sav is missing some of the source guild's {field}."));
+    }
+  }
+  if character_entries(world_data).any(|c| c.raw["group_id"].as_str().is_some_and(|g| g.eq_ignore_ascii_case(source))) {
+    return Err("A character in the merged Level.sav still belongs to the source guild.".into());
+  }
+  if let Some(camps) = world_data.get("BaseCampSaveData") {
+    if gvas::base_camp_group_ids(camps)?.iter().any(|g| g.eq_ignore_ascii_case(source)) {
+      return Err("A base camp in the merged Level.sav still belongs to the source guild.".into());
+    }
+  }
+  Ok(())
+}
+
+/// Decoded RawData of guild `guild_id` in a parsed Level.sav.
+fn guild_rawdata_mut<'a>(json: &'a mut Value, guild_id: &str) -> Result<&'a mut serde_json::Map<String, Value>, String> {
+  let group = json
+    .pointer_mut("/properties/worldSaveData/value/GroupSaveDataMap/value")
+    .and_then(|v| v.as_array_mut())
+    .ok_or("GroupSaveDataMap not found in Level.sav.")?
+    .iter_mut()
+    .find(|g| g["key"].as_str().is_some_and(|k| k.eq_ignore_ascii_case(guild_id)))
+    .ok_or_else(|| format!("Guild {guild_id} not found."))?;
+  let group_type = group.pointer("/value/GroupType/value/value").and_then(|v| v.as_str()).unwrap_or("");
+  if !matches!(group_type, "EPalGroupType::Guild" | "EPalGroupType::IndependentGuild") {
+    return Err(format!("Group {guild_id} is not a guild."));
+  }
+  group
+    .pointer_mut("/value/RawData/value")
+    .and_then(|v| v.as_object_mut())
+    .ok_or_else(|| "Guild has no decoded RawData.".to_string())
+}
+
+/// Rename guild (or independent guild) `guild_id`, crediting `modifier_uid`
+/// as the last player to change the name.
+fn set_guild_name_in_level(json: &mut Value, guild_id: &str, name: &str, modifier_uid: &str) -> Result<(), String> {
+  let rd = guild_rawdata_mut(json, guild_id)?;
+  // Independent guilds keep a second copy of the name
+  if rd.get("guild_name_2").is_some_and(|n2| rd.get("guild_name") == Some(n2)) {
+    rd.insert("guild_name_2".into(), Value::String(name.to_string()));
+  }
+  rd.insert("guild_name".into(), Value::String(name.to_string()));
+  if rd.contains_key("last_guild_name_modifier_player_uid") {
+    rd.insert("last_guild_name_modifier_player_uid".into(), Value::String(modifier_uid.to_string()));
+  }
+  Ok(())
+}
+
+/// Set a guild's base camp level; the rest of its RawData is left as is.
+fn set_guild_base_camp_level_in_level(json: &mut Value, guild_id: &str, level: i32) -> Result<(), String> {
+  let rd = guild_rawdata_mut(json, guild_id)?;
+  if !rd.contains_key("base_camp_level") {
+    return Err(format!("Guild {guild_id} has no base camp level."));
+  }
+  rd.insert("base_camp_level".into(), Value::from(level));
+  Ok(())
+}
+
+/// Rename a guild as the current host. Backs up Level.sav first and checks
+/// the re-encoded save carries the new name before writing it.
+#[tauri::command]
+async fn set_guild_name(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  guild_id: String,
+  new_name: String,
+) -> Result<Vec<GuildSummary>, String> {
+  let name = new_name.trim().to_string();
+  if name.is_empty() {
+    return Err("Guild name cannot be empty.".into());
+  }
+  tauri::async_runtime::spawn_blocking(move || {
+    let edit = |json: &mut Value| {
+      let dir = players_dir(&account_id, &world_id)?;
+      let host_id = resolve_host_id(&load_world_config(&dir), &list_player_ids(&dir)).ok_or("Host not found.")?;
+      set_guild_name_in_level(json, &guild_id, &name, &filename_to_uuid(&host_id)).map(Some)
+    };
+    let check = |sav: &SavRewrite, ()| {
+      let guilds = guilds_from_world_data(&sav.reparsed["properties"]["worldSaveData"]["value"]);
+      if !guilds.iter().any(|g| g.id.eq_ignore_ascii_case(&guild_id) && g.name == name) {
+        return Err("Renamed Level.sav does not carry the new guild name.".to_string());
+      }
+      Ok(guilds)
+    };
+    let guilds = edit_level_sav(&app, &account_id, &world_id, "pre-guild-rename", edit, check)?.unwrap_or_default();
+    log_event(log::Level::Info, &format!("renamed guild {guild_id} to \"{name}\""));
+    Ok(guilds)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Change a guild's base camp level (1..=`MAX_BASE_CAMP_LEVEL`). Backs up
+/// Level.sav first and checks the re-encoded save before writing it.
+#[tauri::command]
+async fn set_guild_base_camp_level(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  guild_id: String,
+  level: i32,
+) -> Result<Vec<GuildSummary>, String> {
+  if !(1..=MAX_BASE_CAMP_LEVEL).contains(&level) {
+    return Err(format!("Base camp level must be between 1 and {MAX_BASE_CAMP_LEVEL}."));
+  }
+  tauri::async_runtime::spawn_blocking(move || {
+    let edit = |json: &mut Value| set_guild_base_camp_level_in_level(json, &guild_id, level).map(Some);
+    let check = |sav: &SavRewrite, ()| {
+      let guilds = guilds_from_world_data(&sav.reparsed["properties"]["worldSaveData"]["value"]);
+      if !guilds.iter().any(|g| g.id.eq_ignore_ascii_case(&guild_id) && g.base_camp_level == Some(level.into())) {
+        return Err("Edited Level.sav does not carry the new base camp level.".to_string());
+      }
+      Ok(guilds)
+    };
+    let guilds = edit_level_sav(&app, &account_id, &world_id, "pre-base-camp", edit, check)?.unwrap_or_default();
+    log_event(log::Level::Info, &format!("set base camp level of guild {guild_id} to {level}"));
+    Ok(guilds)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Set the level of `uid`'s character in its SaveParameter, and its `Exp`
+/// to the total needed to reach that level, whether raising or lowering.
+/// Returns the previous level.
+fn set_player_level_in_level(json: &mut Value, uid: &str, level: u32) -> Result<u32, String> {
+  let entries = json
+    .pointer_mut("/properties/worldSaveData/value/CharacterSaveParameterMap/value")
+    .and_then(|v| v.as_array_mut())
+    .ok_or("Level.sav has no CharacterSaveParameterMap.")?;
+  let param = entries
+    .iter_mut()
+    .filter(|e| e.pointer("/key/PlayerUId/value").and_then(|v| v.as_str()).is_some_and(|u| u.eq_ignore_ascii_case(uid)))
+    .filter_map(|e| e.pointer_mut("/value/RawData/value/object/SaveParameter/value").and_then(|v| v.as_object_mut()))
+    .find(|p| p.get("IsPlayer").and_then(|v| v["value"].as_bool()).unwrap_or(false))
+    .ok_or_else(|| format!("No character for player {uid} in Level.sav."))?;
+  let exp = player_exp_for_level(level).ok_or_else(|| format!("No Exp threshold is known for level {level}."))?;
+  // An unset Level is the default, 1
+  let old = param.get("Level").and_then(|l| l["value"]["value"].as_u64()).unwrap_or(1) as u32;
+  param.insert(
+    "Level".into(),
+    serde_json::json!({"id": null, "value": {"type": "None", "value": level}, "type": "ByteProperty"}),
+  );
+  match param.get_mut("Exp") {
+    Some(prop) => prop["value"] = Value::from(exp),
+    // An unset Exp is 0, right for level 1 only
+    None if exp > 0 => {
+      param.insert("Exp".into(), serde_json::json!({"id": null, "value": exp, "type": "Int64Property"}));
+    }
+    None => {}
+  }
+  Ok(old)
+}
+
+/// Change a player's level (1..=`MAX_PLAYER_LEVEL`, as far as
+/// `PLAYER_TOTAL_EXP` goes) and their Exp to match. Backs up Level.sav
+/// first and checks the re-encoded save before writing it.
+#[tauri::command]
+async fn set_player_level(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  player_id: String,
+  level: u32,
+) -> Result<Vec<Player>, String> {
+  if !(1..=MAX_PLAYER_LEVEL).contains(&level) {
+    return Err(format!("Player level must be between 1 and {MAX_PLAYER_LEVEL}."));
+  }
+  let uid = player_id_to_uuid(&normalize_id(&player_id))?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let edit = |json: &mut Value| set_player_level_in_level(json, &uid, level).map(Some);
+    let check = |sav: &SavRewrite, old| {
+      if !players_from_level_json(sav.reparsed).iter().any(|p| p.uuid.eq_ignore_ascii_case(&uid) && p.level == level) {
+        return Err("Edited Level.sav does not carry the new player level.".to_string());
+      }
+      let world_data = &sav.reparsed["properties"]["worldSaveData"]["value"];
+      let exp = character_entries(world_data)
+        .find(|c| c.is_player() && c.player_uid.eq_ignore_ascii_case(&uid))
+        .map(|c| c.save_param["Exp"]["value"].as_i64().unwrap_or(0));
+      if exp != player_exp_for_level(level) {
+        return Err("Edited Level.sav does not carry the Exp for the new level.".to_string());
+      }
+      Ok(old)
+    };
+    let old = edit_level_sav(&app, &account_id, &world_id, "pre-player-level", edit, check)?.unwrap_or_default();
+    log_event(log::Level::Info, &format!("set level of player {player_id} from {old} to {level}"));
+    get_players_sync(&app, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Merge guild `source_guild_id` into `target_guild_id`. Always backs up
+/// Level.sav first and re-parses the output before writing it.
+#[tauri::command]
+async fn merge_guilds(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  source_guild_id: String,
+  target_guild_id: String,
+) -> Result<Vec<Player>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let edit = |json: &mut Value| merge_guilds_in_level(json, &source_guild_id, &target_guild_id).map(Some);
+    let check = |sav: &SavRewrite, source_raw: Value| {
+      let world_data = &sav.reparsed["properties"]["worldSaveData"]["value"];
+      check_guild_merge(world_data, &source_guild_id, &target_guild_id, &source_raw)
+    };
+    edit_level_sav(&app, &account_id, &world_id, "pre-merge", edit, check)?;
+    log_event(log::Level::Info, &format!("merged guild {source_guild_id} into {target_guild_id}"));
+    get_players_sync(&app, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Remove player `uid` from Level.sav: their character entry (plus the pals
+/// they own when `delete_pals`), guild memberships and character handles.
+/// A guild they administered is handed to its first remaining member; a
+/// guild they were the last member of is removed, unless it still has bases
+/// (an error: merge it into another guild first).
+/// Returns how many character entries were removed.
+fn delete_player_in_level(json: &mut Value, uid: &str, delete_pals: bool) -> Result<usize, String> {
+  let world_data = json
+    .pointer_mut("/properties/worldSaveData/value")
+    .ok_or("worldSaveData not found in Level.sav.")?;
+  let entries = world_data
+    .pointer_mut("/CharacterSaveParameterMap/value")
+    .and_then(|v| v.as_array_mut())
+    .ok_or("CharacterSaveParameterMap not found in Level.sav.")?;
+  let doomed = |entry: &Value| {
+    let is_uid = |p: &str| entry.pointer(p).and_then(|v| v.as_str()).is_some_and(|u| u.eq_ignore_ascii_case(uid));
+    is_uid("/key/PlayerUId/value")
+      || (delete_pals && is_uid("/value/RawData/value/object/SaveParameter/value/OwnerPlayerUId/value"))
+  };
+  let mut removed_instances = Vec::new();
+  entries.retain(|entry| {
+    if !doomed(entry) {
+      return true;
+    }
+    if let Some(inst) = entry.pointer("/key/InstanceId/value").and_then(|v| v.as_str()) {
+      removed_instances.push(inst.to_ascii_lowercase());
+    }
+    false
+  });
+  if removed_instances.is_empty() {
+    return Err(format!("No character for {uid} in Level.sav."));
+  }
+
+  let Some(groups) = world_data.pointer_mut("/GroupSaveDataMap/value").and_then(|v| v.as_array_mut()) else {
+    return Ok(removed_instances.len());
+  };
+  let mut emptied = Vec::new();
+  for (i, group) in groups.iter_mut().enumerate() {
+    let guild = group["key"].as_str().unwrap_or_default().to_string();
+    let solo = group.pointer("/value/GroupType/value/value").and_then(|v| v.as_str()) == Some("EPalGroupType::IndependentGuild");
+    let Some(raw) = group.pointer_mut("/value/RawData/value") else { continue };
+    if let Some(handles) = raw.get_mut("individual_character_handle_ids").and_then(|v| v.as_array_mut()) {
+      handles.retain(|h| {
+        let inst = h["instance_id"].as_str().unwrap_or("").to_ascii_lowercase();
+        !removed_instances.contains(&inst)
+      });
+    }
+    let keeps_bases = |raw: &Value| raw["base_ids"].as_array().is_some_and(|b| !b.is_empty());
+    let last_member = || format!("{uid} is the last member of guild {guild}, which still has bases; merge it into another guild first.");
+    // A solo player's group holds them in player_uid, not in players
+    if solo {
+      if raw["player_uid"].as_str().is_some_and(|u| u.eq_ignore_ascii_case(uid)) {
+        if keeps_bases(raw) {
+          return Err(last_member());
+        }
+        emptied.push(i);
+      }
+      continue;
+    }
+    let Some(members) = raw.get_mut("players").and_then(|v| v.as_array_mut()) else { continue };
+    let before = members.len();
+    members.retain(|m| !m["player_uid"].as_str().is_some_and(|u| u.eq_ignore_ascii_case(uid)));
+    if members.len() == before {
+      continue;
+    }
+    let Some(next_admin) = members.first().and_then(|m| m["player_uid"].as_str()).map(str::to_string) else {
+      if keeps_bases(raw) {
+        return Err(last_member());
+      }
+      emptied.push(i);
+      continue;
+    };
+    if let Some(admin) = raw.get_mut("admin_player_uid") {
+      if admin.as_str().is_some_and(|a| a.eq_ignore_ascii_case(uid)) {
+        *admin = Value::String(next_admin);
+      }
+    }
+  }
+  for i in emptied.into_iter().rev() {
+    groups.remove(i);
+  }
+  Ok(removed_instances.len())
+}
+
+/// Delete a player from a world: their .sav, character entry and guild
+/// memberships (plus owned pals with `delete_pals`). Refuses the host unless
+/// `force`; always backs up and re-parses Level.sav before writing it.
+#[tauri::command]
+async fn delete_player(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  player_id: String,
+  delete_pals: Option<bool>,
+  force: Option<bool>,
+) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    let dir = players_dir(&account_id, &world_id)?;
+    let id = normalize_id(&player_id);
+    let sav = dir.join(format!("{id}.sav"));
+    if !sav.exists() {
+      return Err("Player not found.".into());
+    }
+    let mut wc = load_world_config(&dir);
+    if host_slot_in(&wc, &list_player_ids(&dir), &host_slot_ids()).as_deref() == Some(id.as_str()) && !force.unwrap_or(false) {
+      return Err("Refusing to delete the host.".into());
+    }
+
+    let uid = filename_to_uuid(&id);
+    let edit = |json: &mut Value| delete_player_in_level(json, &uid, delete_pals.unwrap_or(false)).map(Some);
+    // The character must be gone from the re-parsed save
+    let check = |sav: &SavRewrite, removed| {
+      if character_entries(&sav.reparsed["properties"]["worldSaveData"]["value"]).any(|c| c.player_uid.eq_ignore_ascii_case(&uid)) {
+        return Err("Edited Level.sav still contains the player.".to_string());
+      }
+      Ok(removed)
+    };
+    let target = SavTarget::Level(std::slice::from_ref(&id));
+    let removed = rewrite_sav(&a, &account_id, &world_id, target, "pre-delete", edit, check)?.unwrap_or_default();
+    fs::remove_file(&sav).map_err(|e| format!("Cannot delete {id}.sav: {e}"))?;
+    wc.players.remove(&id);
+    wc.original_names.remove(&id);
+    save_world_config(&dir, &wc)?;
+    log_event(log::Level::Info, &format!("deleted player {id} ({removed} character entries)"));
+    get_players_sync(&a, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Where a pal's SlotID names the container it sits in.
+const PAL_SLOT_CONTAINER: &str = "/value/RawData/value/object/SaveParameter/value/SlotID/value/ContainerId/value/ID/value";
+
+/// Splice player `uid` from the `src` Level.sav into `dst`: their character
+/// entry, the pal containers in `container_ids` and, with `with_pals`, the
+/// pals they own. Every copied character gets a fresh InstanceId and loses
+/// its guild (the source guild doesn't exist in `dst`), and every copied
+/// container a fresh ID. Pals sitting in a container that isn't copied (a
+/// base, say) stay behind; container slots of pals left behind are emptied.
+/// Returns the player's new InstanceId, how many pals came along and the
+/// container IDs as old -> new (lowercase).
+fn copy_player_in_level(
+  src: &Value,
+  dst: &mut Value,
+  uid: &str,
+  container_ids: &[String],
+  with_pals: bool,
+) -> Result<(String, usize, HashMap<String, String>), String> {
+  let src_data = &src["properties"]["worldSaveData"]["value"];
+  if character_entries(&dst["properties"]["worldSaveData"]["value"]).any(|c| c.is_player() && c.player_uid.eq_ignore_ascii_case(uid)) {
+    return Err(format!("{uid} already has a character in the destination world."));
+  }
+
+  let mut src_containers = Vec::new();
+  let mut new_containers: HashMap<String, String> = HashMap::new();
+  if !container_ids.is_empty() {
+    let src_ccsd = &src_data["CharacterContainerSaveData"];
+    if src_ccsd.get("skip_type").is_some() {
+      return Err("Source CharacterContainerSaveData could not be decoded.".into());
+    }
+    for id in container_ids {
+      let Some(entry) = src_ccsd["value"].as_array().into_iter().flatten().find(|e| {
+        e.pointer("/key/ID/value").and_then(|v| v.as_str()).is_some_and(|v| v.eq_ignore_ascii_case(id))
+      }) else {
+        log_event(log::Level::Warn, &format!("container {id} not found in the source Level.sav"));
+        continue;
+      };
+      new_containers.insert(id.to_ascii_lowercase(), uuid::Uuid::new_v4().to_string());
+      src_containers.push(entry);
+    }
+  }
+
+  let mut copied = Vec::new();
+  let mut new_ids: HashMap<String, String> = HashMap::new();
+  let mut player_instance = None;
+  let mut left_behind = 0;
+  for entry in src_data["CharacterSaveParameterMap"]["value"].as_array().into_iter().flatten() {
+    let is_uid = |p: &str| entry.pointer(p).and_then(|v| v.as_str()).is_some_and(|u| u.eq_ignore_ascii_case(uid));
+    let is_player = entry
+      .pointer("/value/RawData/value/object/SaveParameter/value/IsPlayer/value")
+      .and_then(|v| v.as_bool())
+      .unwrap_or(false);
+    let wanted = if is_player {
+      is_uid("/key/PlayerUId/value")
+    } else {
+      with_pals && is_uid("/value/RawData/value/object/SaveParameter/value/OwnerPlayerUId/value")
+    };
+    if !wanted {
+      continue;
+    }
+    let container = entry
+      .pointer(PAL_SLOT_CONTAINER)
+      .and_then(|v| v.as_str())
+      .filter(|c| *c != "00000000-0000-0000-0000-000000000000")
+      .map(str::to_ascii_lowercase);
+    let new_container = match &container {
+      Some(c) if !is_player => match new_containers.get(c) {
+        Some(fresh) => Some(fresh.clone()),
+        None => {
+          left_behind += 1;
+          continue;
+        }
+      },
+      _ => None,
+    };
+    let old = entry.pointer("/key/InstanceId/value").and_then(|v| v.as_str()).unwrap_or("").to_ascii_lowercase();
+    let fresh = uuid::Uuid::new_v4().to_string();
+    let mut copy = entry.clone();
+    if let Some(inst) = copy.pointer_mut("/key/InstanceId/value") {
+      *inst = Value::String(fresh.clone());
+    }
+    if let Some(group) = copy.pointer_mut("/value/RawData/value/group_id") {
+      *group = Value::String("00000000-0000-0000-0000-000000000000".into());
+    }
+    if let (Some(fresh_container), Some(slot)) = (new_container, copy.pointer_mut(PAL_SLOT_CONTAINER)) {
+      *slot = Value::String(fresh_container);
+    }
+    if is_player {
+      player_instance = Some(fresh.clone());
+    }
+    new_ids.insert(old, fresh);
+    copied.push(copy);
+  }
+  let player_instance = player_instance.ok_or_else(|| format!("No character for {uid} in the source Level.sav."))?;
+  let pals = copied.len() - 1;
+  if left_behind > 0 {
+    log_event(log::Level::Info, &format!("{left_behind} pal(s) of {uid} are in containers that aren't copied; left behind"));
+  }
+
+  let mut containers = Vec::new();
+  for entry in src_containers {
+    let mut copy = entry.clone();
+    let old = entry.pointer("/key/ID/value").and_then(|v| v.as_str()).unwrap_or("").to_ascii_lowercase();
+    if let (Some(id), Some(fresh)) = (copy.pointer_mut("/key/ID/value"), new_containers.get(&old)) {
+      *id = Value::String(fresh.clone());
+    }
+    for slot in copy.pointer_mut("/value/Slots/value/values").and_then(|v| v.as_array_mut()).into_iter().flatten() {
+      let Some(raw) = slot.pointer_mut("/RawData/value/values") else { continue };
+      let bytes: Vec<u8> = raw.as_array().into_iter().flatten().map(|b| b.as_u64().unwrap_or(0) as u8).collect();
+      let Some((_, old)) = gvas::decode_container_slot_rawdata(&bytes) else { continue };
+      let target = new_ids.get(&old.to_ascii_lowercase()).map_or("00000000-0000-0000-0000-000000000000", String::as_str);
+      let rewritten = gvas::set_container_slot_instance(&bytes, target)?;
+      *raw = Value::Array(rewritten.into_iter().map(Value::from).collect());
+    }
+    containers.push(copy);
+  }
+
+  let dst_data = dst
+    .pointer_mut("/properties/worldSaveData/value")
+    .ok_or("worldSaveData not found in the destination Level.sav.")?;
+  if !containers.is_empty() {
+    let dst_containers = dst_data
+      .pointer_mut("/CharacterContainerSaveData/value")
+      .and_then(|v| v.as_array_mut())
+      .ok_or("Destination CharacterContainerSaveData could not be decoded.")?;
+    dst_containers.extend(containers);
+  }
+  dst_data
+    .pointer_mut("/CharacterSaveParameterMap/value")
+    .and_then(|v| v.as_array_mut())
+    .ok_or("CharacterSaveParameterMap not found in the destination Level.sav.")?
+    .extend(copied);
+  Ok((player_instance, pals, new_containers))
+}
+
+/// Point the party and palbox of a parsed player save at the containers'
+/// new IDs from `new_ids` (old -> new, lowercase); a container without a
+/// new ID wasn't copied and gets the zero GUID. Returns how many were
+/// remapped.
+fn remap_pal_containers(player_json: &mut Value, new_ids: &HashMap<String, String>) -> usize {
+  let mut remapped = 0;
+  for prop in ["OtomoCharacterContainerId", "PalStorageContainerId"] {
+    let Some(id) = player_json.pointer_mut(&format!("/properties/SaveData/value/{prop}/value/ID/value")) else {
+      continue;
+    };
+    let old = id.as_str().unwrap_or("").to_ascii_lowercase();
+    match new_ids.get(&old) {
+      Some(fresh) => {
+        *id = Value::String(fresh.clone());
+        remapped += 1;
+      }
+      None => *id = Value::String("00000000-0000-0000-0000-000000000000".into()),
+    }
+  }
+  remapped
+}
+
+/// Point every inventory container of a parsed player save at the zero GUID,
+/// for a save whose ItemContainerSaveData entries aren't coming along.
+/// Returns how many references were cleared.
+fn clear_inventory_containers(player_json: &mut Value) -> usize {
+  let Some(info) = player_json
+    .pointer_mut("/properties/SaveData/value/InventoryInfo/value")
+    .and_then(|v| v.as_object_mut())
+  else {
+    return 0;
+  };
+  let mut cleared = 0;
+  for container in info.values_mut() {
+    if let Some(id) = container.pointer_mut("/value/ID/value") {
+      *id = Value::String("00000000-0000-0000-0000-000000000000".into());
+      cleared += 1;
+    }
+  }
+  cleared
+}
+
+/// Copy a player from `src_world` into `dst_world` of the same account: the
+/// `.sav` plus their character (and pals with `copy_pals`) spliced into the
+/// destination Level.sav under fresh InstanceIds. Item containers can't be
+/// copied, so the copy starts with an empty inventory. Backs up the
+/// destination and re-parses it before writing.
+#[tauri::command]
+async fn copy_player_between_worlds(
+  app: AppHandle,
+  account_id: String,
+  src_world: String,
+  dst_world: String,
+  player_id: String,
+  copy_pals: Option<bool>,
+) -> Result<(), String> {
+  if src_world == dst_world {
+    return Err("Source and destination are the same world.".into());
+  }
+  // Both worlds, always in world id order
+  let mut worlds = [src_world.clone(), dst_world.clone()];
+  worlds.sort();
+  let _guards = (lock_world(&app, &account_id, &worlds[0])?, lock_world(&app, &account_id, &worlds[1])?);
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    for world in &worlds {
+      wait_for_idle_if_enabled(&a, &account_id, world)?;
+    }
+    let id = normalize_id(&player_id);
+    let uid = player_id_to_uuid(&id)?;
+    let src_sav = players_dir(&account_id, &src_world)?.join(format!("{id}.sav"));
+    let dst_dir = players_dir(&account_id, &dst_world)?;
+    let dst_path = world_dir(&account_id, &dst_world)?;
+    let dst_sav = dst_dir.join(format!("{id}.sav"));
+    if !src_sav.exists() {
+      return Err("Player not found in the source world.".into());
+    }
+    if dst_sav.exists() {
+      return Err("The destination world already has this player.".into());
+    }
+
+    let (mut player_json, player_type) = gvas::sav_to_json(&fs::read(&src_sav).map_err(|e| format!("read player sav: {e}"))?)?;
+    let container_ids: Vec<String> = pal_container_ids(&player_json).into_iter().map(|(id, _)| id).collect();
+    let src_level = fs::read(world_dir(&account_id, &src_world)?.join("Level.sav")).map_err(|e| format!("Cannot read source Level.sav: {e}"))?;
+    let (src_json, _) = gvas::sav_to_json(&src_level)?;
+    let dst_level = dst_path.join("Level.sav");
+    let (mut dst_json, save_type) = gvas::sav_to_json(&fs::read(&dst_level).map_err(|e| format!("Cannot read Level.sav: {e}"))?)?;
+    let (instance_id, pals, new_containers) =
+      copy_player_in_level(&src_json, &mut dst_json, &uid, &container_ids, copy_pals.unwrap_or(false))?;
+    let sav_bytes = gvas::json_to_sav(&dst_json, save_type)?;
+    let (check, _) = gvas::sav_to_json(&sav_bytes).map_err(|e| format!("Edited Level.sav failed to re-parse: {e}"))?;
+    if find_player_instance_id(&check, &uid).as_deref() != Some(instance_id.as_str()) {
+      return Err("Edited Level.sav does not contain the copied player.".into());
+    }
+
+    if let Some(inst) = player_json.pointer_mut("/properties/SaveData/value/IndividualId/value/InstanceId/value") {
+      *inst = Value::String(instance_id.clone());
+    }
+    // The copied party and palbox live under fresh IDs; the source's item
+    // containers aren't in the destination Level.sav at all
+    remap_pal_containers(&mut player_json, &new_containers);
+    clear_inventory_containers(&mut player_json);
+    let player_bytes = gvas::json_to_sav(&player_json, player_type)?;
+
+    let wc = load_world_config(&dst_dir);
+    backup_files(&dst_dir, &dst_path, &backup_root(&a, &account_id, &dst_world)?, &[], &backup_snapshot(&wc, "pre-import"), false)?;
+    fs::write(&dst_sav, &player_bytes).map_err(|e| format!("Cannot write {id}.sav: {e}"))?;
+    if let Err(e) = fs::write(&dst_level, &sav_bytes) {
+      let _ = fs::remove_file(&dst_sav);
+      return Err(format!("Cannot write Level.sav: {e}"));
+    }
+    log_event(log::Level::Info, &format!("copied player {id} from {src_world} to {dst_world} ({pals} pals)"));
+    Ok(())
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Remove what `opts` asks for from a parsed Level.sav: orphan pals (with
+/// their guild handles and container slots) and member-less, base-less
+/// guilds. `present` holds the lowercase PlayerUIds that still have a player
+/// file.
+fn compact_level(json: &mut Value, present: &std::collections::HashSet<String>, opts: CompactOptions) -> Result<CompactReport, String> {
+  let world_data = json
+    .pointer_mut("/properties/worldSaveData/value")
+    .ok_or("worldSaveData not found in Level.sav.")?;
+  let mut report = CompactReport::default();
+
+  if opts.remove_orphan_pals {
+    let orphans: std::collections::HashSet<String> = character_entries(world_data)
+      .filter(|ch| !ch.is_player() && ch.owner().is_some_and(|o| !present.contains(&o.to_ascii_lowercase())))
+      .map(|ch| ch.instance_id.to_ascii_lowercase())
+      .collect();
+    if let Some(entries) = world_data.pointer_mut("/CharacterSaveParameterMap/value").and_then(|v| v.as_array_mut()) {
+      entries.retain(|e| {
+        let inst = e.pointer("/key/InstanceId/value").and_then(|v| v.as_str()).unwrap_or("");
+        !orphans.contains(&inst.to_ascii_lowercase())
+      });
+    }
+    let handle_lists = world_data
+      .pointer_mut("/GroupSaveDataMap/value")
+      .and_then(|v| v.as_array_mut())
+      .into_iter()
+      .flatten()
+      .filter_map(|g| g.pointer_mut("/value/RawData/value/individual_character_handle_ids"))
+      .filter_map(|h| h.as_array_mut());
+    for handles in handle_lists {
+      handles.retain(|h| !h["instance_id"].as_str().is_some_and(|i| orphans.contains(&i.to_ascii_lowercase())));
+    }
+    // Empty any palbox/party slot still pointing at a removed pal
+    let slots = world_data
+      .pointer_mut("/CharacterContainerSaveData/value")
+      .and_then(|v| v.as_array_mut())
+      .into_iter()
+      .flatten()
+      .filter_map(|c| c.pointer_mut("/value/Slots/value/values").and_then(|v| v.as_array_mut()))
+      .flatten();
+    for slot in slots {
+      let Some(raw) = slot.pointer_mut("/RawData/value/values") else { continue };
+      let bytes: Vec<u8> = raw.as_array().into_iter().flatten().map(|b| b.as_u64().unwrap_or(0) as u8).collect();
+      let Some((_, inst)) = gvas::decode_container_slot_rawdata(&bytes) else { continue };
+      if orphans.contains(&inst.to_ascii_lowercase()) {
+        let rewritten = gvas::set_container_slot_instance(&bytes, "00000000-0000-0000-0000-000000000000")?;
+        *raw = Value::Array(rewritten.into_iter().map(Value::from).collect());
+      }
+    }
+    report.orphan_pals_removed = orphans.len();
+  }
+
+  if opts.remove_empty_guilds {
+    if let Some(groups) = world_data.pointer_mut("/GroupSaveDataMap/value").and_then(|v| v.as_array_mut()) {
+      let before = groups.len();
+      groups.retain(|g| {
+        let is_guild = g.pointer("/value/GroupType/value/value").and_then(|v| v.as_str()) == Some("EPalGroupType::Guild");
+        let rd = &g["value"]["RawData"]["value"];
+        let empty = |field: &str| rd[field].as_array().is_some_and(|a| a.is_empty());
+        !(is_guild && empty("players") && empty("base_ids"))
+      });
+      report.guilds_removed = before - groups.len();
+    }
+  }
+  Ok(report)
+}
+
+/// Remove orphan pals and/or empty guilds from Level.sav. Always backs up
+/// first and re-parses the output to check exactly those entries are gone.
+/// Nothing is written when there is nothing to remove.
+#[tauri::command]
+async fn compact_world(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  options: CompactOptions,
+) -> Result<CompactReport, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let dir = players_dir(&account_id, &world_id)?;
+    let counts = |json: &Value| {
+      let wd = &json["properties"]["worldSaveData"]["value"];
+      let len = |field: &str| wd[field]["value"].as_array().map_or(0, |a| a.len());
+      (len("CharacterSaveParameterMap"), len("GroupSaveDataMap"))
+    };
+    let edit = |json: &mut Value| {
+      let before = counts(json);
+      let present = list_player_ids(&dir).iter().map(|id| filename_to_uuid(id).to_ascii_lowercase()).collect();
+      let report = compact_level(json, &present, options)?;
+      Ok((report.orphan_pals_removed > 0 || report.guilds_removed > 0).then_some((report, before)))
+    };
+    let check = |sav: &SavRewrite, (mut report, (chars_before, groups_before)): (CompactReport, (usize, usize))| {
+      let expected = (chars_before - report.orphan_pals_removed, groups_before - report.guilds_removed);
+      if counts(sav.reparsed) != expected {
+        return Err("Compacted Level.sav does not round-trip to the expected entries.".to_string());
+      }
+      report.bytes_before = sav.bytes_before;
+      report.bytes_after = sav.bytes_after;
+      Ok(report)
+    };
+    let Some(mut report) = edit_level_sav(&app, &account_id, &world_id, "pre-compact", edit, check)? else {
+      let level_sav = world_dir(&account_id, &world_id)?.join("Level.sav");
+      let size = fs::metadata(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?.len();
+      return Ok(CompactReport { bytes_before: size, bytes_after: size, ..Default::default() });
+    };
+    report.bytes_saved = report.bytes_before as i64 - report.bytes_after as i64;
+    log_event(
+      log::Level::Info,
+      &format!(
+        "compacted {world_id}: {} orphan pals, {} guilds removed, {} bytes saved",
+        report.orphan_pals_removed, report.guilds_removed, report.bytes_saved
+      ),
+    );
+    Ok(report)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Compare the player rosters of two worlds in the same account.
+#[tauri::command]
+async fn diff_worlds(account_id: String, world_a: String, world_b: String) -> Result<WorldDiff, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let a = extract_players_from_level(&world_dir(&account_id, &world_a)?)
+      .map_err(|e| format!("{world_a}: {e}"))?;
+    let b = extract_players_from_level(&world_dir(&account_id, &world_b)?)
+      .map_err(|e| format!("{world_b}: {e}"))?;
+    Ok(diff_rosters(a, b))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Run a swap on a temp copy of Level.sav and the two player files, check
+/// the result, and report — the real world is never written.
+#[tauri::command]
+async fn rehearse_swap(
+  account_id: String,
+  world_id: String,
+  first_id: String,
+  second_id: String,
+  match_strategy: Option<MatchStrategy>,
+) -> Result<SwapPreview, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let wpath = world_dir(&account_id, &world_id)?;
+    let stamp = chrono::Utc::now().format("%Y%m%d%H%M%S%f");
+    let temp = std::env::temp_dir().join(format!("palhost_rehearse_{stamp}"));
+    let _in_use = TempInUse::new(&temp);
+    let strategy = match_strategy.unwrap_or_default();
+    let result = rehearse_swap_in(&wpath, &temp, &normalize_id(&first_id), &normalize_id(&second_id), strategy);
+    let _ = fs::remove_dir_all(&temp);
+    result
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn rehearse_swap_in(
+  world_path: &Path,
+  temp: &Path,
+  first: &str,
+  second: &str,
+  match_strategy: MatchStrategy,
+) -> Result<SwapPreview, String> {
+  let temp_players = temp.join("Players");
+  fs::create_dir_all(&temp_players).map_err(|e| format!("Cannot create temp folder: {e}"))?;
+  fs::copy(world_path.join("Level.sav"), temp.join("Level.sav")).map_err(|e| format!("Cannot copy Level.sav: {e}"))?;
+  for id in [first, second] {
+    let src = world_path.join("Players").join(format!("{id}.sav"));
+    if src.exists() {
+      fs::copy(&src, temp_players.join(format!("{id}.sav"))).map_err(|e| format!("Cannot copy {id}.sav: {e}"))?;
+    }
+  }
+
+  let before = extract_players_from_level(temp)?;
+  let opts = SwapOptions { match_strategy, ..Default::default() };
+  if let Err(e) = swap_players_full(temp, &temp_players, first, second, &opts) {
+    return Ok(SwapPreview {
+      success: false,
+      error: Some(e),
+      issues: Vec::new(),
+      roster_diff: WorldDiff::default(),
+      matched_by: None,
+    });
+  }
+  let matched_by = load_world_config(&temp_players).swap_history.last().map(|r| r.match_strategy);
+
+  let mut issues = Vec::new();
+  for id in [first, second] {
+    let sav = temp_players.join(format!("{id}.sav"));
+    match read_player_sav_info(&sav) {
+      Err(e) => issues.push(format!("{id}.sav doesn't parse after the swap: {e}")),
+      Ok(_) if player_id_mismatch(&sav, id) => issues.push(format!("{id}.sav has a PlayerUId that doesn't match its filename.")),
+      Ok(_) => {}
+    }
+  }
+  let after = match extract_players_from_level(temp) {
+    Ok(after) => after,
+    Err(e) => {
+      issues.push(format!("Level.sav doesn't parse after the swap: {e}"));
+      Vec::new()
+    }
+  };
+  for id in [first, second] {
+    let uuid = filename_to_uuid(id);
+    if !after.is_empty() && !after.iter().any(|p| p.uuid.eq_ignore_ascii_case(&uuid)) {
+      issues.push(format!("No character for {uuid} in Level.sav after the swap."));
+    }
+  }
+
+  Ok(SwapPreview {
+    success: issues.is_empty(),
+    error: None,
+    issues,
+    roster_diff: diff_rosters(before, after),
+    matched_by,
+  })
+}
+
+fn diff_rosters(a: Vec<LevelPlayerInfo>, b: Vec<LevelPlayerInfo>) -> WorldDiff {
+  let mut b_by_uuid: HashMap<String, LevelPlayerInfo> =
+    b.into_iter().map(|p| (p.uuid.to_ascii_lowercase(), p)).collect();
+  let mut diff = WorldDiff::default();
+  for pa in a {
+    match b_by_uuid.remove(&pa.uuid.to_ascii_lowercase()) {
+      None => diff.only_in_a.push(pa.into()),
+      Some(pb) if pa.level != pb.level || pa.guild_name != pb.guild_name => diff.changed.push(RosterChange {
+        uuid: pa.uuid,
+        name: pa.name,
+        level_a: pa.level,
+        level_b: pb.level,
+        guild_a: pa.guild_name,
+        guild_b: pb.guild_name,
+      }),
+      Some(_) => {}
+    }
+  }
+  diff.only_in_b = b_by_uuid.into_values().map(RosterEntry::from).collect();
+  diff.only_in_b.sort_by(|x, y| x.uuid.cmp(&y.uuid));
+  diff
+}
+
+#[tauri::command]
+fn set_world_name(app: AppHandle, account_id: String, world_id: String, name: String) -> Result<Vec<WorldInfo>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let pdir = players_dir(&account_id, &world_id)?;
+  let mut wc = load_world_config(&pdir);
+  let trimmed = name.trim().to_string();
+  if trimmed.is_empty() {
+    wc.display_name = None;
+  } else {
+    wc.display_name = Some(trimmed);
+  }
+  save_world_config(&pdir, &wc)?;
+  worlds_with_counts(&app, &account_id, false)
+}
+
+#[tauri::command]
+fn set_world_favorite(app: AppHandle, account_id: String, world_id: String, favorite: bool) -> Result<Vec<WorldInfo>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let pdir = players_dir(&account_id, &world_id)?;
+  let mut wc = load_world_config(&pdir);
+  wc.favorite = favorite;
+  save_world_config(&pdir, &wc)?;
+  worlds_with_counts(&app, &account_id, false)
+}
+
+#[tauri::command]
+fn reset_world_name(app: AppHandle, account_id: String, world_id: String) -> Result<Vec<WorldInfo>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let pdir = players_dir(&account_id, &world_id)?;
+  let mut wc = load_world_config(&pdir);
+  wc.display_name = None;
+  save_world_config(&pdir, &wc)?;
+  worlds_with_counts(&app, &account_id, false)
+}
+
+fn reset_world_config_in(players_dir: &Path, keep_display_name: bool) -> Result<(), String> {
+  let old = load_world_config(players_dir);
+  let wc = WorldConfig {
+    display_name: old.display_name.filter(|_| keep_display_name),
+    ..Default::default()
+  };
+  save_world_config(players_dir, &wc)
+}
+
+/// Replace the world's `host_switcher.json` with a fresh config (names,
+/// host, swap history all gone), keeping the display name unless
+/// `keep_display_name` is false. Saves are not touched.
+#[tauri::command]
+async fn reset_world_config(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  keep_display_name: Option<bool>,
+) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    reset_world_config_in(&players_dir(&account_id, &world_id)?, keep_display_name.unwrap_or(true))?;
+    log_event(log::Level::Info, &format!("reset world config of {world_id}"));
+    get_players_sync(&a, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+#[tauri::command]
+async fn get_players(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  opts: Option<PlayerQuery>,
+) -> Result<Vec<Player>, String> {
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    let players = get_players_sync(&a, &account_id, &world_id)?;
+    match opts {
+      Some(query) => query_players(players, &query),
+      None => Ok(players),
+    }
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Apply a `PlayerQuery`: filter first, then a stable sort.
+fn query_players(mut players: Vec<Player>, query: &PlayerQuery) -> Result<Vec<Player>, String> {
+  if query.exclude_host {
+    players.retain(|p| !p.is_host);
+  }
+  if let Some(guild_id) = &query.guild_id {
+    players.retain(|p| p.group_id.as_deref().is_some_and(|g| g.eq_ignore_ascii_case(guild_id)));
+  }
+  let Some(sort_by) = query.sort_by.as_deref() else {
+    return Ok(players);
+  };
+  let compare: fn(&Player, &Player) -> std::cmp::Ordering = match sort_by {
+    "name" => |a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+    "level" => |a, b| a.level.cmp(&b.level),
+    "pals" => |a, b| a.pals_count.cmp(&b.pals_count),
+    "lastSeen" => |a, b| a.last_online_ticks.cmp(&b.last_online_ticks),
+    other => return Err(format!("Unknown sort key '{other}'.")),
+  };
+  players.sort_by(|a, b| if query.descending { compare(b, a) } else { compare(a, b) });
+  Ok(players)
+}
+
+fn get_players_sync(app: &AppHandle, account_id: &str, world_id: &str) -> Result<Vec<Player>, String> {
+  let players = players_in_world(&world_dir(account_id, world_id)?, &players_dir(account_id, world_id)?)?;
+
+  // Remember last-used account/world
+  let mut ac = load_app_config(app).unwrap_or_default();
+  ac.account_id = Some(account_id.to_string());
+  ac.world_id = Some(world_id.to_string());
+  let _ = save_app_config(app, &ac);
+
+  Ok(players)
+}
+
+/// The roster of one world, with Level.sav details where available.
+fn players_in_world(wpath: &Path, dir: &Path) -> Result<Vec<Player>, String> {
+  let player_ids = list_player_ids(dir);
+  if player_ids.is_empty() {
+    return Ok(Vec::new());
+  }
+  let wc = load_world_config(dir);
+  let host_id = resolve_host_id(&wc, &player_ids).ok_or("Host not found.")?;
+
+  // Read player info from Level.sav
+  let level_info = match extract_players_from_level(wpath) {
+    Ok(info) => info,
+    Err(e) => {
+      log_event(log::Level::Error, &format!("Failed to parse Level.sav: {e}"));
+      Vec::new()
+    }
+  };
+
+  let mut players = build_players(&player_ids, &host_id, &level_info);
+  for p in &mut players {
+    p.id_mismatch = player_id_mismatch(&dir.join(format!("{}.sav", p.id)), &p.id);
+    if p.id_mismatch {
+      log_event(log::Level::Warn, &format!("{}.sav has a PlayerUId that doesn't match its filename", p.id));
+    }
+  }
+  Ok(players)
+}
+
+/// Just the host, without building the rest of the roster. `None` if the
+/// world has no player files. Cheaper than `get_players`: only the host's
+/// own entries in Level.sav are decoded.
+#[tauri::command]
+async fn get_host(account_id: String, world_id: String) -> Result<Option<Player>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    host_in_world(&world_dir(&account_id, &world_id)?, &players_dir(&account_id, &world_id)?)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// The host of the world at `world_path`. Taken from `LEVEL_CACHE` when
+/// `get_players` or `prewarm_world` already parsed this Level.sav; otherwise
+/// Level.sav is streamed and only the host's character, their pals and the
+/// groups are decoded. That partial parse isn't cached.
+fn host_in_world(world_path: &Path, players_dir: &Path) -> Result<Option<Player>, String> {
+  let player_ids = list_player_ids(players_dir);
+  if player_ids.is_empty() {
+    return Ok(None);
+  }
+  let host_id = resolve_host_id(&load_world_config(players_dir), &player_ids).ok_or("Host not found.")?;
+  let level_info = match host_level_info(world_path, &host_id) {
+    Ok(info) => info,
+    Err(e) => {
+      log_event(log::Level::Error, &format!("Failed to read the host from Level.sav: {e}"));
+      Vec::new()
+    }
+  };
+  let mut host = build_players(std::slice::from_ref(&host_id), &host_id, &level_info).pop();
+  if let Some(p) = host.as_mut() {
+    p.id_mismatch = player_id_mismatch(&players_dir.join(format!("{}.sav", p.id)), &p.id);
+  }
+  Ok(host)
+}
+
+/// Level.sav info for `host_id` alone, from the cache or a targeted scan.
+fn host_level_info(world_path: &Path, host_id: &str) -> Result<Vec<LevelPlayerInfo>, String> {
+  let level_sav = world_path.join("Level.sav");
+  let players = match cached_level_players(&level_sav) {
+    Some(players) => players,
+    None => {
+      let file = fs::File::open(&level_sav).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+      let uuid = player_id_to_uuid(host_id)?;
+      let json = gvas::scan_player_world_data(std::io::BufReader::new(file), &uuid, &["GroupSaveDataMap", "GameTimeSaveData"])?;
+      players_from_level_json(&json)
+    }
+  };
+  Ok(players.into_iter().filter(|p| p.filename == host_id).collect())
+}
+
+/// List a player's party and palbox contents (read-only).
+#[tauri::command]
+async fn get_pal_containers(account_id: String, world_id: String, player_id: String) -> Result<Vec<PalContainer>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let wpath = world_dir(&account_id, &world_id)?;
+    let id = normalize_id(&player_id);
+    player_id_to_uuid(&id)?;
+    let sav = players_dir(&account_id, &world_id)?.join(format!("{id}.sav"));
+    read_pal_containers(&wpath, &sav)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+#[tauri::command]
+async fn set_host_player(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  player_id: String,
+  reconstruct_missing: Option<bool>,
+  match_strategy: Option<MatchStrategy>,
+  fix_base_camps: Option<bool>,
+) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    let opts = SwapOptions {
+      reconstruct_missing: reconstruct_missing.unwrap_or(false),
+      match_strategy: match_strategy.unwrap_or_default(),
+      fix_base_camps: fix_base_camps.unwrap_or(false),
+      ..Default::default()
+    };
+    set_host_player_sync(&a, &account_id, &world_id, &player_id, opts)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn set_host_player_sync(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  player_id: &str,
+  options: SwapOptions,
+) -> Result<Vec<Player>, String> {
+  let dir = players_dir(account_id, world_id)?;
+  let wpath = world_dir(account_id, world_id)?;
+  let broot = backup_root(app, account_id, world_id)?;
+  let cancel = app.state::<SwapCancels>().register(&wpath);
+  let opts = SwapOptions {
+    auto_backup: load_app_config(app)?.auto_backup,
+    progress: Some((app, 0.0, 90.0)),
+    backup_root: Some(&broot),
+    cancel: Some(&cancel.flag),
+    ..options
+  };
+  let started = std::time::Instant::now();
+  if set_host_in_dir(&wpath, &dir, player_id, &opts)? {
+    record_operation_time(app, "swap", &wpath, started.elapsed());
+    let _ = app.emit("swap-progress", ProgressPayload { percent: 95.0, message: "Reloading players…".into() });
+  }
+  get_players_sync(app, account_id, world_id)
+}
+
+/// Swap `player_id` into the host slot. Returns false (and touches nothing)
+/// if they're already the host.
+fn set_host_in_dir(world_path: &Path, players_dir: &Path, player_id: &str, opts: &SwapOptions) -> Result<bool, String> {
+  let player_ids = list_player_ids(players_dir);
+  let wc = load_world_config(players_dir);
+  let host_id = host_slot_in(&wc, &player_ids, &host_slot_ids())
+    .ok_or("No player file is in a host slot; repair the missing host first.")?;
+  let target_id = normalize_id(player_id);
+  if host_id == target_id {
+    return Ok(false);
+  }
+  swap_players_full(world_path, players_dir, &host_id, &target_id, opts)?;
+  // The new host now lives in the host's file
+  let mut wc = load_world_config(players_dir);
+  wc.host_id = Some(host_id);
+  save_world_config(players_dir, &wc)?;
+  Ok(true)
+}
+
+/// Per-world outcome of `set_host_in_worlds`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchHostResult {
+  world_id: String,
+  error: Option<String>,
+}
+
+/// Find a player file by id (flat or dashed) or by name — the in-game name
+/// from Level.sav or a custom one from the world config, case-insensitive.
+fn resolve_player_ref(world_path: &Path, players_dir: &Path, name_or_id: &str) -> Result<String, String> {
+  let ids = list_player_ids(players_dir);
+  let as_id = uuid_to_filename(&normalize_id(name_or_id));
+  if ids.contains(&as_id) {
+    return Ok(as_id);
+  }
+  let wanted = name_or_id.trim();
+  let wc = load_world_config(players_dir);
+  let level = extract_players_from_level(world_path).unwrap_or_default();
+  let matches: Vec<&String> = ids
+    .iter()
+    .filter(|id| {
+      let in_game = level.iter().find(|p| p.filename == **id).map(|p| p.name.as_str());
+      let custom = wc.players.get(*id).map(String::as_str);
+      [in_game, custom].into_iter().flatten().any(|n| n.trim().eq_ignore_ascii_case(wanted))
+    })
+    .collect();
+  match matches.as_slice() {
+    [id] => Ok((*id).clone()),
+    [] => Err(format!("No player named or with id '{wanted}'.")),
+    _ => Err(format!("'{wanted}' matches {} players.", matches.len())),
+  }
+}
+
+fn set_host_by_ref(app: &AppHandle, account_id: &str, world_id: &str, name_or_id: &str, auto_backup: bool) -> Result<(), String> {
+  let _guard = lock_world(app, account_id, world_id)?;
+  wait_for_idle_if_enabled(app, account_id, world_id)?;
+  let dir = players_dir(account_id, world_id)?;
+  let wpath = world_dir(account_id, world_id)?;
+  let broot = backup_root(app, account_id, world_id)?;
+  let player_id = resolve_player_ref(&wpath, &dir, name_or_id)?;
+  let opts = SwapOptions { auto_backup, backup_root: Some(&broot), ..Default::default() };
+  set_host_in_dir(&wpath, &dir, &player_id, &opts).map(|_| ())
+}
+
+/// Make the same player host in several worlds of one account. A failing
+/// world doesn't stop the batch; each gets its own result and a
+/// `batch-host-progress` event.
+#[tauri::command]
+async fn set_host_in_worlds(
+  app: AppHandle,
+  account_id: String,
+  world_ids: Vec<String>,
+  player_name_or_id: String,
+) -> Result<Vec<BatchHostResult>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let auto_backup = load_app_config(&app)?.auto_backup;
+    let total = world_ids.len();
+    let mut results = Vec::with_capacity(total);
+    for (i, world_id) in world_ids.into_iter().enumerate() {
+      let outcome = set_host_by_ref(&app, &account_id, &world_id, &player_name_or_id, auto_backup);
+      if let Err(e) = &outcome {
+        log_event(log::Level::Warn, &format!("set host in {world_id} failed: {e}"));
+      }
+      let status = if outcome.is_ok() { "done" } else { "failed" };
+      let _ = app.emit(
+        "batch-host-progress",
+        ProgressPayload {
+          percent: ((i + 1) * 100 / total.max(1)) as f64,
+          message: format!("{world_id}: {status} ({}/{total})", i + 1),
+        },
+      );
+      results.push(BatchHostResult { world_id, error: outcome.err() });
+    }
+    Ok(results)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+#[tauri::command]
+async fn swap_players(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  first_id: String,
+  second_id: String,
+  reconstruct_missing: Option<bool>,
+  match_strategy: Option<MatchStrategy>,
+) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    let reconstruct = reconstruct_missing.unwrap_or(false);
+    swap_players_sync(&a, &account_id, &world_id, &first_id, &second_id, reconstruct, match_strategy.unwrap_or_default())
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn swap_players_sync(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  first_id: &str,
+  second_id: &str,
+  reconstruct_missing: bool,
+  match_strategy: MatchStrategy,
+) -> Result<Vec<Player>, String> {
+  let dir = players_dir(account_id, world_id)?;
+  let wpath = world_dir(account_id, world_id)?;
+  let first = normalize_id(first_id);
+  let second = normalize_id(second_id);
+  let broot = backup_root(app, account_id, world_id)?;
+  let cancel = app.state::<SwapCancels>().register(&wpath);
+  let opts = SwapOptions {
+    reconstruct_missing,
+    auto_backup: load_app_config(app)?.auto_backup,
+    progress: Some((app, 0.0, 90.0)),
+    backup_root: Some(&broot),
+    match_strategy,
+    cancel: Some(&cancel.flag),
+    ..Default::default()
+  };
+  let started = std::time::Instant::now();
+  swap_players_full(&wpath, &dir, &first, &second, &opts)?;
+  record_operation_time(app, "swap", &wpath, started.elapsed());
+  let _ = app.emit("swap-progress", ProgressPayload { percent: 95.0, message: "Reloading players…".into() });
+  get_players_sync(app, account_id, world_id)
+}
+
+
+
+/// Give a world with no file in any host slot a proper host by moving the
+/// chosen player into the first host slot (a custom one if set, else 0001).
+#[tauri::command]
+async fn repair_missing_host(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  chosen_player_id: String,
+) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    repair_missing_host_sync(&a, &account_id, &world_id, &chosen_player_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn repair_missing_host_sync(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  chosen_player_id: &str,
+) -> Result<Vec<Player>, String> {
+  let dir = players_dir(account_id, world_id)?;
+  let wpath = world_dir(account_id, world_id)?;
+  let broot = backup_root(app, account_id, world_id)?;
+  repair_missing_host_in_dir(&wpath, &dir, &broot, chosen_player_id, &host_slot_ids())?;
+  get_players_sync(app, account_id, world_id)
+}
+
+/// Back up, then move `chosen_player_id` into `slots[0]`. Fails if any of
+/// `slots` is already taken.
+fn repair_missing_host_in_dir(
+  world_path: &Path,
+  players_dir: &Path,
+  backup_root: &Path,
+  chosen_player_id: &str,
+  slots: &[String],
+) -> Result<(), String> {
+  let player_ids = list_player_ids(players_dir);
+  if player_ids.iter().any(|id| slots.contains(id)) {
+    return Err("This world already has a host slot.".into());
+  }
+  let chosen = normalize_id(chosen_player_id);
+  if !player_ids.contains(&chosen) {
+    return Err("Player not found.".into());
+  }
+  let slot = slots.first().ok_or("No host slot configured.")?;
+  let mut wc = load_world_config(players_dir);
+  backup_files(players_dir, world_path, backup_root, std::slice::from_ref(&chosen), &backup_snapshot(&wc, "pre-repair"), false)?;
+  move_player_to_slot(world_path, players_dir, &chosen, slot)?;
+
+  // Names are keyed by slot, so follow the file
+  if let Some(name) = wc.players.remove(&chosen) {
+    wc.players.insert(slot.clone(), name);
+  }
+  if let Some(orig) = wc.original_names.remove(&chosen) {
+    wc.original_names.insert(slot.clone(), orig);
+  }
+  save_world_config(players_dir, &wc)
+}
+
+/// Fix a player file whose internal PlayerUId disagrees with its filename.
+/// Backs the file up first, only when something changes.
+#[tauri::command]
+async fn resync_player_id(app: AppHandle, account_id: String, world_id: String, player_id: String) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    let dir = players_dir(&account_id, &world_id)?;
+    let id = normalize_id(&player_id);
+    let sav = dir.join(format!("{id}.sav"));
+    if !sav.exists() {
+      return Err("Player not found.".into());
+    }
+    if player_id_mismatch(&sav, &id) {
+      let wc = load_world_config(&dir);
+      let wpath = world_dir(&account_id, &world_id)?;
+      backup_files(&dir, &wpath, &backup_root(&a, &account_id, &world_id)?, &[id], &backup_snapshot(&wc, "pre-repair"), false)?;
+      resync_player_file(&sav, &player_id)?;
+    }
+    get_players_sync(&a, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Make a player file's PlayerUId and IndividualId.PlayerUId agree with its
+/// filename. Backs the file up first, only when something changes.
+#[tauri::command]
+async fn repair_player_ids(app: AppHandle, account_id: String, world_id: String, player_id: String) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    let dir = players_dir(&account_id, &world_id)?;
+    let id = normalize_id(&player_id);
+    if !dir.join(format!("{id}.sav")).exists() {
+      return Err("Player not found.".into());
+    }
+    let expected = filename_to_uuid(&id);
+    let edit = |json: &mut Value| {
+      let replaced = repair_player_ids_in_json(json, &expected)?;
+      Ok((!replaced.is_empty()).then_some(replaced))
+    };
+    // Both ids must read back as the filename's
+    let check = |sav: &SavRewrite, replaced: Vec<String>| {
+      let save = &sav.reparsed["properties"]["SaveData"]["value"];
+      for uid in [&save["PlayerUId"]["value"], &save["IndividualId"]["value"]["PlayerUId"]["value"]] {
+        if !uid.as_str().is_some_and(|u| u.eq_ignore_ascii_case(&expected)) {
+          return Err(format!("Edited {id}.sav still has a mismatched PlayerUId."));
+        }
+      }
+      Ok(replaced)
+    };
+    if let Some(replaced) = rewrite_sav(&a, &account_id, &world_id, SavTarget::Player(&id), "pre-repair", edit, check)? {
+      log_event(log::Level::Info, &format!("repaired ids in {id}.sav: {} -> {expected}", replaced.join(", ")));
+    }
+    get_players_sync(&a, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Flat-hex player file id → GVAS UUID, normalized the same way as swaps.
+#[tauri::command]
+fn to_player_uuid(filename: String) -> Result<String, String> {
+  player_id_to_uuid(&normalize_id(filename.trim_end_matches(".sav")))
+}
+
+/// GVAS UUID → flat-hex player file id (no `.sav`).
+#[tauri::command]
+fn to_filename(uuid: String) -> String {
+  uuid_to_filename(uuid.trim())
+}
+
+/// True for a 32-hex-digit player file id (any case, surrounding whitespace ignored).
+#[tauri::command]
+fn is_valid_player_id(s: String) -> bool {
+  is_hex_id(&normalize_id(&s))
+}
+
+/// Number of player files in one world, for a single-world refresh.
+#[tauri::command]
+fn get_player_count(account_id: String, world_id: String) -> Result<usize, String> {
+  Ok(list_player_ids(&players_dir(&account_id, &world_id)?).len())
+}
+
+#[tauri::command]
+fn get_swap_history(account_id: String, world_id: String) -> Result<Vec<SwapRecord>, String> {
+  let dir = players_dir(&account_id, &world_id)?;
+  Ok(load_world_config(&dir).swap_history)
+}
+
+/// Drop the oldest swaps beyond the world's `swap_history_limit`.
+fn trim_swap_history(wc: &mut WorldConfig) {
+  let limit = wc.swap_history_limit.unwrap_or(DEFAULT_SWAP_HISTORY_LIMIT);
+  let excess = wc.swap_history.len().saturating_sub(limit);
+  wc.swap_history.drain(..excess);
+}
+
+/// Set how many swaps the world keeps (`None` restores the default) and
+/// trim the existing history to fit.
+#[tauri::command]
+fn set_swap_history_limit(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  limit: Option<usize>,
+) -> Result<Vec<SwapRecord>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let dir = players_dir(&account_id, &world_id)?;
+  let mut wc = load_world_config(&dir);
+  wc.swap_history_limit = limit;
+  trim_swap_history(&mut wc);
+  save_world_config(&dir, &wc)?;
+  Ok(wc.swap_history)
+}
+
+#[tauri::command]
+fn clear_swap_history(app: AppHandle, account_id: String, world_id: String) -> Result<(), String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let dir = players_dir(&account_id, &world_id)?;
+  let mut wc = load_world_config(&dir);
+  wc.swap_history.clear();
+  save_world_config(&dir, &wc)
+}
+
+#[tauri::command]
+fn create_backup(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  player_ids: Vec<String>,
+  full: Option<bool>,
+) -> Result<String, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let dir = players_dir(&account_id, &world_id)?;
+  let wpath = world_dir(&account_id, &world_id)?;
+  let wc = load_world_config(&dir);
+  let broot = backup_root(&app, &account_id, &world_id)?;
+  let backup_dir = backup_files(&dir, &wpath, &broot, &player_ids, &backup_snapshot(&wc, "manual"), full.unwrap_or(false))?;
+  Ok(backup_dir.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+fn list_backups(app: AppHandle, account_id: String, world_id: String) -> Result<Vec<String>, String> {
+  Ok(list_backups_dir(&backup_root(&app, &account_id, &world_id)?))
+}
+
+#[tauri::command]
+fn list_backups_detailed(app: AppHandle, account_id: String, world_id: String) -> Result<Vec<BackupEntry>, String> {
+  Ok(list_backup_entries(&backup_root(&app, &account_id, &world_id)?))
+}
+
+/// Backups `offset..offset + limit` (newest first) plus the count and size
+/// of all of them.
+fn backup_page(backup_root: &Path, offset: usize, limit: usize) -> BackupPage {
+  let all = list_backup_entries(backup_root);
+  let total = all.len();
+  let total_bytes = all.iter().map(|b| dir_size(&backup_root.join(&b.name))).sum();
+  let entries = all.into_iter().skip(offset).take(limit).collect();
+  BackupPage { entries, total, total_bytes }
+}
+
+#[tauri::command]
+async fn list_backups_paged(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  offset: usize,
+  limit: usize,
+) -> Result<BackupPage, String> {
+  let broot = backup_root(&app, &account_id, &world_id)?;
+  tauri::async_runtime::spawn_blocking(move || Ok(backup_page(&broot, offset, limit)))
+    .await
+    .map_err(|e| format!("Task error: {e}"))?
+}
+
+#[tauri::command]
+async fn restore_backup(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  backup_name: String,
+  preserve_display_name: Option<bool>,
+) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    restore_backup_sync(&a, &account_id, &world_id, &backup_name, preserve_display_name.unwrap_or(true))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn restore_backup_sync(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  backup_name: &str,
+  preserve_display_name: bool,
+) -> Result<Vec<Player>, String> {
+  let dir = players_dir(account_id, world_id)?;
+  let wpath = world_dir(account_id, world_id)?;
+  let backup_dir = backup_root(app, account_id, world_id)?.join(backup_name);
+  if !backup_dir.exists() {
+    return Err("Backup not found.".to_string());
+  }
+
+  restore_backup_files(&backup_dir, &dir, &wpath, preserve_display_name)?;
+  get_players_sync(app, account_id, world_id)
+}
+
+/// What `restore_backup` would do (see `preview_restore`).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestorePreview {
+  files: Vec<RestoreFilePreview>,
+  has_snapshot: bool,
+  current_display_name: Option<String>,
+  /// Display name the world ends up with after the restore
+  restored_display_name: Option<String>,
+  display_name_changes: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestoreFilePreview {
+  /// Relative to the world folder (`Level.sav`, `Players/<id>.sav`)
+  path: String,
+  overwrites: bool,
+}
+
+/// The `.sav` names and snapshot in a backup folder, or in a backup ZIP
+/// laid out as `<backup>/<file>`.
+fn backup_contents(backup: &Path) -> Result<(Vec<String>, Option<BackupSnapshot>), String> {
+  if backup.is_dir() {
+    let savs = fs::read_dir(backup)
+      .map_err(|err| err.to_string())?
+      .flatten()
+      .filter_map(|e| e.file_name().into_string().ok())
+      .filter(|n| n.ends_with(".sav"))
+      .collect();
+    return Ok((savs, read_backup_snapshot(backup)));
+  }
+  let zip_file = fs::File::open(backup).map_err(|e| format!("Cannot open ZIP: {e}"))?;
+  let mut archive = zip::ZipArchive::new(zip_file).map_err(|e| format!("Invalid ZIP: {e}"))?;
+  let mut savs = Vec::new();
+  let mut snapshot = None;
+  for i in 0..archive.len() {
+    let mut entry = archive.by_index(i).map_err(|e| format!("ZIP read error: {e}"))?;
+    let Some(name) = entry.enclosed_name().and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string())) else {
+      continue;
+    };
+    if name.ends_with(".sav") {
+      savs.push(name);
+    } else if name == "config_snapshot.json" {
+      let mut raw = String::new();
+      entry.read_to_string(&mut raw).map_err(|e| format!("ZIP read error: {e}"))?;
+      snapshot = serde_json::from_str(&raw).ok();
+    }
+  }
+  Ok((savs, snapshot))
+}
+
+fn preview_restore_files(
+  backup: &Path,
+  players_dir: &Path,
+  world_path: &Path,
+  preserve_display_name: bool,
+) -> Result<RestorePreview, String> {
+  let (mut savs, snapshot) = backup_contents(backup)?;
+  savs.sort();
+  let files = savs
+    .into_iter()
+    .map(|name| {
+      // Same routing as `restore_backup_files`
+      let (dest, path) = if WORLD_ROOT_SAVS.contains(&name.as_str()) {
+        (world_path.join(&name), name)
+      } else {
+        (players_dir.join(&name), format!("Players/{name}"))
+      };
+      RestoreFilePreview { path, overwrites: dest.exists() }
+    })
+    .collect();
+  let current_display_name = load_world_config(players_dir).display_name;
+  let restored_display_name = match &snapshot {
+    Some(snap) if !preserve_display_name => snap.display_name.clone(),
+    _ => current_display_name.clone(),
+  };
+  Ok(RestorePreview {
+    files,
+    has_snapshot: snapshot.is_some(),
+    display_name_changes: restored_display_name != current_display_name,
+    current_display_name,
+    restored_display_name,
+  })
+}
+
+/// List what restoring `backup_name` would overwrite or add, without
+/// touching anything. `preserve_display_name` mirrors `restore_backup`.
+#[tauri::command]
+fn preview_restore(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  backup_name: String,
+  preserve_display_name: Option<bool>,
+) -> Result<RestorePreview, String> {
+  let dir = players_dir(&account_id, &world_id)?;
+  let wpath = world_dir(&account_id, &world_id)?;
+  let backup = backup_root(&app, &account_id, &world_id)?.join(&backup_name);
+  if !backup.exists() {
+    return Err("Backup not found.".to_string());
+  }
+  preview_restore_files(&backup, &dir, &wpath, preserve_display_name.unwrap_or(true))
+}
+
+/// Copy a backup's saves back into the world and restore its config snapshot.
+/// With `preserve_display_name` the world keeps its current display name.
+fn restore_backup_files(
+  backup_dir: &Path,
+  players_dir: &Path,
+  world_path: &Path,
+  preserve_display_name: bool,
+) -> Result<(), String> {
+  // Restore .sav files
+  let entries = fs::read_dir(backup_dir).map_err(|err| err.to_string())?;
+  for entry in entries.flatten() {
+    let file_path = entry.path();
+    if let Some(name) = file_path.file_name().and_then(|value| value.to_str()) {
+      if name.ends_with(".sav") {
+        if WORLD_ROOT_SAVS.contains(&name) {
+          // Restore Level.sav / WorldOption.sav / LocalData.sav to world root
+          let dest = world_path.join(name);
+          fs::copy(&file_path, dest).map_err(|err| err.to_string())?;
+        } else {
+          // Restore player .sav to Players dir
+          let dest = players_dir.join(name);
+          fs::copy(&file_path, dest).map_err(|err| err.to_string())?;
+        }
+      }
+    }
+  }
+
+  // Restore config snapshot into world-local config
+  let snapshot_path = backup_dir.join("config_snapshot.json");
+  if snapshot_path.exists() {
+    let raw = fs::read_to_string(&snapshot_path).map_err(|err| err.to_string())?;
+    if let Ok(snapshot) = serde_json::from_str::<BackupSnapshot>(&raw) {
+      let mut wc = load_world_config(players_dir);
+      wc.players = snapshot.players;
+      wc.original_names = snapshot.original_names;
+      wc.host_id = snapshot.host_id;
+      if !preserve_display_name {
+        wc.display_name = snapshot.display_name;
+      }
+      save_world_config(players_dir, &wc)?;
+    }
+  }
+  Ok(())
+}
+
+/// Total size of the files under `path`.
+fn dir_size(path: &Path) -> u64 {
+  WalkDir::new(path)
+    .into_iter()
+    .flatten()
+    .filter_map(|e| e.metadata().ok())
+    .filter(|m| m.is_file())
+    .map(|m| m.len())
+    .sum()
+}
+
+fn account_world_ids(account_id: &str) -> Result<Vec<String>, String> {
+  let root = account_dir(account_id)?;
+  let mut ids: Vec<String> = list_dirs(&root).into_iter().filter(|w| is_world_dir(&root.join(w))).collect();
+  ids.sort();
+  Ok(ids)
+}
+
+/// Every backup of every world in the account, newest first per world.
+#[tauri::command]
+async fn list_all_backups(app: AppHandle, account_id: String) -> Result<Vec<AccountBackupEntry>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let mut out = Vec::new();
+    for world_id in account_world_ids(&account_id)? {
+      let broot = backup_root(&app, &account_id, &world_id)?;
+      for backup in list_backup_entries(&broot) {
+        out.push(AccountBackupEntry {
+          size_bytes: dir_size(&broot.join(&backup.name)),
+          world_id: world_id.clone(),
+          backup,
+        });
+      }
+    }
+    Ok(out)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Keep only the newest `keep_per_world` backups of each world in the
+/// account, locking each world while its backups are pruned. Busy worlds
+/// and backups that can't be deleted are skipped (and logged). Returns how
+/// many backups were deleted.
+#[tauri::command]
+async fn prune_backups(app: AppHandle, account_id: String, keep_per_world: usize) -> Result<usize, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let mut deleted = 0;
+    for world_id in account_world_ids(&account_id)? {
+      let _guard = match lock_world(&app, &account_id, &world_id) {
+        Ok(guard) => guard,
+        Err(e) => {
+          log_event(log::Level::Warn, &format!("not pruning backups of {world_id}: {e}"));
+          continue;
+        }
+      };
+      let broot = backup_root(&app, &account_id, &world_id)?;
+      for backup in list_backup_entries(&broot).into_iter().skip(keep_per_world) {
+        match fs::remove_dir_all(broot.join(&backup.name)) {
+          Ok(()) => deleted += 1,
+          Err(e) => log_event(log::Level::Warn, &format!("cannot delete backup {world_id}/{}: {e}", backup.name)),
+        }
+      }
+    }
+    log_event(log::Level::Info, &format!("pruned {deleted} backups in account {account_id}"));
+    Ok(deleted)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Zip a backup folder as `<backup_name>/<file>` entries.
+fn zip_backup_dir(backup_dir: &Path, dest: &Path) -> Result<(), String> {
+  let name = backup_dir.file_name().and_then(|n| n.to_str()).ok_or("Invalid backup folder name.")?;
+  let file = fs::File::create(dest).map_err(|e| format!("Cannot create ZIP file: {e}"))?;
+  let mut zip = zip::ZipWriter::new(file);
+  let options = SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Deflated)
+    .unix_permissions(0o644);
+  let mut files: Vec<PathBuf> = fs::read_dir(backup_dir)
+    .map_err(|e| format!("Cannot read backup: {e}"))?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| p.is_file())
+    .collect();
+  files.sort();
+  for path in files {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    zip.start_file(format!("{name}/{file_name}"), options)
+      .map_err(|e| format!("Error adding file to ZIP: {e}"))?;
+    let buf = fs::read(&path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    zip.write_all(&buf).map_err(|e| format!("ZIP write error: {e}"))?;
+  }
+  zip.finish().map_err(|e| format!("Error finalizing ZIP: {e}"))?;
+  Ok(())
+}
+
+/// Zip a diagnostics bundle for a bug report: recent logs, the world config,
+/// Level.sav's header, a `scan_world` report and `metadata`. Save files are
+/// never included; only their names and sizes are listed.
+fn write_log_bundle(dest: &Path, world_path: &Path, players_dir: &Path, mut metadata: Value) -> Result<(), String> {
+  let pretty = |v: &Value| serde_json::to_vec_pretty(v).map_err(|e| e.to_string());
+  let logs: Vec<LogEntry> = LOG_BUFFER.lock().map(|b| b.iter().cloned().collect()).unwrap_or_default();
+  let header = match fs::read(world_path.join("Level.sav")).map_err(|e| e.to_string()).and_then(|d| gvas::sav_header(&d, 128)) {
+    Ok((header, _, save_type)) => serde_json::json!({ "header": header, "saveType": save_type }),
+    Err(e) => serde_json::json!({ "error": e }),
+  };
+  let scan = scan_world_dir(world_path, players_dir, &|_, _| {});
+  let files: Vec<Value> = WalkDir::new(world_path)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter(|e| e.file_type().is_file())
+    .filter_map(|e| {
+      let rel = e.path().strip_prefix(world_path).ok()?.to_string_lossy().replace('\\', "/");
+      Some(serde_json::json!({ "path": rel, "size": e.metadata().ok()?.len() }))
+    })
+    .collect();
+  metadata["files"] = Value::Array(files);
+
+  let mut entries: Vec<(&str, Vec<u8>)> = vec![
+    ("logs.json", serde_json::to_vec_pretty(&logs).map_err(|e| e.to_string())?),
+    ("level_header.json", pretty(&header)?),
+    ("scan.json", serde_json::to_vec_pretty(&scan).map_err(|e| e.to_string())?),
+    ("metadata.json", pretty(&metadata)?),
+  ];
+  if let Ok(config) = fs::read(players_dir.join(WORLD_CONFIG_FILE)) {
+    entries.push((WORLD_CONFIG_FILE, config));
+  }
+
+  let file = fs::File::create(dest).map_err(|e| format!("Cannot create ZIP file: {e}"))?;
+  let mut zip = zip::ZipWriter::new(file);
+  let options = SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Deflated)
+    .unix_permissions(0o644);
+  for (name, data) in entries {
+    zip.start_file(name, options).map_err(|e| format!("Error adding {name} to ZIP: {e}"))?;
+    zip.write_all(&data).map_err(|e| format!("ZIP write error: {e}"))?;
+  }
+  zip.finish().map_err(|e| format!("Error finalizing ZIP: {e}"))?;
+  Ok(())
+}
+
+/// Export a diagnostics ZIP for a bug report (see `write_log_bundle`).
+/// Returns the ZIP path.
+#[tauri::command]
+async fn export_log_bundle(app: AppHandle, account_id: String, world_id: String, out_path: String) -> Result<String, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let dir = players_dir(&account_id, &world_id)?;
+    let wpath = world_dir(&account_id, &world_id)?;
+    let metadata = serde_json::json!({
+      "appVersion": app.package_info().version.to_string(),
+      "os": std::env::consts::OS,
+      "arch": std::env::consts::ARCH,
+      "createdAt": chrono::Utc::now().to_rfc3339(),
+      "accountId": account_id,
+      "worldId": world_id,
+      "playerCount": list_player_ids(&dir).len(),
+      "settings": load_app_config(&app).map(|c| AppSettings::from_config(&c)).ok(),
+    });
+    write_log_bundle(Path::new(&out_path), &wpath, &dir, metadata)?;
+    log_event(log::Level::Info, &format!("exported log bundle to {out_path}"));
+    Ok(out_path)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Extract a ZIP made by `zip_backup_dir` into `backup_root/<name>`.
+/// The archive must hold a single folder of `.sav` files (plus an optional
+/// `config_snapshot.json`). Returns the backup name.
+fn import_backup_zip(zip_path: &Path, backup_root: &Path) -> Result<String, String> {
+  let zip_file = fs::File::open(zip_path).map_err(|e| format!("Cannot open ZIP: {e}"))?;
+  let mut archive = zip::ZipArchive::new(zip_file).map_err(|e| format!("Invalid ZIP: {e}"))?;
+
+  let mut name: Option<String> = None;
+  let mut has_sav = false;
+  for i in 0..archive.len() {
+    let entry = archive.by_index(i).map_err(|e| format!("ZIP read error: {e}"))?;
+    if entry.is_dir() {
+      continue;
+    }
+    let path = entry.enclosed_name().ok_or_else(|| format!("Unsafe path in ZIP: {}", entry.name()))?;
+    let parts: Vec<String> = path.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    let [folder, file] = parts.as_slice() else {
+      return Err(format!("Unexpected entry in backup ZIP: {}", entry.name()));
+    };
+    if name.get_or_insert_with(|| folder.clone()) != folder {
+      return Err("Backup ZIP contains more than one folder.".into());
+    }
+    if file.ends_with(".sav") {
+      has_sav = true;
+    } else if file != "config_snapshot.json" {
+      return Err(format!("Unexpected file in backup ZIP: {file}"));
+    }
+  }
+  let name = name.filter(|_| has_sav).ok_or("Backup ZIP contains no .sav files.")?;
+  let target = backup_root.join(&name);
+  if target.exists() {
+    return Err(format!("A backup named '{name}' already exists."));
+  }
+
+  fs::create_dir_all(&target).map_err(|e| format!("Cannot create backup folder: {e}"))?;
+  for i in 0..archive.len() {
+    let mut entry = archive.by_index(i).map_err(|e| format!("ZIP read error: {e}"))?;
+    if entry.is_dir() {
+      continue;
+    }
+    let Some(file_name) = entry.enclosed_name().and_then(|p| p.file_name().map(|n| n.to_owned())) else {
+      continue;
+    };
+    let mut out = fs::File::create(target.join(file_name)).map_err(|e| format!("Cannot create file: {e}"))?;
+    std::io::copy(&mut entry, &mut out).map_err(|e| format!("Extract error: {e}"))?;
+  }
+  Ok(name)
+}
+
+/// Archive one backup as a ZIP at `dest_path` for off-machine storage.
+#[tauri::command]
+async fn copy_backup_to(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  backup_name: String,
+  dest_path: String,
+) -> Result<String, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let backup_dir = backup_root(&app, &account_id, &world_id)?.join(&backup_name);
+    if !backup_dir.is_dir() {
+      return Err("Backup not found.".to_string());
+    }
+    zip_backup_dir(&backup_dir, Path::new(&dest_path))?;
+    Ok(dest_path)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Bring a ZIP made by `copy_backup_to` back into the world's backups.
+#[tauri::command]
+async fn import_backup_from(app: AppHandle, account_id: String, world_id: String, zip_path: String) -> Result<Vec<String>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    let broot = backup_root(&app, &account_id, &world_id)?;
+    let name = import_backup_zip(Path::new(&zip_path), &broot)?;
+    log_event(log::Level::Info, &format!("imported backup {name} into {world_id}"));
+    Ok(list_backups_dir(&broot))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Restore straight from a ZIP made by `copy_backup_to`, without importing
+/// it into the backups first. The current world is backed up before.
+#[tauri::command]
+async fn restore_from_external_zip(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  zip_path: String,
+  preserve_display_name: Option<bool>,
+) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    let temp = std::env::temp_dir().join(format!("{TEMP_TRANSFER_PREFIX}restore_{}", uuid::Uuid::new_v4().simple()));
+    let _in_use = TempInUse::new(&temp);
+    let restored = restore_from_zip_via(&a, &account_id, &world_id, Path::new(&zip_path), &temp, preserve_display_name.unwrap_or(true));
+    let _ = fs::remove_dir_all(&temp);
+    restored?;
+    log_event(log::Level::Info, &format!("restored {world_id} from {zip_path}"));
+    get_players_sync(&a, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Extract a backup ZIP under `temp`, check it and restore it over the world.
+fn restore_from_zip_via(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  zip_path: &Path,
+  temp: &Path,
+  preserve_display_name: bool,
+) -> Result<(), String> {
+  let dir = players_dir(account_id, world_id)?;
+  let wpath = world_dir(account_id, world_id)?;
+  let broot = backup_root(app, account_id, world_id)?;
+  restore_from_zip_in(zip_path, temp, &dir, &wpath, &broot, preserve_display_name)
+}
+
+fn restore_from_zip_in(
+  zip_path: &Path,
+  temp: &Path,
+  players_dir: &Path,
+  world_path: &Path,
+  backup_root: &Path,
+  preserve_display_name: bool,
+) -> Result<(), String> {
+  let backup_dir = temp.join(import_backup_zip(zip_path, temp)?);
+  if backup_dir.join("config_snapshot.json").exists() && read_backup_snapshot(&backup_dir).is_none() {
+    return Err("Backup ZIP has an unreadable config_snapshot.json.".into());
+  }
+  let wc = load_world_config(players_dir);
+  backup_files(players_dir, world_path, backup_root, &[], &backup_snapshot(&wc, "pre-restore"), false)?;
+  restore_backup_files(&backup_dir, players_dir, world_path, preserve_display_name)
+}
+
+/// Parse every `.sav` in a backup folder and its config snapshot. `emit` is
+/// called once per file with (done, total, file name).
+fn verify_backup_dir(backup_dir: &Path, emit: &dyn Fn(usize, usize, &str)) -> Result<BackupVerification, String> {
+  let mut savs: Vec<PathBuf> = fs::read_dir(backup_dir)
+    .map_err(|e| format!("Cannot read backup: {e}"))?
+    .flatten()
+    .map(|e| e.path())
+    .filter(|p| p.extension().is_some_and(|ext| ext == "sav"))
+    .collect();
+  savs.sort();
+
+  let mut unreadable = Vec::new();
+  for (i, path) in savs.iter().enumerate() {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    emit(i, savs.len(), &name);
+    let parsed = fs::read(path).map_err(|e| e.to_string()).and_then(|data| gvas::sav_to_json(&data));
+    if let Err(error) = parsed {
+      unreadable.push(UnreadableFile { file: name, error });
+    }
+  }
+  emit(savs.len(), savs.len(), "");
+
+  let snapshot_path = backup_dir.join("config_snapshot.json");
+  let snapshot_ok = !snapshot_path.exists()
+    || fs::read_to_string(&snapshot_path)
+      .ok()
+      .is_some_and(|raw| serde_json::from_str::<BackupSnapshot>(&raw).is_ok());
+  if !snapshot_ok {
+    unreadable.push(UnreadableFile {
+      file: "config_snapshot.json".into(),
+      error: "Snapshot is not valid JSON.".into(),
+    });
+  }
+
+  Ok(BackupVerification {
+    backup_name: backup_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+    ok: unreadable.is_empty(),
+    files_checked: savs.len(),
+    snapshot_ok,
+    unreadable,
+  })
+}
+
+/// Check a backup is restorable without touching the world.
+#[tauri::command]
+async fn verify_backup(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  backup_name: String,
+) -> Result<BackupVerification, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let backup_dir = backup_root(&app, &account_id, &world_id)?.join(&backup_name);
+    if !backup_dir.exists() {
+      return Err("Backup not found.".to_string());
+    }
+    verify_backup_dir(&backup_dir, &|done, total, name| {
+      let _ = app.emit("verify-progress", ProgressPayload {
+        percent: if total == 0 { 100.0 } else { done as f64 * 100.0 / total as f64 },
+        message: if name.is_empty() { "Done".into() } else { format!("Checking {name}…") },
+      });
+    })
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+#[tauri::command]
+fn delete_backup(app: AppHandle, account_id: String, world_id: String, backup_name: String) -> Result<Vec<String>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let broot = backup_root(&app, &account_id, &world_id)?;
+  let backup_dir = broot.join(&backup_name);
+  if backup_dir.exists() {
+    fs::remove_dir_all(&backup_dir).map_err(|err| err.to_string())?;
+  }
+  Ok(list_backups_dir(&broot))
+}
+
+/// Label a backup (stored in its `config_snapshot.json`, so the folder name
+/// and sort order don't change). An empty label clears it.
+#[tauri::command]
+fn rename_backup(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  backup_name: String,
+  new_label: String,
+) -> Result<Vec<BackupEntry>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let broot = backup_root(&app, &account_id, &world_id)?;
+  let backup_dir = broot.join(&backup_name);
+  if !backup_dir.is_dir() {
+    return Err(format!("Backup '{backup_name}' not found."));
+  }
+  // A blank snapshot would wipe names on restore, so only label real ones
+  let mut snapshot = read_backup_snapshot(&backup_dir).ok_or("This backup has no config snapshot to label.")?;
+  let label = new_label.trim();
+  snapshot.label = (!label.is_empty()).then(|| label.to_string());
+  let snapshot_json = serde_json::to_string_pretty(&snapshot).map_err(|err| err.to_string())?;
+  fs::write(backup_dir.join("config_snapshot.json"), snapshot_json).map_err(|err| err.to_string())?;
+  Ok(list_backup_entries(&broot))
+}
+
+#[tauri::command]
+fn delete_all_backups(app: AppHandle, account_id: String, world_id: String) -> Result<Vec<String>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let broot = backup_root(&app, &account_id, &world_id)?;
+  if broot.exists() {
+    fs::remove_dir_all(&broot).map_err(|err| err.to_string())?;
+  }
+  Ok(Vec::new())
+}
+
+// ── World transfer ────────────────────────────────────────
+
+/// Export a world folder as a ZIP file (runs on background thread).
+#[tauri::command]
+async fn export_world(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  dest_path: String,
+  include_game_backups: Option<bool>,
+  password: Option<String>,
+) -> Result<String, String> {
+  let app2 = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    let started = std::time::Instant::now();
+    let include = include_game_backups.unwrap_or(false);
+    let dest = export_world_sync(&app2, &account_id, &world_id, &dest_path, include, password.as_deref())?;
+    record_operation_time(&app2, "export", &world_dir(&account_id, &world_id)?, started.elapsed());
+    Ok(dest)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn export_world_sync(
+  app: &AppHandle,
+  account_id: &str,
+  world_id: &str,
+  dest_path: &str,
+  include_game_backups: bool,
+  password: Option<&str>,
+) -> Result<String, String> {
+  let wdir = long_path(&world_dir(account_id, world_id)?);
+  if !wdir.exists() {
+    return Err("World folder does not exist.".to_string());
+  }
+
+  let dest = long_path(Path::new(dest_path));
+
+  // Ensure destination directory exists
+  if let Some(parent) = dest.parent() {
+    if !parent.exists() {
+      fs::create_dir_all(parent).map_err(|e| format!("Cannot create destination folder: {e}"))?;
+    }
+  }
+
+  let mut throttle = ProgressThrottle::from_config(app);
+  let _ = app.emit("export-progress", ProgressPayload { percent: 0.0, message: "Starting export…".to_string() });
+
+  let file = fs::File::create(&dest)
+    .map_err(|e| format!("Cannot create ZIP file: {e}"))?;
+  let overridden = load_app_config(app)?.backup_root_override.is_some();
+  write_world_zip(zip::ZipWriter::new(file), &wdir, world_id, include_game_backups, overridden, password, &mut |done, total| {
+    if let Some(pct) = throttle.tick(done, total) {
+      let _ = app.emit("export-progress", ProgressPayload { percent: pct as f64, message: format!("Compressing… {done}/{total}") });
+    }
+  })?;
+  let _ = app.emit("export-progress", ProgressPayload { percent: 100.0, message: "Export complete.".to_string() });
+  Ok(dest_path.to_string())
+}
+
+/// Write the world folder `wdir` into `zip` under a `world_id/` root, with
+/// the checksum manifest first, and return the finished writer. Works on
+/// anything seekable, or on a pipe via `ZipWriter::new_stream` (unencrypted
+/// only). `progress` gets (files done, total files).
+fn write_world_zip<W: Write + Seek>(
+  mut zip: zip::ZipWriter<W>,
+  wdir: &Path,
+  world_id: &str,
+  include_game_backups: bool,
+  backup_root_overridden: bool,
+  password: Option<&str>,
+  progress: &mut dyn FnMut(usize, usize),
+) -> Result<W, String> {
+  // ── Skip backup directories for P2P export ──────────────────────────
+  // <worldDir>/Players/backup/ holds PalHost swap backups and is always
+  // skipped, except with a backup root override: the swap backups live
+  // elsewhere then and the folder is left as ordinary world content.
+  // <worldDir>/backup/ (Palworld game backups: backup/world/ and
+  // backup/local/) is skipped unless the caller wants the recipient to be
+  // able to roll back. Backups can be 100MB+ each.
+  let mut skip_dirs: Vec<PathBuf> = Vec::new();
+  if !backup_root_overridden {
+    skip_dirs.push(wdir.join("Players").join("backup"));
+  }
+  if !include_game_backups {
+    skip_dirs.push(wdir.join("backup"));
+  }
+
+  // Count total files for progress (excluding skipped backup dirs)
+  let stale_manifest = wdir.join(EXPORT_MANIFEST_FILE);
+  let entries: Vec<_> = WalkDir::new(wdir)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter(|e| {
+      let p = e.path();
+      !skip_dirs.iter().any(|sk| p.starts_with(sk)) && p != stale_manifest
+    })
+    .collect();
+  let total = entries.iter().filter(|e| e.path().is_file()).count().max(1);
+  let mut done = 0usize;
+
+  // Checksums go in first so the importer can detect truncated transfers
+  let mut manifest = ExportManifest::default();
+  for entry in entries.iter().filter(|e| e.path().is_file()) {
+    let rel_path = entry.path().strip_prefix(wdir).map_err(|e| e.to_string())?;
+    manifest.files.push(ManifestEntry {
+      path: rel_path.to_string_lossy().replace('\\', "/"),
+      sha256: sha256_file(entry.path())?,
+    });
+  }
+
+  let dir_options = SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Deflated)
+    .unix_permissions(0o644);
+  let options = export_file_options(password);
+
+  zip.start_file(format!("{world_id}/{EXPORT_MANIFEST_FILE}"), options)
+    .map_err(|e| format!("Error adding manifest to ZIP: {e}"))?;
+  let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+  zip.write_all(&manifest_json)
+    .map_err(|e| format!("ZIP write error: {e}"))?;
+
+  // Walk the world directory and add all files
+  for entry in &entries {
+    let abs_path = entry.path();
+    let rel_path = abs_path.strip_prefix(wdir).map_err(|e| e.to_string())?;
+
+    // Use world_id as the root folder name inside the ZIP
+    let archive_path = PathBuf::from(world_id).join(rel_path);
+    let archive_name = archive_path.to_string_lossy().replace('\\', "/");
+
+    if abs_path.is_dir() {
+      zip.add_directory(&archive_name, dir_options)
+        .map_err(|e| format!("Error adding folder to ZIP: {e}"))?;
+    } else {
+      zip.start_file(&archive_name, options)
+        .map_err(|e| format!("Error adding file to ZIP: {e}"))?;
+      let mut f = fs::File::open(abs_path)
+        .map_err(|e| format!("Cannot read {}: {e}", abs_path.display()))?;
+      let mut buf = Vec::new();
+      f.read_to_end(&mut buf)
+        .map_err(|e| format!("File read error: {e}"))?;
+      zip.write_all(&buf)
+        .map_err(|e| format!("ZIP write error: {e}"))?;
+      done += 1;
+      progress(done, total);
+    }
+  }
+
+  zip.finish().map_err(|e| format!("Error finalizing ZIP: {e}"))
+}
+
+/// Options for files in a world export. With a (non-empty) password the
+/// contents are encrypted with WinZip AES-256 (AE-2: PBKDF2-HMAC-SHA1 key
+/// derivation, HMAC-SHA1 authentication); file names and sizes stay visible.
+fn export_file_options(password: Option<&str>) -> zip::write::FileOptions<'_, ()> {
+  let options = SimpleFileOptions::default()
+    .compression_method(zip::CompressionMethod::Deflated)
+    .unix_permissions(0o644);
+  match password.filter(|p| !p.is_empty()) {
+    Some(pw) => options.with_aes_encryption(zip::AesMode::Aes256, pw),
+    None => options,
+  }
+}
+
+/// Open entry `index` of `archive`, decrypting it with `password` if given.
+/// Encrypted entries without a password, or with the wrong one, get a clear
+/// error instead of the zip crate's.
+fn open_zip_entry<'a, R: Read + Seek>(
+  archive: &'a mut zip::ZipArchive<R>,
+  index: usize,
+  password: Option<&str>,
+) -> Result<zip::read::ZipFile<'a, R>, String> {
+  let entry = match password.filter(|p| !p.is_empty()) {
+    Some(pw) => archive.by_index_decrypt(index, pw.as_bytes()),
+    None => archive.by_index(index),
+  };
+  entry.map_err(|e| match e {
+    zip::result::ZipError::UnsupportedArchive(msg) if msg == zip::result::ZipError::PASSWORD_REQUIRED => {
+      "This ZIP is password-protected; a password is required.".to_string()
+    }
+    zip::result::ZipError::InvalidPassword => "Wrong password for this ZIP.".to_string(),
+    e => format!("ZIP read error: {e}"),
+  })
+}
+
+/// Validate a folder to check if it looks like a valid Palworld world.
+/// Returns the folder name (world ID).
+#[tauri::command]
+fn validate_world_folder(folder_path: String, mode: Option<ValidationMode>) -> Result<ValidatedFolder, String> {
+  validate_world_dir(Path::new(&folder_path), mode.unwrap_or_default())
+}
+
+/// Classify a directory as a world; `None` if it has neither Players/ nor
+/// any root .sav.
+fn world_completeness(dir: &Path) -> Option<WorldCompleteness> {
+  let has_players = dir.join("Players").is_dir();
+  let has_level = dir.join("Level.sav").is_file();
+  let has_sav = || {
+    fs::read_dir(dir)
+      .ok()
+      .into_iter()
+      .flatten()
+      .filter_map(|e| e.ok())
+      .any(|e| e.path().extension().is_some_and(|ext| ext == "sav") && e.path().is_file())
+  };
+  match (has_players, has_level) {
+    (true, true) => Some(WorldCompleteness::Full),
+    (true, false) => Some(WorldCompleteness::MissingLevel),
+    (false, true) => Some(WorldCompleteness::LevelOnly),
+    (false, false) if has_sav() => Some(WorldCompleteness::SavesOnly),
+    _ => None,
+  }
+}
+
+fn validate_world_dir(src: &Path, mode: ValidationMode) -> Result<ValidatedFolder, String> {
+  // Lenient: a Level.sav picked directly stands for its folder
+  if mode == ValidationMode::Lenient && src.is_file() && src.file_name().is_some_and(|n| n == "Level.sav") {
+    let parent = src.parent().ok_or("Invalid folder name.")?;
+    return Ok(ValidatedFolder {
+      name: parent.file_name().and_then(|n| n.to_str()).ok_or("Invalid folder name.")?.to_string(),
+      path: parent.to_string_lossy().to_string(),
+      completeness: world_completeness(parent).unwrap_or(WorldCompleteness::LevelOnly),
+    });
+  }
+  if !src.exists() || !src.is_dir() {
+    return Err("The path is not a valid folder.".to_string());
+  }
+  let folder_name = src
+    .file_name()
+    .and_then(|n| n.to_str())
+    .ok_or("Invalid folder name.")?
+    .to_string();
+
+  // First, check the folder itself
+  if let Some(completeness) = world_completeness(src) {
+    return Ok(ValidatedFolder {
+      name: folder_name,
+      path: src.to_string_lossy().to_string(),
+      completeness,
+    });
+  }
+
+  // Fallback: check for a subfolder with the same name (common after ZIP extraction)
+  let nested = src.join(&folder_name);
+  if let Some(completeness) = world_completeness(&nested) {
+    return Ok(ValidatedFolder {
+      name: folder_name,
+      path: nested.to_string_lossy().to_string(),
+      completeness,
+    });
+  }
+
+  // Also check any single subfolder (in case name differs)
+  let sub_entries: Vec<_> = fs::read_dir(src)
+    .ok()
+    .into_iter()
+    .flatten()
+    .filter_map(|e| e.ok())
+    .filter(|e| e.path().is_dir())
+    .collect();
+  if sub_entries.len() == 1 {
+    let sub_path = sub_entries[0].path();
+    if let Some(completeness) = world_completeness(&sub_path) {
+      let sub_name = sub_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(&folder_name)
+        .to_string();
+      return Ok(ValidatedFolder {
+        name: sub_name,
+        path: sub_path.to_string_lossy().to_string(),
+        completeness,
+      });
+    }
+  }
+
+  Err("The folder does not appear to be a valid Palworld world (no Players/ folder or .sav files).".to_string())
+}
+
+/// Check if a world folder already exists for the given account.
+#[tauri::command]
+fn check_world_exists(account_id: String, world_name: String) -> Result<bool, String> {
+  if account_id.trim().is_empty() || world_name.trim().is_empty() {
+    return Ok(false);
+  }
+  let target = account_dir(&account_id)?.join(&world_name);
+  Ok(target.exists())
+}
+
+/// Read-only preflight for a shared world that hasn't been imported yet:
+/// engine version, host slot, missing player files and host identity.
+#[tauri::command]
+async fn verify_shared_world_compatibility(account_id: String, source_dir: String) -> Result<CompatReport, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let account_root = account_dir(&account_id)?;
+    shared_world_compat(Path::new(&source_dir), &account_root)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+fn shared_world_compat(source_dir: &Path, account_root: &Path) -> Result<CompatReport, String> {
+  let src = validate_world_dir(source_dir, ValidationMode::Strict)?;
+  let wpath = PathBuf::from(&src.path);
+  let pdir = wpath.join("Players");
+  let data = fs::read(wpath.join("Level.sav")).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+  let (json, _) = gvas::sav_to_json(&data)?;
+  let header = &json["header"];
+  let engine_supported = match (header["engine_version_major"].as_i64(), header["engine_version_minor"].as_i64()) {
+    (Some(major), Some(minor)) => SUPPORTED_ENGINE_VERSIONS.contains(&(major, minor)),
+    _ => false,
+  };
+
+  let player_ids = list_player_ids(&pdir);
+  let wc = load_world_config(&pdir);
+  let host_slot_present = player_ids.iter().any(|id| is_host_slot(id));
+  let host_id = resolve_host_id(&wc, &player_ids);
+  let level_players = players_from_level_json(&json);
+  let missing_player_files: Vec<String> = level_players
+    .iter()
+    .filter(|p| !player_ids.contains(&p.filename))
+    .map(|p| p.filename.clone())
+    .collect();
+
+  let mut warnings = Vec::new();
+  if let Some(host) = &host_id {
+    if !is_host_slot(host) {
+      warnings.push(format!("The shared host {host} isn't in a host slot here; you won't load in as that character."));
+    }
+    if !level_players.iter().any(|p| &p.filename == host) {
+      warnings.push(format!("The host {host} has no character in Level.sav; the game will start a new one."));
+    }
+  }
+  if account_root.join(&src.name).exists() {
+    warnings.push(format!("World {} already exists in this account; import it as a copy or replace it.", src.name));
+  }
+
+  Ok(CompatReport {
+    compatible: engine_supported && host_slot_present && missing_player_files.is_empty(),
+    engine_version: engine_version_string(header),
+    engine_supported,
+    host_id,
+    host_slot_present,
+    missing_player_files,
+    warnings,
+  })
+}
+
+/// Import a world folder into the account's save directory (runs on background thread).
+/// mode: "replace" | "new"
+/// new_name is used only when mode == "new"
+/// create_account_if_missing creates the account folder first (e.g. on a PC
+/// where the game hasn't been launched yet). The game only loads it if the id
+/// is the Steam/Epic account the user actually plays on.
+#[tauri::command]
+async fn import_world(
+  app: AppHandle,
+  account_id: String,
+  folder_path: String,
+  mode: String,
+  new_name: Option<String>,
+  create_account_if_missing: Option<bool>,
+  dry_run: Option<bool>,
+) -> Result<ImportResult, String> {
+  let _guard = match dry_run {
+    Some(true) => None,
+    _ => Some(lock_world(&app, &account_id, &import_target_name(Path::new(&folder_path), &mode, new_name.as_deref())?)?),
+  };
+  let app2 = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    if dry_run.unwrap_or(false) {
+      return plan_import(&account_id, &folder_path, &mode, new_name.as_deref()).map(ImportResult::Plan);
+    }
+    import_world_sync(
+      &app2,
+      &account_id,
+      &folder_path,
+      &mode,
+      new_name.as_deref(),
+      create_account_if_missing.unwrap_or(false),
+    )
+    .map(ImportResult::Worlds)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Build the skip-set for old backups in the import SOURCE: keep only the
+/// most recent backup subfolder in each category so we don't bloat the
+/// destination with tons of old backup folders.
+fn import_skip_dirs(src: &Path) -> std::collections::HashSet<PathBuf> {
+  let mut skip_src_dirs: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
+  skip_src_dirs.insert(src.join(EXPORT_MANIFEST_FILE));
+
+  for sub in &["world", "local"] {
+    let bdir = src.join("backup").join(sub);
+    if bdir.is_dir() {
+      if let Ok(rd) = fs::read_dir(&bdir) {
+        let mut folders: Vec<PathBuf> = rd
+          .filter_map(|e| e.ok())
+          .filter(|e| e.path().is_dir())
+          .map(|e| e.path())
+          .collect();
+        // Sort descending by name (timestamp format sorts lexicographically)
+        folders.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+        // Skip everything except the most recent
+        for old in folders.iter().skip(1) {
+          skip_src_dirs.insert(old.clone());
+        }
+      }
+    }
+  }
+  skip_src_dirs
+}
+
+/// World folder an import of `src` lands in: the source folder's name, or
+/// the sanitized `new_name` in "new" mode.
+fn import_target_name(src: &Path, mode: &str, new_name: Option<&str>) -> Result<String, String> {
+  let folder_name = src
+    .file_name()
+    .and_then(|n| n.to_str())
+    .ok_or("Invalid source folder name.")?;
+  match mode {
+    "new" => sanitize_world_name(new_name.unwrap_or(folder_name)),
+    _ => Ok(folder_name.to_string()),
+  }
+}
+
+/// What an import would do, without touching the filesystem. Paths are
+/// relative to the world folder, `/`-separated.
+fn plan_import(account_id: &str, folder_path: &str, mode: &str, new_name: Option<&str>) -> Result<Vec<ImportAction>, String> {
+  let src = long_path(Path::new(folder_path));
+  if !src.exists() || !src.is_dir() {
+    return Err("Source folder does not exist.".to_string());
+  }
+  let target_name = import_target_name(&src, mode, new_name)?;
+  let target = long_path(&account_dir(account_id)?.join(&target_name));
+  if mode == "new" && target.exists() {
+    return Err(format!("A world named '{}' already exists.", target_name));
+  }
+
+  let skip_src_dirs = import_skip_dirs(&src);
+  let mut actions = Vec::new();
+  let mut incoming = std::collections::HashSet::new();
+  plan_dir_merge(&src, &target, "", &skip_src_dirs, &mut incoming, &mut actions);
+
+  if mode == "replace" && target.exists() {
+    // Everything `remove_dir_except_backups` would delete and the copy won't bring back
+    let kept = [target.join("backup").join("world"), target.join("backup").join("local")];
+    for entry in WalkDir::new(&target).min_depth(1).into_iter().filter_map(|e| e.ok()) {
+      let p = entry.path();
+      if !entry.file_type().is_file() || kept.iter().any(|k| p.starts_with(k)) {
+        continue;
+      }
+      let rel = p.strip_prefix(&target).map_err(|e| e.to_string())?.to_string_lossy().replace('\\', "/");
+      if !incoming.contains(&rel) {
+        actions.push(ImportAction { kind: ImportActionKind::DeleteExisting, path: rel });
+      }
+    }
+  }
+  Ok(actions)
+}
+
+/// Read-only twin of `copy_dir_recursive_merge`: records what copying `src`
+/// into `dest` would create or overwrite. `incoming` collects every copied path.
+fn plan_dir_merge(
+  src: &Path,
+  dest: &Path,
+  rel: &str,
+  skip_dirs: &std::collections::HashSet<PathBuf>,
+  incoming: &mut std::collections::HashSet<String>,
+  actions: &mut Vec<ImportAction>,
+) {
+  let Ok(rd) = fs::read_dir(src) else { return };
+  for entry in rd.filter_map(|e| e.ok()) {
+    let path = entry.path();
+    let name = entry.file_name().to_string_lossy().to_string();
+    let rel_path = if rel.is_empty() { name } else { format!("{rel}/{name}") };
+    if skip_dirs.iter().any(|sk| path == *sk || path.starts_with(sk)) {
+      if path.is_dir() {
+        actions.push(ImportAction { kind: ImportActionKind::SkipBackup, path: rel_path });
+      }
+      continue;
+    }
+    let dest_path = dest.join(entry.file_name());
+    if path.is_dir() {
+      plan_dir_merge(&path, &dest_path, &rel_path, skip_dirs, incoming, actions);
+    } else {
+      let kind = if dest_path.exists() { ImportActionKind::Overwrite } else { ImportActionKind::Create };
+      incoming.insert(rel_path.clone());
+      actions.push(ImportAction { kind, path: rel_path });
+    }
+  }
+}
+
+/// Copy just `Level.sav` and `Players/*.sav` from `source_dir` into an
+/// existing world, backing up the files they replace first. Every incoming
+/// save is checked before anything is written. Returns how many player files
+/// were copied.
+fn import_saves_into(source_dir: &Path, world_path: &Path, players_dir: &Path, backup_root: &Path) -> Result<usize, String> {
+  let src_level = source_dir.join("Level.sav");
+  if !src_level.is_file() {
+    return Err("The source folder has no Level.sav.".into());
+  }
+  let data = fs::read(&src_level).map_err(|e| format!("Cannot read Level.sav: {e}"))?;
+  gvas::sav_header(&data, 0).map_err(|e| format!("The source Level.sav isn't a valid save: {e}"))?;
+  let source_players = source_dir.join("Players");
+  let mut incoming = Vec::new();
+  for id in list_player_ids(&source_players) {
+    let name = format!("{id}.sav");
+    let src = player_file_variants(&source_players, &id).into_iter().next().ok_or_else(|| format!("{name} vanished"))?;
+    let bytes = fs::read(long_path(&src)).map_err(|e| format!("Cannot read {name}: {e}"))?;
+    gvas::sav_to_json(&bytes).map_err(|e| format!("The source {name} isn't a valid save: {e}"))?;
+    incoming.push((id, bytes));
+  }
+
+  let existing: Vec<String> =
+    incoming.iter().map(|(id, _)| id).filter(|id| players_dir.join(format!("{id}.sav")).exists()).cloned().collect();
+  let wc = load_world_config(players_dir);
+  backup_files(players_dir, world_path, backup_root, &existing, &backup_snapshot(&wc, "pre-import"), false)?;
+
+  fs::create_dir_all(players_dir).map_err(|e| format!("Cannot create Players folder: {e}"))?;
+  for (id, bytes) in &incoming {
+    let name = format!("{id}.sav");
+    fs::write(long_path(&players_dir.join(&name)), bytes).map_err(|e| format!("Cannot copy {name}: {e}"))?;
+  }
+  fs::write(long_path(&world_path.join("Level.sav")), &data).map_err(|e| format!("Cannot write Level.sav: {e}"))?;
+  Ok(incoming.len())
+}
+
+/// Splice shared saves (Level.sav + player files) into an existing world.
+/// Only WorldOption/LocalData, the game's backups and the world config are
+/// kept; everything in Level.sav, base camps included, comes from the source.
+#[tauri::command]
+async fn import_saves_only(app: AppHandle, account_id: String, world_id: String, source_dir: String) -> Result<Vec<Player>, String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&app, &account_id, &world_id)?;
+    let dir = players_dir(&account_id, &world_id)?;
+    let wpath = world_dir(&account_id, &world_id)?;
+    if !wpath.is_dir() {
+      return Err("World folder does not exist.".into());
+    }
+    let copied = import_saves_into(Path::new(&source_dir), &wpath, &dir, &backup_root(&app, &account_id, &world_id)?)?;
+    log_event(log::Level::Info, &format!("imported Level.sav and {copied} player saves into {world_id}"));
+    get_players_sync(&app, &account_id, &world_id)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Make a world folder name safe on every platform a share might land on:
+/// reserved characters become `_`, trailing dots/spaces are trimmed, and
+/// Windows device names (CON, COM1, …) are rejected.
+fn sanitize_world_name(name: &str) -> Result<String, String> {
+  let replaced: String = name
+    .trim()
+    .chars()
+    .map(|c| if c.is_control() || "<>:\"/\\|?*".contains(c) { '_' } else { c })
+    .collect();
+  let cleaned = replaced.trim_end_matches(['.', ' ']).to_string();
+  if cleaned.is_empty() {
+    return Err("World name cannot be empty.".to_string());
+  }
+  let stem = cleaned.split('.').next().unwrap_or("").trim_end().to_ascii_uppercase();
+  let reserved = matches!(stem.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+    || ((stem.starts_with("COM") || stem.starts_with("LPT"))
+      && stem.len() == 4
+      && stem.as_bytes()[3].is_ascii_digit()
+      && stem.as_bytes()[3] != b'0');
+  if reserved {
+    return Err(format!("'{cleaned}' is a reserved name on Windows."));
+  }
+  Ok(cleaned)
+}
+
+fn import_world_sync(
+  app: &AppHandle,
+  account_id: &str,
+  folder_path: &str,
+  mode: &str,
+  new_name: Option<&str>,
+  create_account_if_missing: bool,
+) -> Result<Vec<WorldInfo>, String> {
+  let src = long_path(Path::new(folder_path));
+  if !src.exists() || !src.is_dir() {
+    return Err("Source folder does not exist.".to_string());
+  }
+
+  // Check the export manifest (if any) before touching the target world
+  verify_export_manifest(&src)?;
+
+  let target_name = import_target_name(&src, mode, new_name)?;
+
+  let account_root = account_dir(account_id)?;
+  if !account_root.exists() {
+    if !create_account_if_missing {
+      return Err("Account folder does not exist.".to_string());
+    }
+    // Check the raw id: the folder name alone would accept `../../123`
+    let (_, bare_id) = resolve_account(account_id)?;
+    if classify_account_id(&bare_id) == "unknown" {
+      return Err(format!("'{account_id}' doesn't look like a Steam or Epic account id."));
+    }
+    fs::create_dir_all(&account_root).map_err(|e| format!("Cannot create account folder: {e}"))?;
+    log_event(log::Level::Info, &format!("created account folder {account_id} for import"));
+  }
+  let target = long_path(&account_root.join(&target_name));
+
+  if mode == "new" && target.exists() {
+    return Err(format!("A world named '{}' already exists.", target_name));
+  }
+
+  if mode == "replace" && target.exists() {
+    wait_for_idle_if_enabled(app, account_id, &target_name)?;
+    // Remove everything EXCEPT backup/world and backup/local
+    remove_dir_except_backups(&target)
+      .map_err(|e| format!("Cannot clean existing world: {e}"))?;
+  }
+
+  let skip_src_dirs = import_skip_dirs(&src);
+
+  // Count total files for progress (excluding skipped backup dirs)
+  let total_files = WalkDir::new(&src)
+    .into_iter()
+    .filter_map(|e| e.ok())
+    .filter(|e| {
+      let p = e.path();
+      !skip_src_dirs.iter().any(|sk| p.starts_with(sk))
+    })
+    .filter(|e| e.path().is_file())
+    .count()
+    .max(1);
+  let counter = std::sync::atomic::AtomicUsize::new(0);
+  let mut throttle = ProgressThrottle::from_config(app);
+
+  let _ = app.emit("import-progress", ProgressPayload { percent: 0.0, message: "Starting import…".to_string() });
+
+  // Recursively copy src into target, merging backups and skipping old ones
+  copy_dir_recursive_merge(&src, &target, app, &counter, total_files, &mut throttle, &skip_src_dirs)?;
+
+  let _ = app.emit("import-progress", ProgressPayload { percent: 100.0, message: "Import complete.".to_string() });
+
+  // Return updated world list
+  worlds_with_counts(app, account_id, false)
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+  use sha2::{Digest, Sha256};
+  let mut f = fs::File::open(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+  let mut hasher = Sha256::new();
+  std::io::copy(&mut f, &mut hasher).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+  Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Check every file listed in a world folder's export manifest. Folders
+/// without a manifest (older exports, plain copies) pass unchecked.
+fn verify_export_manifest(world: &Path) -> Result<(), String> {
+  let manifest_path = world.join(EXPORT_MANIFEST_FILE);
+  if !manifest_path.exists() {
+    return Ok(());
+  }
+  let raw = fs::read_to_string(&manifest_path).map_err(|e| format!("Cannot read manifest: {e}"))?;
+  let manifest: ExportManifest = serde_json::from_str(&raw).map_err(|e| format!("Invalid manifest: {e}"))?;
+  // Only plain paths inside the world; the manifest comes from the sender
+  if let Some(bad) = manifest.files.iter().find(|f| !is_plain_relative_path(&f.path)) {
+    return Err(format!("Manifest lists a path outside the world: {}", bad.path));
+  }
+  let corrupt: Vec<String> = manifest
+    .files
+    .into_iter()
+    .filter(|f| sha256_file(&world.join(&f.path)).ok().as_deref() != Some(f.sha256.as_str()))
+    .map(|f| f.path)
+    .collect();
+  if corrupt.is_empty() {
+    Ok(())
+  } else {
+    Err(format!("Corrupt or missing files: {}", corrupt.join(", ")))
+  }
+}
+
+/// True for a non-empty relative path made only of plain names (no root,
+/// drive prefix, `.` or `..`).
+fn is_plain_relative_path(path: &str) -> bool {
+  let path = Path::new(path);
+  path.components().next().is_some() && path.components().all(|c| matches!(c, std::path::Component::Normal(_)))
+}
+
+/// Remove all contents of a world directory EXCEPT backup/world and backup/local.
+/// This preserves existing game backups while replacing everything else.
+fn remove_dir_except_backups(dir: &Path) -> std::io::Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let entry = entry?;
+    let path = entry.path();
+    let name = entry.file_name();
+
+    if name == "backup" && path.is_dir() {
+      // Inside the backup folder, remove everything except "world" and "local"
+      for bentry in fs::read_dir(&path)? {
+        let bentry = bentry?;
+        let bname = bentry.file_name();
+        if bname != "world" && bname != "local" {
+          if bentry.path().is_dir() {
+            fs::remove_dir_all(bentry.path())?;
+          } else {
+            fs::remove_file(bentry.path())?;
+          }
+        }
+      }
+    } else if path.is_dir() {
+      fs::remove_dir_all(&path)?;
+    } else {
+      fs::remove_file(&path)?;
+    }
+  }
+  Ok(())
+}
+
+/// Recursively copy src to dest, merging backup directories and skipping old backup folders.
+fn copy_dir_recursive_merge(
+  src: &Path,
+  dest: &Path,
+  app: &AppHandle,
+  counter: &std::sync::atomic::AtomicUsize,
+  total: usize,
+  throttle: &mut ProgressThrottle,
+  skip_dirs: &std::collections::HashSet<PathBuf>,
+) -> Result<(), String> {
+  let (src, dest) = (&long_path(src), &long_path(dest));
+  if !dest.exists() {
+    fs::create_dir_all(dest).map_err(|e| format!("Cannot create {}: {e}", dest.display()))?;
+  }
+  for entry in fs::read_dir(src).map_err(|e| format!("Cannot read {}: {e}", src.display()))? {
+    let entry = entry.map_err(|e| e.to_string())?;
+    let path = entry.path();
+
+    // Skip old backup folders from the source
+    if skip_dirs.iter().any(|sk| path == *sk || path.starts_with(sk)) {
+      continue;
+    }
+
+    let dest_path = dest.join(entry.file_name());
+    if path.is_dir() {
+      // For backup subdirs that already exist at destination, don't clear them — just merge
+      copy_dir_recursive_merge(&path, &dest_path, app, counter, total, throttle, skip_dirs)?;
+    } else {
+      fs::copy(&path, &dest_path)
+        .map_err(|e| format!("Cannot copy {}: {e}", path.display()))?;
+      let done = counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+      if let Some(pct) = throttle.tick(done, total) {
+        let _ = app.emit("import-progress", ProgressPayload { percent: pct as f64, message: format!("Copying… {done}/{total}") });
+      }
+    }
+  }
+  Ok(())
+}
+
+#[cfg(windows)]
+#[tauri::command]
+fn is_palworld_running() -> bool {
+  use std::os::windows::process::CommandExt;
+  const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+  if let Ok(output) = StdCommand::new("tasklist")
+    .args(["/FI", "IMAGENAME eq Palworld-Win64-Shipping.exe", "/NH", "/FO", "CSV"])
+    .creation_flags(CREATE_NO_WINDOW)
+    .output()
+  {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.contains("Palworld-Win64-Shipping.exe")
+  } else {
+    false
+  }
+}
+
+/// The game only runs on Windows; other builds exist for developing and
+/// testing the save parser, so the game is never considered running.
+#[cfg(not(windows))]
+#[tauri::command]
+fn is_palworld_running() -> bool {
+  false
+}
+
+/// Wait until `is_running` reports the game closed or, with `level_sav`, the
+/// file's mtime and size stay put for `window`. False if `timeout` passes first.
+fn wait_until_idle(
+  level_sav: Option<&Path>,
+  window: std::time::Duration,
+  timeout: std::time::Duration,
+  is_running: impl Fn() -> bool,
+) -> bool {
+  let started = std::time::Instant::now();
+  let mut last = level_sav.and_then(file_stamp);
+  let mut stable_since = started;
+  loop {
+    if !is_running() {
+      return true;
+    }
+    if let Some(path) = level_sav {
+      let stamp = file_stamp(path);
+      if stamp != last {
+        // An autosave is in progress; start the window over
+        last = stamp;
+        stable_since = std::time::Instant::now();
+      } else if stable_since.elapsed() >= window {
+        return true;
+      }
+    }
+    if started.elapsed() >= timeout {
+      return false;
+    }
+    std::thread::sleep(IDLE_POLL_INTERVAL);
+  }
+}
+
+/// Wait for Palworld to be closed or, given a world, for its Level.sav to
+/// stop changing (no autosave in progress). Returns false on timeout.
+#[tauri::command]
+async fn wait_for_game_idle(
+  app: AppHandle,
+  timeout_ms: u64,
+  account_id: Option<String>,
+  world_id: Option<String>,
+) -> Result<bool, String> {
+  let window_ms = load_app_config(&app)?.idle_window_ms.unwrap_or(DEFAULT_IDLE_WINDOW_MS);
+  tauri::async_runtime::spawn_blocking(move || {
+    let level_sav = match (&account_id, &world_id) {
+      (Some(a), Some(w)) => Some(world_dir(a, w)?.join("Level.sav")),
+      _ => None,
+    };
+    Ok(wait_until_idle(
+      level_sav.as_deref(),
+      std::time::Duration::from_millis(window_ms),
+      std::time::Duration::from_millis(timeout_ms),
+      is_palworld_running,
+    ))
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Run before a command writes saves: with `wait_for_idle` on, block until
+/// the game is idle and fail if it doesn't get there in `IDLE_WAIT_TIMEOUT`.
+fn wait_for_idle_if_enabled(app: &AppHandle, account_id: &str, world_id: &str) -> Result<(), String> {
+  wait_for_idle_in(&load_app_config(app)?, &world_dir(account_id, world_id)?)
+}
+
+/// `wait_for_idle_if_enabled` for the world at `world_path`, with settings
+/// already loaded (the headless CLI has no app handle).
+fn wait_for_idle_in(config: &AppConfig, world_path: &Path) -> Result<(), String> {
+  if !config.wait_for_idle {
+    return Ok(());
+  }
+  let level_sav = world_path.join("Level.sav");
+  let window = std::time::Duration::from_millis(config.idle_window_ms.unwrap_or(DEFAULT_IDLE_WINDOW_MS));
+  if !wait_until_idle(Some(&level_sav), window, IDLE_WAIT_TIMEOUT, is_palworld_running) {
+    return Err("Palworld is still saving this world. Try again in a moment or close the game.".into());
+  }
+  Ok(())
+}
+
+/// Check that a player `.sav` parses and report its identity (read-only).
+#[tauri::command]
+async fn validate_player_sav(path: String) -> Result<PlayerSavInfo, String> {
+  tauri::async_runtime::spawn_blocking(move || read_player_sav_info(Path::new(&path)))
+    .await
+    .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Rewrite a world's Level.sav, or with `player_id` that player's `.sav`,
+/// through the parser and serializer to canonicalize its byte layout. Backs
+/// up first, and nothing is written unless the output re-parses to the same
+/// data.
+#[tauri::command]
+async fn normalize_save(app: AppHandle, account_id: String, world_id: String, player_id: Option<String>) -> Result<(), String> {
+  let _guard = lock_world(&app, &account_id, &world_id)?;
+  let a = app.clone();
+  tauri::async_runtime::spawn_blocking(move || {
+    wait_for_idle_if_enabled(&a, &account_id, &world_id)?;
+    let id = player_id.as_deref().map(normalize_id);
+    let target = match &id {
+      Some(id) => SavTarget::Player(id),
+      None => SavTarget::Level(&[]),
+    };
+    let broot = backup_root(&a, &account_id, &world_id)?;
+    let (dir, wpath) = (players_dir(&account_id, &world_id)?, world_dir(&account_id, &world_id)?);
+    let (before, after) = normalize_sav_in(&wpath, &dir, &broot, target)?;
+    log_event(
+      log::Level::Info,
+      &format!("normalized {} in world {world_id} ({before} -> {after} bytes)", id.as_deref().unwrap_or("Level.sav")),
+    );
+    Ok(())
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Re-encode `target` in place; returns its size before and after.
+fn normalize_sav_in(wpath: &Path, dir: &Path, backup_root: &Path, target: SavTarget) -> Result<(u64, u64), String> {
+  // Nothing changes in the JSON; the re-encoded file must parse back to it
+  let edit = |_: &mut Value| Ok(Some(()));
+  let check = |sav: &SavRewrite, ()| {
+    if sav.reparsed != sav.edited {
+      return Err("Normalized save doesn't match the original after re-parsing; nothing was written.".to_string());
+    }
+    Ok((sav.bytes_before, sav.bytes_after))
+  };
+  Ok(rewrite_sav_in(wpath, dir, backup_root, target, "pre-normalize", edit, check)?.unwrap_or_default())
+}
+
+/// Re-container a `.sav` as `target`: "plz" (double zlib), "cnk" (single
+/// zlib behind a CNK wrapper), "plm" (Oodle; needs `oodle_dll_path`) or
+/// "gvas" (bare uncompressed stream for diffing; the game can't load it).
+/// The GVAS payload is copied as-is, not re-serialized.
+#[tauri::command]
+async fn convert_sav_format(in_path: String, out_path: String, target: String) -> Result<(), String> {
+  let save_type = match target.to_ascii_lowercase().as_str() {
+    "plz" => 0x32,
+    "cnk" => 0x30,
+    "plm" => 0x31,
+    "gvas" => gvas::SAVE_TYPE_RAW_GVAS,
+    other => return Err(format!("Unknown target format '{other}' (expected plz, cnk, plm or gvas).")),
+  };
+  tauri::async_runtime::spawn_blocking(move || {
+    let data = fs::read(&in_path).map_err(|e| format!("Cannot read {in_path}: {e}"))?;
+    let (gvas, source_type) = gvas::decompress_sav_with(&data, true)?;
+    let out = if target.eq_ignore_ascii_case("cnk") {
+      gvas::compress_sav_cnk(&gvas)?
+    } else {
+      gvas::compress_sav(&gvas, save_type)?
+    };
+    // compress_sav quietly falls back to PlZ when Oodle isn't available
+    if save_type == 0x31 && out.get(8..12) != Some(&b"PlM\x31"[..]) {
+      return Err("PLM output needs the game's oo2core DLL; set oodle_dll_path in settings.".into());
+    }
+    if gvas::decompress_sav_with(&out, true)?.0 != gvas {
+      return Err("Converted save doesn't decompress to the original data; nothing was written.".into());
+    }
+    fs::write(&out_path, &out).map_err(|e| format!("Cannot write {out_path}: {e}"))?;
+    log_event(
+      log::Level::Info,
+      &format!("converted {in_path} (0x{source_type:02X}) to {target} at {out_path}"),
+    );
+    Ok(())
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Pretty-print `json` straight into `out` through a small buffer, so the
+/// text is never held in memory as a whole. Skipped blobs are already
+/// base64 strings and go out as-is.
+fn write_json_pretty<W: Write>(json: &Value, out: W) -> Result<(), String> {
+  let mut w = std::io::BufWriter::new(out);
+  serde_json::to_writer_pretty(&mut w, json).map_err(|e| format!("JSON write error: {e}"))?;
+  w.flush().map_err(|e| format!("JSON write error: {e}"))
+}
+
+/// Parse a `.sav` and write it to `out_path` as pretty JSON. Returns the
+/// size of the JSON file.
+#[tauri::command]
+async fn dump_sav_to_json(in_path: String, out_path: String) -> Result<u64, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let data = fs::read(&in_path).map_err(|e| format!("Cannot read {in_path}: {e}"))?;
+    let (json, _) = gvas::sav_to_json(&data)?;
+    drop(data);
+    let file = fs::File::create(&out_path).map_err(|e| format!("Cannot create {out_path}: {e}"))?;
+    write_json_pretty(&json, file)?;
+    let size = fs::metadata(&out_path).map(|m| m.len()).unwrap_or(0);
+    log_event(log::Level::Info, &format!("dumped {in_path} to {out_path} ({size} bytes)"));
+    Ok(size)
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Decode just the GVAS header of a `.sav`. Works on saves whose properties
+/// the parser can't handle.
+#[tauri::command]
+async fn inspect_sav_header(path: String) -> Result<HeaderInspection, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let data = fs::read(&path).map_err(|e| format!("Cannot read {path}: {e}"))?;
+    let (header, prefix, save_type) = gvas::sav_header(&data, 128)?;
+    let header_hex = prefix.iter().map(|b| format!("{b:02x}")).collect();
+    Ok(HeaderInspection { header, header_hex, save_type })
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Return the most recent log entries (oldest first), for bug reports.
+#[tauri::command]
+fn get_recent_logs(limit: Option<usize>) -> Vec<LogEntry> {
+  let buf = LOG_BUFFER.lock().map(|b| b.clone()).unwrap_or_default();
+  let limit = limit.unwrap_or(LOG_BUFFER_CAPACITY);
+  buf.into_iter().rev().take(limit).rev().collect()
+}
+
+/// Re-emit the current save-root state, starting the watcher if the root's
+/// parent has appeared since launch.
+#[tauri::command]
+fn rescan_storage(app: AppHandle) -> Result<(), String> {
+  if let Err(e) = start_save_root_watcher(&app) {
+    log_event(log::Level::Warn, &format!("save-root watcher not running: {e}"));
+  }
+  emit_root_status(&app);
+  Ok(())
+}
+
+// ── P2P Transfer helper commands ──────────────────────────
+
+/// Everything PalHost puts in the temp dir starts with this.
+const TEMP_TRANSFER_PREFIX: &str = "palhost_";
+/// Temp items older than this are removed on startup.
+const STALE_TEMP_TRANSFER_MINUTES: u64 = 24 * 60;
+
+/// Temp items a transfer, restore or rehearsal is still using; cleaning
+/// leaves them alone.
+static TEMP_IN_USE: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+fn mark_temp_in_use(path: &Path) {
+  if let Ok(mut in_use) = TEMP_IN_USE.lock() {
+    if !in_use.iter().any(|p| p == path) {
+      in_use.push(path.to_path_buf());
+    }
+  }
+}
+
+fn release_temp(path: &Path) {
+  if let Ok(mut in_use) = TEMP_IN_USE.lock() {
+    in_use.retain(|p| p != path);
+  }
+}
+
+fn temp_in_use() -> Vec<PathBuf> {
+  TEMP_IN_USE.lock().map(|in_use| in_use.clone()).unwrap_or_default()
+}
+
+/// Keeps a temp item marked in use until dropped.
+struct TempInUse(PathBuf);
+
+impl TempInUse {
+  fn new(path: &Path) -> Self {
+    mark_temp_in_use(path);
+    Self(path.to_path_buf())
+  }
+}
+
+impl Drop for TempInUse {
+  fn drop(&mut self) {
+    release_temp(&self.0);
+  }
+}
+
+/// PalHost-prefixed entries directly under `temp_root`, with their age.
+fn temp_transfer_entries(temp_root: &Path) -> Vec<(PathBuf, fs::Metadata)> {
+  let mut entries: Vec<(PathBuf, fs::Metadata)> = fs::read_dir(temp_root)
+    .into_iter()
+    .flatten()
+    .flatten()
+    .filter(|e| e.file_name().to_string_lossy().to_ascii_lowercase().starts_with(TEMP_TRANSFER_PREFIX))
+    .filter_map(|e| Some((e.path(), e.metadata().ok()?)))
+    .collect();
+  entries.sort_by(|a, b| a.0.cmp(&b.0));
+  entries
+}
+
+fn temp_item(path: &Path, meta: &fs::Metadata) -> TempItem {
+  let created = meta.created().or_else(|_| meta.modified()).map(chrono::DateTime::<chrono::Utc>::from).unwrap_or_default();
+  TempItem {
+    path: path.to_string_lossy().to_string(),
+    size: if meta.is_dir() { dir_size(path) } else { meta.len() },
+    created_at: created.to_rfc3339(),
+  }
+}
+
+/// Delete PalHost temp items last modified more than `older_than` ago (all
+/// of them when `None`), except those in `in_use`. Returns what was removed.
+fn clean_temp_transfers_in(temp_root: &Path, older_than: Option<std::time::Duration>, in_use: &[PathBuf]) -> Vec<TempItem> {
+  let now = std::time::SystemTime::now();
+  temp_transfer_entries(temp_root)
+    .into_iter()
+    .filter(|(path, _)| !in_use.contains(path))
+    .filter(|(_, meta)| {
+      older_than.map_or(true, |min_age| {
+        meta.modified().ok().and_then(|m| now.duration_since(m).ok()).is_some_and(|age| age >= min_age)
+      })
+    })
+    .filter_map(|(path, meta)| {
+      let item = temp_item(&path, &meta);
+      let removed = if meta.is_dir() { fs::remove_dir_all(&path) } else { fs::remove_file(&path) };
+      match removed {
+        Ok(()) => Some(item),
+        Err(e) => {
+          log_event(log::Level::Warn, &format!("cannot remove {}: {e}", path.display()));
+          None
+        }
+      }
+    })
+    .collect()
+}
+
+/// PalHost files and folders left in the temp dir by P2P transfers.
+#[tauri::command]
+fn list_temp_transfers() -> Vec<TempItem> {
+  temp_transfer_entries(&std::env::temp_dir())
+    .iter()
+    .map(|(path, meta)| temp_item(path, meta))
+    .collect()
+}
+
+/// Delete PalHost temp items, optionally only those older than
+/// `older_than_minutes`. Returns what was removed.
+#[tauri::command]
+async fn clean_temp_transfers(older_than_minutes: Option<u64>) -> Result<Vec<TempItem>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let older_than = older_than_minutes.map(|m| std::time::Duration::from_secs(m * 60));
+    clean_temp_transfers_in(&std::env::temp_dir(), older_than, &temp_in_use())
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))
+}
+
+/// Export a world to a temporary ZIP file for P2P sharing.
+/// Returns the full path to the temp ZIP.
+#[tauri::command]
+async fn export_world_to_temp(
+  app: AppHandle,
+  account_id: String,
+  world_id: String,
+  password: Option<String>,
+) -> Result<String, String> {
+  let temp_path = std::env::temp_dir()
+    .join(format!("palhost_share_{}.zip", &world_id))
+    .to_string_lossy()
+    .to_string();
+  let tp = temp_path.clone();
+  let app2 = app.clone();
+  // Read chunk by chunk while sending; released by `delete_temp_file`
+  mark_temp_in_use(Path::new(&temp_path));
+  tauri::async_runtime::spawn_blocking(move || {
+    export_world_sync(&app2, &account_id, &world_id, &tp, false, password.as_deref())
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Get the file size in bytes.
+#[tauri::command]
+fn get_file_size(path: String) -> Result<u64, String> {
+  let meta = fs::metadata(&path).map_err(|e| format!("Cannot read: {e}"))?;
+  Ok(meta.len())
+}
+
+/// Read a binary chunk from a file. Returns Vec<u8> → ArrayBuffer on JS side.
+#[tauri::command]
+fn read_file_chunk(path: String, offset: u64, length: u64) -> Result<Vec<u8>, String> {
+  let mut f = fs::File::open(&path).map_err(|e| format!("Cannot open: {e}"))?;
+  let file_len = f.metadata().map_err(|e| format!("Cannot read: {e}"))?.len();
+  if offset >= file_len {
+    return Ok(Vec::new());
+  }
+  // Only allocate what's left in the file, not the full requested length
+  let len = length.min(file_len - offset) as usize;
+  f.seek(std::io::SeekFrom::Start(offset)).map_err(|e| format!("Seek error: {e}"))?;
+  let mut buf = vec![0u8; len];
+  let mut filled = 0;
+  while filled < len {
+    let n = f.read(&mut buf[filled..]).map_err(|e| format!("Read error: {e}"))?;
+    if n == 0 {
+      break;
+    }
+    filled += n;
+  }
+  buf.truncate(filled);
+  Ok(buf)
+}
+
+/// Start a fresh transfer into `path`: create it empty, truncating anything
+/// a failed earlier transfer left behind. Call before the first chunk.
+#[tauri::command]
+fn begin_file_transfer(path: String) -> Result<(), String> {
+  mark_temp_in_use(Path::new(&path));
+  fs::File::create(&path).map_err(|e| format!("Cannot create {path}: {e}"))?;
+  Ok(())
+}
+
+/// Decode a base64 string and append it to a file (creates if needed).
+#[tauri::command]
+fn append_file_chunk_b64(path: String, data_b64: String) -> Result<(), String> {
+  let data = base64_decode(&data_b64)
+    .map_err(|_| "Invalid base64 data".to_string())?;
+  let mut f = fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(&path)
+    .map_err(|e| format!("Cannot open: {e}"))?;
+  f.write_all(&data).map_err(|e| format!("Write error: {e}"))?;
+  Ok(())
+}
+
+/// Get a path in the system temp directory for receiving P2P files.
+#[tauri::command]
+fn get_temp_path(filename: String) -> String {
+  std::env::temp_dir()
+    .join(&filename)
+    .to_string_lossy()
+    .to_string()
+}
+
+/// Delete a temporary file.
+#[tauri::command]
+fn delete_temp_file(path: String) -> Result<(), String> {
+  let p = Path::new(&path);
+  release_temp(p);
+  if p.exists() {
+    if p.is_dir() {
+      fs::remove_dir_all(p).map_err(|e| format!("Cannot delete: {e}"))?;
+    } else {
+      fs::remove_file(p).map_err(|e| format!("Cannot delete: {e}"))?;
+    }
+  }
+  Ok(())
+}
+
+/// Parse one `.sav` straight out of a ZIP, without extracting the archive.
+fn read_sav_from_zip(zip_path: &Path, inner_path: &str, password: Option<&str>) -> Result<Value, String> {
+  let zip_file = fs::File::open(zip_path).map_err(|e| format!("Cannot open ZIP: {e}"))?;
+  let mut archive = zip::ZipArchive::new(zip_file).map_err(|e| format!("Invalid ZIP: {e}"))?;
+  let index = archive.index_for_name(inner_path).ok_or_else(|| format!("{inner_path} not found in ZIP."))?;
+  let mut entry = open_zip_entry(&mut archive, index, password)?;
+  // The size comes from the archive, so don't trust it for the allocation
+  let mut data = Vec::with_capacity(entry.size().min(64 << 20) as usize);
+  entry.read_to_end(&mut data).map_err(|e| format!("ZIP read error: {e}"))?;
+  Ok(gvas::sav_to_json(&data)?.0)
+}
+
+/// Show the player roster of a shared world ZIP before importing it.
+#[tauri::command]
+async fn preview_shared_world(zip_path: String, password: Option<String>) -> Result<Vec<RosterEntry>, String> {
+  tauri::async_runtime::spawn_blocking(move || {
+    let zip_file = fs::File::open(&zip_path).map_err(|e| format!("Cannot open ZIP: {e}"))?;
+    let archive = zip::ZipArchive::new(zip_file).map_err(|e| format!("Invalid ZIP: {e}"))?;
+    // The shallowest Level.sav is the world's (deeper ones are game backups)
+    let level_path = archive
+      .file_names()
+      .filter(|n| *n == "Level.sav" || n.ends_with("/Level.sav"))
+      .min_by_key(|n| n.matches('/').count())
+      .ok_or("No Level.sav found in ZIP.")?
+      .to_string();
+    let json = read_sav_from_zip(Path::new(&zip_path), &level_path, password.as_deref())?;
+    Ok(
+      players_from_level_json(&json)
+        .into_iter()
+        .map(RosterEntry::from)
+        .collect(),
+    )
+  })
+  .await
+  .map_err(|e| format!("Task error: {e}"))?
+}
+
+/// Extract a ZIP file to a temp directory and return the extracted folder path.
+/// `password` is needed for encrypted exports (see `export_file_options`).
+#[tauri::command]
+fn extract_zip_to_temp(zip_path: String, password: Option<String>) -> Result<String, String> {
+  let zip_file = fs::File::open(&zip_path)
+    .map_err(|e| format!("Cannot open ZIP: {e}"))?;
+  let mut archive = zip::ZipArchive::new(zip_file)
+    .map_err(|e| format!("Invalid ZIP: {e}"))?;
+
+  let extract_dir = std::env::temp_dir().join("palhost_p2p_extract");
+  // Imported from after this returns, so it stays in use until the import
+  // flow deletes it through `delete_temp_file`
+  mark_temp_in_use(&extract_dir);
+  // Clean previous extraction
+  if extract_dir.exists() {
+    let _ = fs::remove_dir_all(&extract_dir);
+  }
+  fs::create_dir_all(&extract_dir)
+    .map_err(|e| format!("Cannot create temp dir: {e}"))?;
+
+  for i in 0..archive.len() {
+    let mut file = open_zip_entry(&mut archive, i, password.as_deref())?;
+    let out_path = extract_dir.join(file.mangled_name());
+
+    if file.is_dir() {
+      fs::create_dir_all(&out_path)
+        .map_err(|e| format!("Cannot create dir: {e}"))?;
+    } else {
+      if let Some(parent) = out_path.parent() {
+        fs::create_dir_all(parent)
+          .map_err(|e| format!("Cannot create parent: {e}"))?;
+      }
+      let mut out_file = fs::File::create(&out_path)
+        .map_err(|e| format!("Cannot create file: {e}"))?;
+      std::io::copy(&mut file, &mut out_file)
+        .map_err(|e| format!("Extract error: {e}"))?;
+    }
+  }
+
+  // Find the world folder inside (should be the first directory)
+  let mut world_folder = extract_dir.clone();
+  if let Ok(entries) = fs::read_dir(&extract_dir) {
+    for entry in entries.flatten() {
+      if entry.path().is_dir() {
+        world_folder = entry.path();
+        break;
+      }
+    }
+  }
+
+  Ok(world_folder.to_string_lossy().to_string())
+}
+
+/// Simple base64 decoder (no extra crate needed).
+fn base64_decode(input: &str) -> Result<Vec<u8>, ()> {
+  let table: [u8; 128] = {
+    let mut t = [255u8; 128];
+    for (i, &c) in b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/".iter().enumerate() {
+      t[c as usize] = i as u8;
+    }
+    t
+  };
+  let input = input.as_bytes();
+  let mut out = Vec::with_capacity(input.len() * 3 / 4);
+  let mut buf = 0u32;
+  let mut bits = 0u32;
+  for &b in input {
+    if b == b'=' || b == b'\n' || b == b'\r' || b == b' ' { continue; }
+    let val = if (b as usize) < 128 { table[b as usize] } else { 255 };
+    if val == 255 { return Err(()); }
+    buf = (buf << 6) | val as u32;
+    bits += 6;
+    if bits >= 8 {
+      bits -= 8;
+      out.push((buf >> bits) as u8);
+      buf &= (1 << bits) - 1;
+    }
+  }
+  Ok(out)
+}
+
+pub use cli::run_cli;
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+  tauri::Builder::default()
+    .setup(|app| {
+      if cfg!(debug_assertions) {
+        app.handle().plugin(
+          tauri_plugin_log::Builder::default()
+            .level(log::LevelFilter::Info)
+            .filter(|metadata| {
+              // Suppress noisy tao event-loop warnings on Windows
+              !metadata.target().starts_with("tao::")
+            })
+            .build(),
+        )?;
+      }
+      app.handle().plugin(tauri_plugin_dialog::init())?;
+      // Migrate old app-level config data into per-world files
+      let _ = migrate_legacy_config(app.handle());
+      if let Ok(config) = load_app_config(app.handle()) {
+        apply_app_config(&config);
+      }
+      let stale = std::time::Duration::from_secs(STALE_TEMP_TRANSFER_MINUTES * 60);
+      let cleaned = clean_temp_transfers_in(&std::env::temp_dir(), Some(stale), &temp_in_use());
+      if !cleaned.is_empty() {
+        log_event(log::Level::Info, &format!("removed {} stale temp transfer item(s)", cleaned.len()));
+      }
+      app.manage(SaveRootWatcher::default());
+      app.manage(WorldWatcher::default());
+      app.manage(SwapCancels::default());
+      app.manage(WorldLocks::default());
+      if let Err(e) = start_save_root_watcher(app.handle()) {
+        log_event(log::Level::Warn, &format!("save-root watcher not running: {e}"));
+      }
+      Ok(())
+    })
+    .invoke_handler(tauri::generate_handler![
+      get_app_config,
+      update_app_config,
+      set_custom_host_ids,
+      get_accounts,
+      get_accounts_detailed,
+      get_save_games_root,
+      save_root_status,
+      get_worlds,
+      get_worlds_with_counts,
+      get_full_tree,
+      get_players,
+      get_host,
+      get_pal_containers,
+      validate_player_sav,
+      inspect_sav_header,
+      normalize_save,
+      convert_sav_format,
+      dump_sav_to_json,
+      set_host_player,
+      swap_players,
+      cancel_swap,
+      set_host_in_worlds,
+      repair_missing_host,
+      resync_player_id,
+      repair_player_ids,
+      get_player_count,
+      to_player_uuid,
+      to_filename,
+      is_valid_player_id,
+      get_swap_history,
+      clear_swap_history,
+      set_swap_history_limit,
+      create_backup,
+      list_backups,
+      list_backups_detailed,
+      list_backups_paged,
+      restore_backup,
+      preview_restore,
+      delete_backup,
+      delete_all_backups,
+      rename_backup,
+      export_world,
+      validate_world_folder,
+      check_world_exists,
+      verify_shared_world_compatibility,
+      import_world,
+      import_saves_only,
+      set_world_name,
+      reset_world_name,
+      reset_world_config,
+      get_world_real_name,
+      get_world_thumbnail,
+      estimate_operation_time,
+      verify_oodle,
+      set_world_favorite,
+      diff_worlds,
+      get_base_structures,
+      list_orphan_pals,
+      get_pal_dex,
+      get_world_age,
+      find_duplicate_worlds,
+      get_character_instance_map,
+      scan_world,
+      rehearse_swap,
+      get_world_summary,
+      get_world_population,
+      prewarm_world,
+      get_guilds,
+      list_world_data_keys,
+      get_world_version,
+      verify_backup,
+      copy_backup_to,
+      import_backup_from,
+      restore_from_external_zip,
+      list_all_backups,
+      prune_backups,
+      merge_guilds,
+      fix_base_camp_ownership,
+      set_guild_name,
+      set_guild_base_camp_level,
+      set_player_level,
+      delete_player,
+      copy_player_between_worlds,
+      compact_world,
+      watch_world,
+      stop_watching,
+      get_level_sav_mtime,
+      is_palworld_running,
+      wait_for_game_idle,
+      rescan_storage,
+      get_recent_logs,
+      export_log_bundle,
+      export_world_to_temp,
+      list_temp_transfers,
+      clean_temp_transfers,
+      get_file_size,
+      read_file_chunk,
+      begin_file_transfer,
+      append_file_chunk_b64,
+      get_temp_path,
+      delete_temp_file,
+      extract_zip_to_temp,
+      preview_shared_world,
+    ])
+    .run(tauri::generate_context!())
+    .expect("error while running tauri application");
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use std::path::Path;
+
+  // ── Synthetic world fixtures ──
+  // Small hand-built Level.sav / player saves so tests don't depend on the
+  // (git-ignored) example saves.
+
+  const ZERO_GUID: &str = "00000000-0000-0000-0000-000000000000";
+  const FIXTURE_GUILD: &str = "9a000000-0000-0000-0000-000000000001";
+
+  struct FixturePlayer {
+    uid: &'static str,
+    instance_id: &'static str,
+    name: &'static str,
+    level: u8,
+  }
+
+  fn fixture_players() -> Vec<FixturePlayer> {
+    vec![
+      FixturePlayer {
+        uid: "00000001-0000-0000-0000-000000000000",
+        instance_id: "11111111-0000-0000-0000-000000000001",
+        name: "Host",
+        level: 30,
+      },
+      FixturePlayer {
+        uid: "baab90a2-0000-0000-0000-000000000000",
+        instance_id: "22222222-0000-0000-0000-000000000002",
+        name: "Friend",
+        level: 12,
+      },
+    ]
+  }
+
+  fn fixture_header() -> Value {
+    json!({
+      "magic": 0x53415647,
+      "save_game_version": 3,
+      "package_file_version_ue4": 522,
+      "package_file_version_ue5": 1009,
+      "engine_version_major": 5,
+      "engine_version_minor": 1,
+      "engine_version_patch": 1,
+      "engine_version_changelist": 0,
+      "engine_version_branch": "++UE5+Release-5.1",
+      "custom_version_format": 3,
+      "custom_versions": [],
+      "save_game_class_name": "/Script/Pal.PalWorldSaveGame",
+    })
+  }
+
+  fn guid_prop(v: &str) -> Value {
+    json!({"struct_type": "Guid", "struct_id": ZERO_GUID, "id": null, "value": v, "type": "StructProperty"})
+  }
+
+  fn character_entry(player_uid: &str, instance_id: &str, save_param: Value) -> Value {
+    json!({
+      "key": {
+        "PlayerUId": guid_prop(player_uid),
+        "InstanceId": guid_prop(instance_id),
+      },
+      "value": {
+        "RawData": {
+          "array_type": "ByteProperty",
+          "id": null,
+          "value": {
+            "object": {
+              "SaveParameter": {
+                "struct_type": "PalIndividualCharacterSaveParameter",
+                "struct_id": ZERO_GUID,
+                "id": null,
+                "value": save_param,
+                "type": "StructProperty",
+              }
+            },
+            "unknown_bytes": [0, 0, 0, 0],
+            "group_id": FIXTURE_GUILD,
+            "trailing_bytes": [0, 0, 0, 0],
+          },
+          "type": "ArrayProperty",
+          "custom_type": "character_rawdata",
+        }
+      }
+    })
+  }
+
+  fn fixture_level_json(players: &[FixturePlayer]) -> Value {
+    let mut cspm = Vec::new();
+    for p in players {
+      cspm.push(character_entry(p.uid, p.instance_id, json!({
+        "Level": {"id": null, "value": {"type": "None", "value": p.level}, "type": "ByteProperty"},
+        "NickName": {"id": null, "value": p.name, "type": "StrProperty"},
+        "IsPlayer": {"id": null, "value": true, "type": "BoolProperty"},
+      })));
+      // One pal per player
+      cspm.push(character_entry(ZERO_GUID, &format!("{}-0000000000aa", &p.instance_id[..23]), json!({
+        "CharacterID": {"id": null, "value": "SheepBall", "type": "NameProperty"},
+        "Level": {"id": null, "value": {"type": "None", "value": 5}, "type": "ByteProperty"},
+        "OwnerPlayerUId": guid_prop(p.uid),
+      })));
+    }
+    let guild = json!({
+      "group_id": FIXTURE_GUILD,
+      "group_name": "Fixture Guild",
+      "individual_character_handle_ids": players.iter()
+        .map(|p| json!({"guid": p.uid, "instance_id": p.instance_id}))
+        .collect::<Vec<_>>(),
+      "org_type": 0,
+      "leading_bytes": [0, 0, 0, 0],
+      "base_ids": [],
+      "unknown_1": 0,
+      "base_camp_level": 1,
+      "map_object_instance_ids_base_camp_points": [],
+      "guild_name": "Fixture Guild",
+      "last_guild_name_modifier_player_uid": players[0].uid,
+      "unknown_2": [0, 0, 0, 0],
+      "admin_player_uid": players[0].uid,
+      "players": players.iter()
+        .map(|p| json!({"player_uid": p.uid, "player_info": {"last_online_real_time": 0, "player_name": p.name}}))
+        .collect::<Vec<_>>(),
+      "trailing_bytes": [],
+    });
+    json!({
+      "header": fixture_header(),
+      "properties": {
+        "worldSaveData": {
+          "struct_type": "PalWorldSaveData",
+          "struct_id": ZERO_GUID,
+          "id": null,
+          "value": {
+            "CharacterSaveParameterMap": {
+              "key_type": "StructProperty",
+              "value_type": "StructProperty",
+              "key_struct_type": "",
+              "value_struct_type": "",
+              "id": null,
+              "value": cspm,
+              "type": "MapProperty",
+            },
+            "GroupSaveDataMap": {
+              "key_type": "StructProperty",
+              "value_type": "StructProperty",
+              "key_struct_type": "Guid",
+              "value_struct_type": "",
+              "id": null,
+              "value": [{
+                "key": FIXTURE_GUILD,
+                "value": {
+                  "GroupType": {"id": null, "value": {"type": "EPalGroupType", "value": "EPalGroupType::Guild"}, "type": "EnumProperty"},
+                  "RawData": {"array_type": "ByteProperty", "id": null, "value": guild, "type": "ArrayProperty"},
+                }
+              }],
+              "type": "MapProperty",
+              "custom_type": "group_rawdata_map",
+            },
+          },
+          "type": "StructProperty",
+        }
+      },
+      "trailer": "AAAAAA==",
+    })
+  }
+
+  /// Create a fresh world folder under the temp dir with Level.sav and one
+  /// player `.sav` per fixture player. Returns the world path.
+  fn write_fixture_world(name: &str) -> PathBuf {
+    let world = std::env::temp_dir().join(format!("palhost_fixture_{name}"));
+    if world.exists() {
+      fs::remove_dir_all(&world).unwrap();
+    }
+    fs::create_dir_all(world.join("Players")).unwrap();
+    let players = fixture_players();
+    let level = fixture_level_json(&players);
+    fs::write(world.join("Level.sav"), gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    for p in &players {
+      let json = build_player_sav_json(&level["header"], p.uid, p.instance_id);
+      let file = world.join("Players").join(format!("{}.sav", uuid_to_filename(p.uid)));
+      fs::write(file, gvas::json_to_sav(&json, 0x32).unwrap()).unwrap();
+    }
+    world
+  }
+
+  fn read_sav(path: &Path) -> Value {
+    gvas::sav_to_json(&fs::read(path).unwrap()).unwrap().0
+  }
+
+  #[test]
+  fn test_shared_world_compat_flags_missing_files() {
+    let world = write_fixture_world("compat");
+    let account = std::env::temp_dir().join("palhost_fixture_compat_account");
+    let report = shared_world_compat(&world, &account).unwrap();
+    assert!(report.compatible && report.engine_supported && report.host_slot_present);
+    assert_eq!(report.host_id.as_deref(), Some(DEFAULT_HOST_ID));
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+
+    fs::remove_file(world.join("Players").join(format!("{DEFAULT_HOST_ID}.sav"))).unwrap();
+    let report = shared_world_compat(&world, &account).unwrap();
+    assert!(!report.compatible && !report.host_slot_present);
+    assert_eq!(report.missing_player_files, [DEFAULT_HOST_ID]);
+    assert_eq!(report.warnings.len(), 1, "{:?}", report.warnings);
+  }
+
+  #[test]
+  fn test_cancelled_swap_is_rolled_back() {
+    let world = write_fixture_world("cancelled_swap");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    let level_before = fs::read(world.join("Level.sav")).unwrap();
+    let host_before = read_sav(&pdir.join(format!("{host}.sav")));
+
+    let cancel = AtomicBool::new(true);
+    for auto_backup in [false, true] {
+      let opts = SwapOptions { cancel: Some(&cancel), auto_backup, ..Default::default() };
+      assert_eq!(swap_players_full(&world, &pdir, host, friend, &opts), Err(SWAP_CANCELLED.to_string()));
+      assert_eq!(fs::read(world.join("Level.sav")).unwrap(), level_before);
+      assert_eq!(read_sav(&pdir.join(format!("{host}.sav"))), host_before);
+    }
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_failed_level_swap_is_rolled_back() {
+    let world = write_fixture_world("failed_swap");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    // Level.sav only fails to parse after both player files were patched
+    fs::write(world.join("Level.sav"), b"not a save").unwrap();
+    let host_before = read_sav(&pdir.join(format!("{host}.sav")));
+    let friend_before = read_sav(&pdir.join(format!("{friend}.sav")));
+
+    for auto_backup in [false, true] {
+      let opts = SwapOptions { auto_backup, ..Default::default() };
+      assert!(swap_players_full(&world, &pdir, host, friend, &opts).is_err());
+      assert_eq!(fs::read(world.join("Level.sav")).unwrap(), b"not a save");
+      assert_eq!(read_sav(&pdir.join(format!("{host}.sav"))), host_before);
+      assert_eq!(read_sav(&pdir.join(format!("{friend}.sav"))), friend_before);
+    }
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_host_swap_skips_guilds_the_new_host_is_not_in() {
+    let world = write_fixture_world("camps_skip");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    // The host runs a guild with a camp, but the friend isn't in it
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players[..1]);
+    level["properties"]["worldSaveData"]["value"]["CharacterSaveParameterMap"] =
+      fixture_level_json(&players)["properties"]["worldSaveData"]["value"]["CharacterSaveParameterMap"].clone();
+    level["properties"]["worldSaveData"]["value"]["BaseCampSaveData"] =
+      gvas::base_camp_test_property(&[(FIXTURE_GUILD, "7a000000-0000-0000-0000-00000000b0c5")], players[0].uid);
+    fs::write(world.join("Level.sav"), gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+
+    let opts = SwapOptions { fix_base_camps: true, ..Default::default() };
+    swap_players_full(&world, &pdir, host, friend, &opts).unwrap();
+    let (level, _) = gvas::sav_to_json(&fs::read(world.join("Level.sav")).unwrap()).unwrap();
+    let world_data = &level["properties"]["worldSaveData"]["value"];
+    // The old host's character now carries the friend's uid and keeps the guild
+    assert_eq!(base_camps_run_by(world_data, players[1].uid), 1);
+    assert_eq!(base_camps_run_by(world_data, players[0].uid), 0);
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_cancel_only_stops_that_worlds_swap() {
+    let cancelled = write_fixture_world("cancel_this_world");
+    let other = write_fixture_world("cancel_other_world");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    let level_before = fs::read(cancelled.join("Level.sav")).unwrap();
+
+    let cancels = SwapCancels::default();
+    let cancelled_token = cancels.register(&cancelled);
+    let other_token = cancels.register(&other);
+    assert!(cancels.cancel(&cancelled));
+    assert!(!other_token.flag.load(Ordering::Relaxed), "the other world's swap is untouched");
+    assert!(!cancels.cancel(&std::env::temp_dir().join("palhost_no_swap_here")));
+
+    let opts = SwapOptions { cancel: Some(&cancelled_token.flag), ..Default::default() };
+    let result = swap_players_full(&cancelled, &cancelled.join("Players"), host, friend, &opts);
+    assert_eq!(result, Err(SWAP_CANCELLED.to_string()));
+    assert_eq!(fs::read(cancelled.join("Level.sav")).unwrap(), level_before);
+    let opts = SwapOptions { cancel: Some(&other_token.flag), ..Default::default() };
+    swap_players_full(&other, &other.join("Players"), host, friend, &opts).unwrap();
+    assert_eq!(load_world_config(&other.join("Players")).swap_history.len(), 1);
+
+    // Finished swaps unregister, so a late cancel finds nothing to stop
+    drop(cancelled_token);
+    drop(other_token);
+    assert!(!cancels.cancel(&cancelled));
+    assert!(cancels.0.lock().unwrap().is_empty());
+
+    let _ = fs::remove_dir_all(&cancelled);
+    let _ = fs::remove_dir_all(&other);
+  }
+
+  #[test]
+  fn test_normalize_save_round_trips_and_backs_up() {
+    let world = write_fixture_world("normalize");
+    let pdir = world.join("Players");
+    let broot = world.join("backups");
+    let level_before = read_sav(&world.join("Level.sav"));
+
+    normalize_sav_in(&world, &pdir, &broot, SavTarget::Level(&[])).unwrap();
+    assert_eq!(read_sav(&world.join("Level.sav")), level_before, "normalizing keeps the data");
+    let backups = list_backup_entries(&broot);
+    assert_eq!(backups.len(), 1);
+    assert!(broot.join(&backups[0].name).join("Level.sav").exists());
+
+    // A save that doesn't parse is refused before anything is backed up
+    let friend = "baab90a2000000000000000000000000";
+    fs::write(pdir.join(format!("{friend}.sav")), b"garbage").unwrap();
+    assert!(normalize_sav_in(&world, &pdir, &broot, SavTarget::Player(friend)).is_err());
+    assert_eq!(list_backup_entries(&broot).len(), 1);
+    assert_eq!(fs::read(pdir.join(format!("{friend}.sav"))).unwrap(), b"garbage");
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_rehearse_swap_leaves_the_world_alone() {
+    let world = write_fixture_world("rehearse");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    let level_before = fs::read(world.join("Level.sav")).unwrap();
+    let host_before = fs::read(pdir.join(format!("{host}.sav"))).unwrap();
+    let temp = world.join("rehearsal");
+
+    let preview = rehearse_swap_in(&world, &temp, host, friend, MatchStrategy::InstanceId).unwrap();
+    assert!(preview.success, "{:?} {:?}", preview.error, preview.issues);
+    assert_eq!(preview.matched_by, Some(MatchStrategy::InstanceId));
+    assert_eq!(load_world_config(&temp.join("Players")).swap_history.len(), 1, "the swap ran on the copy");
+    assert_eq!(fs::read(world.join("Level.sav")).unwrap(), level_before);
+    assert_eq!(fs::read(pdir.join(format!("{host}.sav"))).unwrap(), host_before);
+    assert!(load_world_config(&pdir).swap_history.is_empty());
+
+    // A failing swap is reported, not returned as an error
+    let _ = fs::remove_dir_all(&temp);
+    let missing = "cccccccc000000000000000000000000";
+    let preview = rehearse_swap_in(&world, &temp, host, missing, MatchStrategy::InstanceId).unwrap();
+    assert!(!preview.success);
+    assert!(preview.error.is_some());
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_swap_records_ownership_fields_swapped() {
+    let world = write_fixture_world("ownership_count");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+
+    swap_players_full(&world, &pdir, host, friend, &SwapOptions::default()).unwrap();
+    let history = load_world_config(&pdir).swap_history;
+    // One pal per player, each with an OwnerPlayerUId
+    assert_eq!(history[0].ownership_fields_swapped, 2);
+    let level = read_sav(&world.join("Level.sav"));
+    let owners: Vec<String> = character_entries(&level["properties"]["worldSaveData"]["value"])
+      .filter(|c| !c.is_player())
+      .filter_map(|c| c.owner().map(String::from))
+      .collect();
+    assert_eq!(owners, [fixture_players()[1].uid, fixture_players()[0].uid]);
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_swap_rejects_identical_ids() {
+    let world = write_fixture_world("self_swap");
+    let pdir = world.join("Players");
+    let level_before = fs::read(world.join("Level.sav")).unwrap();
+
+    let err = swap_players_full(&world, &pdir, "BAAB90A2000000000000000000000000", " baab90a2000000000000000000000000", &SwapOptions::default());
+    assert_eq!(err, Err("Cannot swap a player with themselves".to_string()));
+    assert_eq!(fs::read(world.join("Level.sav")).unwrap(), level_before);
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_cancel_inside_deep_swap_removes_reconstructed_sav() {
+    let world = write_fixture_world("cancelled_deep_swap");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    fs::remove_file(pdir.join(format!("{friend}.sav"))).unwrap();
+    let level_before = fs::read(world.join("Level.sav")).unwrap();
+    let host_before = read_sav(&pdir.join(format!("{host}.sav")));
+
+    let cancel = AtomicBool::new(true);
+    for auto_backup in [false, true] {
+      let opts = SwapOptions {
+        cancel: Some(&cancel),
+        auto_backup,
+        reconstruct_missing: true,
+        deep_swap_report_every: Some(1),
+        ..Default::default()
+      };
+      assert_eq!(swap_players_full(&world, &pdir, host, friend, &opts), Err(SWAP_CANCELLED.to_string()));
+      assert!(!pdir.join(format!("{friend}.sav")).exists(), "the reconstructed file must be removed");
+      assert_eq!(fs::read(world.join("Level.sav")).unwrap(), level_before);
+      assert_eq!(read_sav(&pdir.join(format!("{host}.sav"))), host_before);
+    }
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_player_id_to_uuid_rejects_non_hex() {
+    assert_eq!(to_player_uuid("00000001000000000000000000000000.sav".into()).unwrap(), "00000001-0000-0000-0000-000000000000");
+    // 32 bytes but not 32 characters: must not panic slicing
+    let multibyte = format!("é{}", "0".repeat(30));
+    assert!(to_player_uuid(multibyte.clone()).is_err());
+    assert_eq!(filename_to_uuid(&multibyte), multibyte);
+    assert!(player_id_to_uuid("../00000001").is_err());
+  }
+
+  #[test]
+  fn test_swap_reconstructs_missing_player_sav() {
+    let world = write_fixture_world("reconstruct");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    fs::remove_file(pdir.join(format!("{friend}.sav"))).unwrap();
+
+    let err = swap_players_full(&world, &pdir, host, friend, &SwapOptions::default());
+    assert!(err.is_err(), "swap without reconstruct should fail on a missing file");
+
+    let opts = SwapOptions { reconstruct_missing: true, ..Default::default() };
+    swap_players_full(&world, &pdir, host, friend, &opts).expect("swap with reconstruct");
+
+    // Both files exist and parse; the host slot now holds the friend's character.
+    let host_json = read_sav(&pdir.join(format!("{host}.sav")));
+    let friend_json = read_sav(&pdir.join(format!("{friend}.sav")));
+    assert_eq!(
+      host_json.pointer("/properties/SaveData/value/IndividualId/value/InstanceId/value").and_then(|v| v.as_str()),
+      Some("22222222-0000-0000-0000-000000000002"),
+    );
+    assert_eq!(
+      friend_json.pointer("/properties/SaveData/value/PlayerUId/value").and_then(|v| v.as_str()),
+      Some("baab90a2-0000-0000-0000-000000000000"),
+    );
+
+    // Level.sav: the friend's character entry is now keyed by the host UID.
+    let level = read_sav(&world.join("Level.sav"));
+    assert_eq!(
+      find_player_instance_id(&level, "00000001-0000-0000-0000-000000000000").as_deref(),
+      Some("22222222-0000-0000-0000-000000000002"),
+    );
+
+    // The swap was recorded with both players' names.
+    let history = load_world_config(&pdir).swap_history;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].first_id, host);
+    assert_eq!(history[0].second_name.as_deref(), Some("Friend"));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_set_current_host_creates_no_backup() {
+    let world = write_fixture_world("noop_backup");
+    let pdir = world.join("Players");
+    let opts = SwapOptions { auto_backup: true, ..Default::default() };
+
+    let swapped = set_host_in_dir(&world, &pdir, "00000001000000000000000000000000", &opts).unwrap();
+    assert!(!swapped);
+    assert!(list_backups_dir(&pdir.join("backup")).is_empty(), "no-op host change must not back up");
+
+    // A real swap backs up exactly once, before writing.
+    assert!(set_host_in_dir(&world, &pdir, "baab90a2000000000000000000000000", &opts).unwrap());
+    assert_eq!(list_backups_dir(&pdir.join("backup")).len(), 1);
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_auto_match_falls_back_to_player_uid() {
+    let world = write_fixture_world("match_auto");
+    let pdir = world.join("Players");
+    let host = "00000001000000000000000000000000";
+    let friend = "baab90a2000000000000000000000000";
+    // Friend's .sav points at an InstanceId that isn't in Level.sav
+    let level = read_sav(&world.join("Level.sav"));
+    let stale = build_player_sav_json(&level["header"], &filename_to_uuid(friend), "99999999-0000-0000-0000-000000000009");
+    fs::write(pdir.join(format!("{friend}.sav")), gvas::json_to_sav(&stale, 0x32).unwrap()).unwrap();
+
+    let opts = SwapOptions { match_strategy: MatchStrategy::Auto, ..Default::default() };
+    swap_players_full(&world, &pdir, host, friend, &opts).unwrap();
+    let record = load_world_config(&pdir).swap_history.pop().unwrap();
+    assert_eq!(record.match_strategy, MatchStrategy::PlayerUId);
+
+    // Friend's character (found by UID) now carries the host UID
+    let level = read_sav(&world.join("Level.sav"));
+    let friend_entry = level
+      .pointer("/properties/worldSaveData/value/CharacterSaveParameterMap/value")
+      .and_then(|v| v.as_array())
+      .unwrap()
+      .iter()
+      .find(|e| e.pointer("/key/InstanceId/value").and_then(|v| v.as_str()) == Some("22222222-0000-0000-0000-000000000002"))
+      .unwrap();
+    assert_eq!(friend_entry.pointer("/key/PlayerUId/value").and_then(|v| v.as_str()), Some(filename_to_uuid(host).as_str()));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_character_instance_map_flags_stale_instance() {
+    let world = write_fixture_world("instance_map");
+    let pdir = world.join("Players");
+    let friend = "baab90a2000000000000000000000000";
+    let map = character_instance_map(&world, &pdir).unwrap();
+    assert_eq!(map.len(), 2);
+    assert!(map.iter().all(|m| m.matched));
+
+    let level = read_sav(&world.join("Level.sav"));
+    let stale = build_player_sav_json(&level["header"], &filename_to_uuid(friend), "99999999-0000-0000-0000-000000000009");
+    fs::write(pdir.join(format!("{friend}.sav")), gvas::json_to_sav(&stale, 0x32).unwrap()).unwrap();
+    let map = character_instance_map(&world, &pdir).unwrap();
+    let m = map.iter().find(|m| m.filename == format!("{friend}.sav")).unwrap();
+    assert!(!m.matched);
+    assert_eq!(m.cspm_instance_id.as_deref(), Some("22222222-0000-0000-0000-000000000002"));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_resync_player_id_fixes_mismatch() {
+    let world = write_fixture_world("resync");
+    let pdir = world.join("Players");
+    let friend = "baab90a2000000000000000000000000";
+    let moved = "cccccccc000000000000000000000000";
+    fs::copy(pdir.join(format!("{friend}.sav")), pdir.join(format!("{moved}.sav"))).unwrap();
+    let moved_sav = pdir.join(format!("{moved}.sav"));
+
+    assert!(!player_id_mismatch(&pdir.join(format!("{friend}.sav")), friend));
+    assert!(player_id_mismatch(&moved_sav, moved));
+    assert!(PLAYER_UID_CACHE.lock().unwrap().contains_key(&moved_sav), "the parse should be cached");
+
+    // Rewriting the file must invalidate the cached PlayerUId
+    assert!(resync_player_file(&moved_sav, moved).unwrap());
+    assert_eq!(read_player_sav_info(&moved_sav).unwrap().player_uid, "cccccccc-0000-0000-0000-000000000000");
+    assert!(!player_id_mismatch(&moved_sav, moved));
+    assert!(!resync_player_file(&moved_sav, moved).unwrap());
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_operation_timing_average() {
+    assert_eq!(compression_factor(b"\0\0\0\0\0\0\0\0PlM\x31"), OODLE_WORKLOAD_FACTOR);
+    assert_eq!(compression_factor(b"\0\0\0\0\0\0\0\0PlZ\x32"), 1.0);
+    assert_eq!(compression_factor(b"GVAS"), 1.0);
+
+    assert_eq!(updated_ms_per_mb(None, 2000.0, 4.0, 1.0), Some(500.0));
+    // 30% of the way from 500 towards the new 1000 ms/MB sample
+    assert_eq!(updated_ms_per_mb(Some(500.0), 4000.0, 4.0, 1.0), Some(650.0));
+    assert_eq!(updated_ms_per_mb(None, 1600.0, 1.0, OODLE_WORKLOAD_FACTOR), Some(1000.0));
+    assert_eq!(updated_ms_per_mb(Some(500.0), 1000.0, 0.0, 1.0), None);
+  }
+
+  #[test]
+  fn test_level_cache_invalidated_by_changes() {
+    let world = write_fixture_world("level_cache");
+    let level_sav = world.join("Level.sav");
+    let players = extract_players_from_level(&world).unwrap();
+    assert_eq!(cached_level_players(&level_sav).map(|p| p.len()), Some(players.len()));
+
+    // Same size and (most likely) the same mtime, different content
+    let mut bytes = fs::read(&level_sav).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    fs::write(&level_sav, &bytes).unwrap();
+    assert!(cached_level_players(&level_sav).is_none(), "a changed file must miss the cache");
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_prewarm_claim_blocks_duplicate_parses() {
+    let level_sav = std::env::temp_dir().join("palhost_fixture_prewarm").join("Level.sav");
+    let claim = PrewarmClaim::take(&level_sav).unwrap();
+    assert!(PrewarmClaim::take(&level_sav).is_none(), "a second prewarm must not start");
+    drop(claim);
+    assert!(PrewarmClaim::take(&level_sav).is_some());
+  }
+
+  #[test]
+  fn test_world_config_drops_names_when_roster_changes() {
+    let world = write_fixture_world("checksum");
+    let pdir = world.join("Players");
+    let friend = "baab90a2000000000000000000000000";
+    let mut wc = load_world_config(&pdir);
+    wc.players.insert(DEFAULT_HOST_ID.to_string(), "Alice".into());
+    wc.players.insert(friend.to_string(), "Bob".into());
+    wc.original_names.insert(friend.to_string(), "Bob".into());
+    save_world_config(&pdir, &wc).unwrap();
+    assert_eq!(load_world_config(&pdir).players.len(), 2);
+
+    fs::write(pdir.join("cccccccc000000000000000000000000.sav"), b"").unwrap();
+    assert_eq!(load_world_config(&pdir).players.len(), 2, "a new player keeps everyone's names");
+
+    fs::remove_file(pdir.join(format!("{friend}.sav"))).unwrap();
+    let wc = load_world_config(&pdir);
+    assert_eq!(wc.players.keys().collect::<Vec<_>>(), [DEFAULT_HOST_ID]);
+    assert!(wc.original_names.is_empty(), "names of removed players should be dropped");
+    let raw = fs::read_to_string(world_config_path(&pdir)).unwrap();
+    let saved: WorldConfig = serde_json::from_str(&raw).unwrap();
+    assert_eq!(saved.players.len(), 2, "loading alone must not write the config");
+    save_world_config(&pdir, &wc).unwrap();
+    let raw = fs::read_to_string(world_config_path(&pdir)).unwrap();
+    let saved: WorldConfig = serde_json::from_str(&raw).unwrap();
+    assert_eq!(saved.config_checksum, Some(roster_checksum(&pdir)));
+    assert_eq!(saved.players.keys().collect::<Vec<_>>(), [DEFAULT_HOST_ID]);
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_reset_world_config_keeps_or_clears_display_name() {
+    let world = write_fixture_world("reset_config");
+    let pdir = world.join("Players");
+    let friend = "baab90a2000000000000000000000000";
+    let seed = || {
+      let mut wc = load_world_config(&pdir);
+      wc.display_name = Some("Island".into());
+      wc.host_id = Some(friend.into());
+      wc.players.insert(friend.into(), "Bob".into());
+      wc.favorite = true;
+      save_world_config(&pdir, &wc).unwrap();
+    };
+
+    seed();
+    reset_world_config_in(&pdir, true).unwrap();
+    let wc = load_world_config(&pdir);
+    assert_eq!(wc.display_name.as_deref(), Some("Island"));
+    assert!(wc.host_id.is_none() && wc.players.is_empty() && !wc.favorite);
+
+    seed();
+    reset_world_config_in(&pdir, false).unwrap();
+    let wc = load_world_config(&pdir);
+    assert!(wc.display_name.is_none());
+    assert!(wc.host_id.is_none() && wc.players.is_empty());
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_full_backup_round_trips_world_option_and_local_data() {
+    let world = write_fixture_world("full_backup");
+    let pdir = world.join("Players");
+    fs::write(world.join("WorldOption.sav"), b"options-v1").unwrap();
+    fs::write(world.join("LocalData.sav"), b"local-v1").unwrap();
+    let ids = list_player_ids(&pdir);
+    let snap = backup_snapshot(&load_world_config(&pdir), "manual");
+
+    let light = backup_files(&pdir, &world, &pdir.join("backup"), &ids, &snap, false).unwrap();
+    assert!(light.join("Level.sav").exists());
+    assert!(!light.join("WorldOption.sav").exists());
+    fs::remove_dir_all(&light).unwrap();
+
+    let full = backup_files(&pdir, &world, &pdir.join("backup"), &ids, &snap, true).unwrap();
+    assert!(full.join("WorldOption.sav").exists());
+    assert!(full.join("LocalData.sav").exists());
+
+    fs::write(world.join("WorldOption.sav"), b"options-v2").unwrap();
+    fs::remove_file(world.join("LocalData.sav")).unwrap();
+    restore_backup_files(&full, &pdir, &world, true).unwrap();
+    assert_eq!(fs::read(world.join("WorldOption.sav")).unwrap(), b"options-v1");
+    assert_eq!(fs::read(world.join("LocalData.sav")).unwrap(), b"local-v1");
+    assert!(!pdir.join("WorldOption.sav").exists(), "world-root saves must not land in Players");
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_delete_player_in_level_hands_over_guild() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    assert_eq!(delete_player_in_level(&mut level, players[0].uid, true).unwrap(), 2);
+
+    let world_data = &level["properties"]["worldSaveData"]["value"];
+    let left: Vec<&str> = character_entries(world_data).map(|c| c.instance_id).collect();
+    assert_eq!(left, [players[1].instance_id, "22222222-0000-0000-0000-0000000000aa"]);
+    let guild = &world_data["GroupSaveDataMap"]["value"][0]["value"]["RawData"]["value"];
+    assert_eq!(guild["admin_player_uid"], players[1].uid);
+    assert_eq!(guild["players"].as_array().unwrap().len(), 1);
+    assert_eq!(guild["individual_character_handle_ids"][0]["instance_id"], players[1].instance_id);
+    assert!(delete_player_in_level(&mut level, players[0].uid, true).is_err());
+  }
+
+  #[test]
+  fn test_delete_last_guild_member_removes_the_guild() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    let groups = "/properties/worldSaveData/value/GroupSaveDataMap/value";
+    delete_player_in_level(&mut level, players[0].uid, false).unwrap();
+
+    // A guild with bases can't be left memberless
+    let mut blocked = level.clone();
+    blocked.pointer_mut(groups).unwrap()[0]["value"]["RawData"]["value"]["base_ids"] =
+      json!(["cccccccc-0000-0000-0000-0000000000bb"]);
+    let err = delete_player_in_level(&mut blocked, players[1].uid, false).unwrap_err();
+    assert!(err.contains("still has bases"), "{err}");
+    assert_eq!(blocked.pointer(groups).unwrap().as_array().unwrap().len(), 1);
+
+    delete_player_in_level(&mut level, players[1].uid, false).unwrap();
+    assert!(level.pointer(groups).unwrap().as_array().unwrap().is_empty(), "empty guild removed");
+  }
+
+  #[test]
+  fn test_delete_solo_player_removes_their_group() {
+    let players = fixture_players();
+    let solo = FixturePlayer { uid: "cccccccc-0000-0000-0000-000000000003", instance_id: "33333333-0000-0000-0000-000000000003", name: "Solo", level: 8 };
+    let mut level = fixture_level_json(&players);
+    let solo_cspm = fixture_level_json(std::slice::from_ref(&solo))["properties"]["worldSaveData"]["value"]["CharacterSaveParameterMap"]["value"][0].clone();
+    let world_data = level.pointer_mut("/properties/worldSaveData/value").unwrap();
+    world_data["CharacterSaveParameterMap"]["value"].as_array_mut().unwrap().push(solo_cspm);
+    let indep = json!({
+      "group_id": "eeeeeeee-0000-0000-0000-000000000005",
+      "group_name": "",
+      "individual_character_handle_ids": [{"guid": solo.uid, "instance_id": solo.instance_id}],
+      "org_type": 0,
+      "base_ids": [],
+      "base_camp_level": 1,
+      "map_object_instance_ids_base_camp_points": [],
+      "guild_name": "Solo Camp",
+      "player_uid": solo.uid,
+      "guild_name_2": "Solo Camp",
+      "player_info": {"last_online_real_time": 0, "player_name": solo.name},
+    });
+    world_data["GroupSaveDataMap"]["value"].as_array_mut().unwrap().push(json!({
+      "key": "eeeeeeee-0000-0000-0000-000000000005",
+      "value": {
+        "GroupType": {"id": null, "value": {"type": "EPalGroupType", "value": "EPalGroupType::IndependentGuild"}, "type": "EnumProperty"},
+        "RawData": {"array_type": "ByteProperty", "id": null, "value": indep, "type": "ArrayProperty"},
+      }
+    }));
+    let groups = "/properties/worldSaveData/value/GroupSaveDataMap/value";
+
+    // A solo group with bases can't be left pointing at nobody
+    let mut blocked = level.clone();
+    blocked.pointer_mut(groups).unwrap()[1]["value"]["RawData"]["value"]["base_ids"] =
+      json!(["cccccccc-0000-0000-0000-0000000000bb"]);
+    let err = delete_player_in_level(&mut blocked, &solo.uid.to_ascii_uppercase(), false).unwrap_err();
+    assert!(err.contains("still has bases"), "{err}");
+
+    delete_player_in_level(&mut level, &solo.uid.to_ascii_uppercase(), false).unwrap();
+    let guilds = guilds_from_world_data(&level["properties"]["worldSaveData"]["value"]);
+    assert_eq!(guilds.len(), 1, "solo group removed");
+    assert_eq!(guilds[0].id, FIXTURE_GUILD);
+    assert_eq!(guilds[0].member_count, 2);
+  }
+
+  #[test]
+  fn test_independent_guild_players_are_listed() {
+    let solo = "cccccccc-0000-0000-0000-000000000003";
+    let mut level = fixture_level_json(&fixture_players());
+    let indep = json!({
+      "group_id": "eeeeeeee-0000-0000-0000-000000000005",
+      "group_name": "",
+      "individual_character_handle_ids": [{"guid": solo, "instance_id": "33333333-0000-0000-0000-000000000003"}],
+      "org_type": 0,
+      "base_camp_level": 2,
+      "map_object_instance_ids_base_camp_points": [],
+      "guild_name": "Solo Camp",
+      "player_uid": solo,
+      "guild_name_2": "Solo Camp",
+      "player_info": {"last_online_real_time": 0, "player_name": "Solo"},
+    });
+    level.pointer_mut("/properties/worldSaveData/value/GroupSaveDataMap/value").unwrap().as_array_mut().unwrap().push(json!({
+      "key": "eeeeeeee-0000-0000-0000-000000000005",
+      "value": {
+        "GroupType": {"id": null, "value": {"type": "EPalGroupType", "value": "EPalGroupType::IndependentGuild"}, "type": "EnumProperty"},
+        "RawData": {"array_type": "ByteProperty", "id": null, "value": indep, "type": "ArrayProperty"},
+      }
+    }));
+    // Go through the binary format so the IndependentGuild codec is covered too
+    let (level, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+
+    let players = players_from_level_json(&level);
+    let solo_info = players.iter().find(|p| p.uuid == solo).expect("solo player listed");
+    assert_eq!(solo_info.name, "Solo");
+    assert_eq!(solo_info.guild_name, "Solo Camp");
+    assert_eq!(players.iter().find(|p| p.name == "Friend").unwrap().guild_name, "Fixture Guild");
+
+    let guilds = guilds_from_world_data(&level["properties"]["worldSaveData"]["value"]);
+    let types: Vec<&str> = guilds.iter().map(|g| g.group_type.as_str()).collect();
+    assert_eq!(types, ["Guild", "IndependentGuild"]);
+    assert_eq!(guilds[1].member_count, 1);
+    assert_eq!(guilds[1].base_camp_level, Some(2));
+  }
+
+  #[test]
+  fn test_import_saves_only_backs_up_and_copies() {
+    let source = write_fixture_world("saves_only_src");
+    let world = write_fixture_world("saves_only_dst");
+    let pdir = world.join("Players");
+    let host = uuid_to_filename(fixture_players()[0].uid);
+    fs::write(pdir.join(format!("{host}.sav")), b"old host").unwrap();
+    fs::create_dir_all(world.join("backup").join("world")).unwrap();
+    fs::remove_file(source.join("Players").join(format!("{}.sav", uuid_to_filename(fixture_players()[1].uid)))).unwrap();
+
+    let copied = import_saves_into(&source, &world, &pdir, &pdir.join("backup")).unwrap();
+    assert_eq!(copied, 1);
+    assert_eq!(fs::read(pdir.join(format!("{host}.sav"))).unwrap(), fs::read(source.join("Players").join(format!("{host}.sav"))).unwrap());
+    assert!(world.join("backup").join("world").is_dir());
+    let backup = pdir.join("backup").join(&list_backups_dir(&pdir.join("backup"))[0]);
+    assert_eq!(fs::read(backup.join(format!("{host}.sav"))).unwrap(), b"old host");
+
+    // A broken player save stops the import before anything is touched
+    let level_before = fs::read(world.join("Level.sav")).unwrap();
+    let host_before = fs::read(pdir.join(format!("{host}.sav"))).unwrap();
+    fs::write(source.join("Players").join(format!("{host}.sav")), b"not a save").unwrap();
+    assert!(import_saves_into(&source, &world, &pdir, &pdir.join("backup")).unwrap_err().contains(&host));
+    assert_eq!(fs::read(world.join("Level.sav")).unwrap(), level_before);
+    assert_eq!(fs::read(pdir.join(format!("{host}.sav"))).unwrap(), host_before);
+    assert_eq!(list_backups_dir(&pdir.join("backup")).len(), 1);
+
+    fs::remove_file(source.join("Level.sav")).unwrap();
+    assert!(import_saves_into(&source, &world, &pdir, &pdir.join("backup")).is_err());
+
+    let _ = fs::remove_dir_all(&source);
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_log_bundle_excludes_saves() {
+    let world = write_fixture_world("log_bundle");
+    let pdir = world.join("Players");
+    save_world_config(&pdir, &load_world_config(&pdir)).unwrap();
+    let dest = world.join("bundle.zip");
+    write_log_bundle(&dest, &world, &pdir, json!({"worldId": "fixture"})).unwrap();
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(&dest).unwrap()).unwrap();
+    let mut names: Vec<String> = archive.file_names().map(String::from).collect();
+    names.sort();
+    assert_eq!(names, ["host_switcher.json", "level_header.json", "logs.json", "metadata.json", "scan.json"]);
+    let mut raw = String::new();
+    archive.by_name("metadata.json").unwrap().read_to_string(&mut raw).unwrap();
+    let metadata: Value = serde_json::from_str(&raw).unwrap();
+    assert!(metadata["files"].as_array().unwrap().iter().any(|f| f["path"] == "Level.sav"));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_backup_zip_round_trip() {
+    let world = write_fixture_world("backup_zip");
+    let pdir = world.join("Players");
+    let ids = list_player_ids(&pdir);
+    let snap = backup_snapshot(&load_world_config(&pdir), "manual");
+    let backup = backup_files(&pdir, &world, &pdir.join("backup"), &ids, &snap, false).unwrap();
+    let zip_path = world.join("backup.zip");
+    zip_backup_dir(&backup, &zip_path).unwrap();
+
+    let other_root = world.join("imported");
+    let name = import_backup_zip(&zip_path, &other_root).unwrap();
+    assert_eq!(name, backup.file_name().unwrap().to_string_lossy());
+    assert_eq!(fs::read(other_root.join(&name).join("Level.sav")).unwrap(), fs::read(backup.join("Level.sav")).unwrap());
+    assert!(other_root.join(&name).join("config_snapshot.json").exists());
+    assert!(import_backup_zip(&zip_path, &other_root).is_err(), "existing backup must not be overwritten");
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_restore_from_zip_rejects_a_bad_zip() {
+    let world = write_fixture_world("external_zip");
+    let pdir = world.join("Players");
+    let broot = world.join("backups");
+    let level = fs::read(world.join("Level.sav")).unwrap();
+    let temp = world.join("temp");
+
+    let junk = world.join("junk.zip");
+    fs::write(&junk, b"not a zip").unwrap();
+    let err = restore_from_zip_in(&junk, &temp, &pdir, &world, &broot, true).unwrap_err();
+    assert!(err.contains("Invalid ZIP"), "{err}");
+
+    // A well-formed ZIP that isn't a backup
+    let stray = world.join("stray.zip");
+    {
+      let mut zip = zip::ZipWriter::new(fs::File::create(&stray).unwrap());
+      zip.start_file("notes.txt", SimpleFileOptions::default()).unwrap();
+      zip.write_all(b"hello").unwrap();
+      zip.finish().unwrap();
+    }
+    assert!(restore_from_zip_in(&stray, &temp, &pdir, &world, &broot, true).is_err());
+
+    assert_eq!(fs::read(world.join("Level.sav")).unwrap(), level, "the world must be untouched");
+    assert!(!broot.exists(), "no pre-restore backup for a rejected ZIP");
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_swap_canonicalizes_case_variant_files() {
+    let world = write_fixture_world("case_variants");
+    let pdir = world.join("Players");
+    let host = uuid_to_filename(fixture_players()[0].uid);
+    let friend = uuid_to_filename(fixture_players()[1].uid);
+    let upper = format!("{}.sav", friend.to_ascii_uppercase());
+    // Case variants can't coexist on a case-insensitive file system
+    if pdir.join(&upper).exists() {
+      return;
+    }
+    fs::copy(pdir.join(format!("{friend}.sav")), pdir.join(&upper)).unwrap();
+    assert_eq!(list_player_ids(&pdir).len(), 2);
+    assert_eq!(player_file_variants(&pdir, &friend).len(), 2);
+
+    let names = |dir: &Path| -> Vec<String> {
+      let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|n| n.contains(".sav"))
+        .collect();
+      names.sort();
+      names
+    };
+    let before = names(&pdir);
+
+    // A cancelled swap leaves the variants as they were
+    let cancel = AtomicBool::new(true);
+    for auto_backup in [false, true] {
+      let broot = world.join(format!("backups_{auto_backup}"));
+      let opts = SwapOptions { cancel: Some(&cancel), auto_backup, backup_root: Some(&broot), deep_swap_report_every: Some(1), ..Default::default() };
+      assert_eq!(swap_players_full(&world, &pdir, &host, &friend, &opts), Err(SWAP_CANCELLED.to_string()));
+      assert_eq!(names(&pdir), before);
+    }
+    let backup = world.join("backups_true").join(&list_backups_dir(&world.join("backups_true"))[0]);
+    assert!(names(&backup).contains(&upper), "the backup keeps both variants");
+    assert!(names(&backup).contains(&format!("{friend}.sav")));
+
+    swap_players_full(&world, &pdir, &host, &friend, &SwapOptions::default()).unwrap();
+
+    let names = names(&pdir);
+    assert!(names.contains(&format!("{friend}.sav")));
+    assert!(!names.contains(&upper));
+    assert_eq!(names.iter().filter(|n| n.contains(".case-dup-")).count(), 1);
+    assert_eq!(player_file_variants(&pdir, &friend).len(), 1);
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_scan_world_dir_findings() {
+    let world = write_fixture_world("scan");
+    let pdir = world.join("Players");
+    let quiet = |_: f64, _: &str| {};
+    let clean = scan_world_dir(&world, &pdir, &quiet);
+    assert!(clean.iter().all(|f| f.severity == ScanSeverity::Info), "{clean:?}");
+
+    let friend = uuid_to_filename(fixture_players()[1].uid);
+    fs::remove_file(pdir.join(format!("{friend}.sav"))).unwrap();
+    fs::write(pdir.join("00000001000000000000000000000000.sav"), b"garbage").unwrap();
+    let findings = scan_world_dir(&world, &pdir, &quiet);
+    assert_eq!(findings[0].severity, ScanSeverity::Error);
+    assert_eq!(findings[0].category, "player");
+    assert!(findings.iter().any(|f| f.category == "references" && f.target.as_deref() == Some(friend.as_str())));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_preview_restore_lists_without_writing() {
+    let world = write_fixture_world("preview_restore");
+    let pdir = world.join("Players");
+    let ids = list_player_ids(&pdir);
+    let mut wc = load_world_config(&pdir);
+    wc.display_name = Some("Old name".into());
+    let backup = backup_files(&pdir, &world, &pdir.join("backup"), &ids[..1], &backup_snapshot(&wc, "manual"), false).unwrap();
+    wc.display_name = Some("New name".into());
+    save_world_config(&pdir, &wc).unwrap();
+    fs::remove_file(pdir.join(format!("{}.sav", ids[0]))).unwrap();
+    let zip_path = world.join("backup.zip");
+    zip_backup_dir(&backup, &zip_path).unwrap();
+
+    for source in [&backup, &zip_path] {
+      let preview = preview_restore_files(source, &pdir, &world, false).unwrap();
+      let files: Vec<(&str, bool)> = preview.files.iter().map(|f| (f.path.as_str(), f.overwrites)).collect();
+      assert_eq!(files, [("Level.sav", true), (format!("Players/{}.sav", ids[0]).as_str(), false)]);
+      assert!(preview.has_snapshot);
+      assert!(preview.display_name_changes);
+      assert_eq!(preview.restored_display_name.as_deref(), Some("Old name"));
+      assert!(!preview_restore_files(source, &pdir, &world, true).unwrap().display_name_changes);
+    }
+    assert!(!pdir.join(format!("{}.sav", ids[0])).exists());
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_restore_preserves_display_name_by_default() {
+    let world = write_fixture_world("preserve_name");
+    let pdir = world.join("Players");
+    let ids = list_player_ids(&pdir);
+    let mut wc = load_world_config(&pdir);
+    wc.display_name = Some("Old name".into());
+    save_world_config(&pdir, &wc).unwrap();
+    let backup = backup_files(&pdir, &world, &pdir.join("backup"), &ids, &backup_snapshot(&wc, "manual"), false).unwrap();
+
+    wc.display_name = Some("New name".into());
+    wc.favorite = true;
+    save_world_config(&pdir, &wc).unwrap();
+
+    restore_backup_files(&backup, &pdir, &world, true).unwrap();
+    let restored = load_world_config(&pdir);
+    assert_eq!(restored.display_name.as_deref(), Some("New name"));
+    assert!(restored.favorite);
+
+    restore_backup_files(&backup, &pdir, &world, false).unwrap();
+    assert_eq!(load_world_config(&pdir).display_name.as_deref(), Some("Old name"));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_scan_world_data_keys_matches_full_parse() {
+    let world = write_fixture_world("scan_keys");
+    let data = fs::read(world.join("Level.sav")).unwrap();
+    let (json, _) = gvas::sav_to_json(&data).unwrap();
+    let (header, keys) = gvas::scan_world_data_keys(&data[..]).expect("scan");
+    assert_eq!(header, json["header"]);
+    let file = fs::File::open(world.join("Level.sav")).unwrap();
+    assert_eq!(gvas::scan_sav_header(std::io::BufReader::new(file)).unwrap(), (json["header"].clone(), 0x32));
+
+    let wsd = json["properties"]["worldSaveData"]["value"].as_object().unwrap();
+    let mut names: Vec<&str> = keys.iter().map(|k| k.name.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(names, wsd.keys().map(|k| k.as_str()).collect::<Vec<_>>());
+    let cspm = keys.iter().find(|k| k.name == "CharacterSaveParameterMap").unwrap();
+    assert_eq!(cspm.entry_count, Some(4));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_extended_length_path() {
+    assert_eq!(extended_length_path(r"C:\Users\me\Saves"), r"\\?\C:\Users\me\Saves");
+    assert_eq!(extended_length_path("D:/OneDrive/Saves"), r"\\?\D:\OneDrive\Saves");
+    assert_eq!(extended_length_path(r"\\nas\share\Saves"), r"\\?\UNC\nas\share\Saves");
+    assert_eq!(extended_length_path(r"\\?\C:\already"), r"\\?\C:\already");
+    assert_eq!(extended_length_path(r"relative\dir"), r"relative\dir");
+  }
+
+  #[cfg(windows)]
+  #[test]
+  fn test_backup_files_beyond_max_path() {
+    let base = std::env::temp_dir().join("palhost_fixture_long_path");
+    let _ = fs::remove_dir_all(long_path(&base));
+    let mut world = base.clone();
+    while world.as_os_str().len() <= 260 {
+      world = world.join("a_rather_long_onedrive_synced_folder_name");
+    }
+    let players = world.join("Players");
+    fs::create_dir_all(long_path(&players)).unwrap();
+    fs::write(long_path(&world.join("Level.sav")), b"level").unwrap();
+    fs::write(long_path(&players.join("00000000000000000000000000000001.sav")), b"host").unwrap();
+
+    let ids = vec!["00000000000000000000000000000001".to_string()];
+    let dir = backup_files(&players, &world, &world.join("backups"), &ids, &BackupSnapshot::default(), false).unwrap();
+    assert!(dir.as_os_str().len() > 260);
+    assert_eq!(fs::read(long_path(&dir.join("Level.sav"))).unwrap(), b"level");
+    assert_eq!(fs::read(long_path(&dir.join("00000000000000000000000000000001.sav"))).unwrap(), b"host");
+
+    let _ = fs::remove_dir_all(long_path(&base));
+  }
+
+  #[test]
+  fn test_plan_dir_merge_is_read_only() {
+    let root = std::env::temp_dir().join("palhost_fixture_import_plan");
+    let _ = fs::remove_dir_all(&root);
+    let (src, dest) = (root.join("src"), root.join("dest"));
+    fs::create_dir_all(src.join("Players")).unwrap();
+    fs::create_dir_all(src.join("backup").join("world").join("2024.01.01-00.00.00")).unwrap();
+    fs::create_dir_all(src.join("backup").join("world").join("2024.02.01-00.00.00")).unwrap();
+    fs::write(src.join("Level.sav"), b"new").unwrap();
+    fs::write(src.join("Players").join("00000000000000000000000000000001.sav"), b"host").unwrap();
+    fs::create_dir_all(&dest).unwrap();
+    fs::write(dest.join("Level.sav"), b"old").unwrap();
+
+    let mut incoming = std::collections::HashSet::new();
+    let mut actions = Vec::new();
+    plan_dir_merge(&src, &dest, "", &import_skip_dirs(&src), &mut incoming, &mut actions);
+    let find = |path: &str| actions.iter().find(|a| a.path == path).map(|a| a.kind);
+    assert_eq!(find("Level.sav"), Some(ImportActionKind::Overwrite));
+    assert_eq!(find("Players/00000000000000000000000000000001.sav"), Some(ImportActionKind::Create));
+    assert_eq!(find("backup/world/2024.01.01-00.00.00"), Some(ImportActionKind::SkipBackup));
+    assert_eq!(find("backup/world/2024.02.01-00.00.00"), None);
+    assert!(!dest.join("Players").exists());
+    assert_eq!(fs::read(dest.join("Level.sav")).unwrap(), b"old");
+
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn test_level_meta_world_name() {
+    let root = std::env::temp_dir().join("palhost_fixture_level_meta");
+    let world = root.join("w");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(world.join("Players")).unwrap();
+    assert_eq!(read_level_meta_name(&world).unwrap(), None);
+
+    let meta = json!({
+      "header": fixture_header(),
+      "properties": {
+        "SaveData": {
+          "struct_type": "PalLevelMetaSaveData",
+          "struct_id": ZERO_GUID,
+          "id": null,
+          "value": {
+            "WorldName": {"id": null, "value": "Sunny Isles", "type": "StrProperty"},
+          },
+          "type": "StructProperty",
+        }
+      },
+      "trailer": "AAAAAA==",
+    });
+    fs::write(world.join("LevelMeta.sav"), gvas::json_to_sav(&meta, 0x32).unwrap()).unwrap();
+    assert_eq!(read_level_meta_name(&world).unwrap().as_deref(), Some("Sunny Isles"));
+
+    // The in-game name is reported on its own, not as the user's name
+    let info = &list_worlds(&root, true, None)[0];
+    assert_eq!((info.display_name.as_deref(), info.in_game_name.as_deref()), (None, Some("Sunny Isles")));
+    let wc = WorldConfig { display_name: Some("Ours".into()), ..Default::default() };
+    save_world_config(&world.join("Players"), &wc).unwrap();
+    let info = &list_worlds(&root, true, None)[0];
+    assert_eq!((info.display_name.as_deref(), info.in_game_name.as_deref()), (Some("Ours"), Some("Sunny Isles")));
+
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn test_sanitize_world_name() {
+    assert_eq!(sanitize_world_name("  My: World?. . ").unwrap(), "My_ World_");
+    assert_eq!(sanitize_world_name("a/b\\c").unwrap(), "a_b_c");
+    assert!(sanitize_world_name(" ... ").is_err());
+    assert!(sanitize_world_name("con").is_err());
+    assert!(sanitize_world_name("LPT1.txt").is_err());
+    assert_eq!(sanitize_world_name("COM0").unwrap(), "COM0");
+    assert_eq!(sanitize_world_name("Console").unwrap(), "Console");
+  }
+
+  #[test]
+  fn test_validate_world_dir_modes() {
+    let root = std::env::temp_dir().join("palhost_fixture_validate");
+    let _ = fs::remove_dir_all(&root);
+    let level_only = root.join("LevelOnly");
+    fs::create_dir_all(&level_only).unwrap();
+    fs::write(level_only.join("Level.sav"), b"").unwrap();
+    let extracted = root.join("Extracted");
+    fs::create_dir_all(extracted.join("Extracted").join("Players")).unwrap();
+
+    let strict = validate_world_dir(&level_only, ValidationMode::Strict).unwrap();
+    assert_eq!(strict.completeness, WorldCompleteness::LevelOnly);
+    let level_file = level_only.join("Level.sav");
+    assert!(validate_world_dir(&level_file, ValidationMode::Strict).is_err());
+    let lenient = validate_world_dir(&level_file, ValidationMode::Lenient).unwrap();
+    assert_eq!(lenient.completeness, WorldCompleteness::LevelOnly);
+    assert_eq!(lenient.name, "LevelOnly");
+
+    let saves_only = root.join("SavesOnly");
+    fs::create_dir_all(&saves_only).unwrap();
+    fs::write(saves_only.join("LevelMeta.sav"), b"").unwrap();
+    let saves = validate_world_dir(&saves_only, ValidationMode::Strict).unwrap();
+    assert_eq!(saves.completeness, WorldCompleteness::SavesOnly);
+    assert!(validate_world_dir(&root.join("Extracted").join("Extracted").join("Players"), ValidationMode::Strict).is_err());
+
+    let nested = validate_world_dir(&extracted, ValidationMode::Strict).unwrap();
+    assert_eq!(nested.name, "Extracted");
+    assert_eq!(nested.completeness, WorldCompleteness::MissingLevel);
+    assert!(nested.path.ends_with("Extracted"));
+
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn test_backups_sort_by_timestamp_with_or_without_prefix() {
+    let root = std::env::temp_dir().join(format!("palhost_fixture_prefixed_backups_{}", uuid::Uuid::new_v4().simple()));
+    for name in ["2024-01-01_10-00-00", "pre-swap_2024-01-02_10-00-00", "2024-01-03_10-00-00", "pre-normalize_2023-12-31_10-00-00"] {
+      fs::create_dir_all(root.join(name)).unwrap();
+    }
+    assert_eq!(
+      parse_backup_timestamp("pre-guild-rename_2024-01-02_10-00-00"),
+      parse_backup_timestamp("2024-01-02_10-00-00"),
+    );
+    // Any label works, and a same-second counter doesn't hide the stamp
+    let stamp = parse_backup_timestamp("2024-01-02_10-00-00");
+    assert!(stamp.is_some());
+    assert_eq!(parse_backup_timestamp("pre-unknown_2024-01-02_10-00-00"), stamp);
+    assert_eq!(parse_backup_timestamp("2024-01-02_10-00-00-2"), stamp);
+    assert_eq!(parse_backup_timestamp("manual_2024-01-02_10-00-00-12"), stamp);
+    assert!(parse_backup_timestamp("_2024-01-02_10-00-00").is_none());
+    assert!(parse_backup_timestamp("label2024-01-02_10-00-00").is_none());
+    assert!(parse_backup_timestamp("2024-01-02").is_none());
+
+    assert_eq!(
+      list_backups_dir(&root),
+      ["2024-01-03_10-00-00", "pre-swap_2024-01-02_10-00-00", "2024-01-01_10-00-00", "pre-normalize_2023-12-31_10-00-00"],
+    );
+
+    // Two backups in the same second get different folders
+    let world = write_fixture_world("same_second_backups");
+    let broot = world.join("Players").join("backup");
+    let snapshot = backup_snapshot(&WorldConfig::default(), "manual");
+    let first = backup_files(&world.join("Players"), &world, &broot, &[], &snapshot, false).unwrap();
+    let second = backup_files(&world.join("Players"), &world, &broot, &[], &snapshot, false).unwrap();
+    assert_ne!(first, second);
+    assert_eq!(list_backups_dir(&broot).len(), 2);
+    let _ = fs::remove_dir_all(&world);
+
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn test_character_nick_name_and_history_trim() {
+    let level = fixture_level_json(&fixture_players());
+    let instance = "22222222-0000-0000-0000-000000000002";
+    assert_eq!(character_nick_name(&level, instance).as_deref(), Some("Friend"));
+    assert_eq!(character_nick_name(&level, &instance.to_ascii_uppercase()).as_deref(), Some("Friend"));
+
+    let mut wc = WorldConfig { swap_history_limit: Some(2), ..Default::default() };
+    wc.swap_history = (0..5).map(|i| SwapRecord { first_id: i.to_string(), ..Default::default() }).collect();
+    trim_swap_history(&mut wc);
+    let kept: Vec<_> = wc.swap_history.iter().map(|r| r.first_id.as_str()).collect();
+    assert_eq!(kept, ["3", "4"]);
+  }
+
+  #[test]
+  fn test_repair_missing_host_moves_player_into_slot() {
+    let world = write_fixture_world("repair_host");
+    let pdir = world.join("Players");
+    let broot = world.join("backups");
+    let stray = "cc000000000000000000000000000000";
+    move_player_to_slot(&world, &pdir, DEFAULT_HOST_ID, stray).unwrap();
+    let slots = vec![DEFAULT_HOST_ID.to_string()];
+
+    assert!(repair_missing_host_in_dir(&world, &pdir, &broot, "dd000000000000000000000000000000", &slots).is_err());
+    repair_missing_host_in_dir(&world, &pdir, &broot, stray, &slots).unwrap();
+    assert!(!pdir.join(format!("{stray}.sav")).exists());
+    let host_json = read_sav(&pdir.join(format!("{DEFAULT_HOST_ID}.sav")));
+    assert_eq!(
+      host_json.pointer("/properties/SaveData/value/PlayerUId/value").and_then(|v| v.as_str()),
+      Some("00000001-0000-0000-0000-000000000000"),
+    );
+    let players = extract_players_from_level(&world).unwrap();
+    assert!(players.iter().any(|p| p.filename == DEFAULT_HOST_ID));
+    assert_eq!(list_backups_dir(&broot).len(), 1);
+    assert!(repair_missing_host_in_dir(&world, &pdir, &broot, DEFAULT_HOST_ID, &slots).is_err());
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_merge_guilds_in_level() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    let other = "55555555-0000-0000-0000-000000000005";
+    let world_data = level.pointer_mut("/properties/worldSaveData/value").unwrap();
+    let groups = world_data["GroupSaveDataMap"]["value"].as_array_mut().unwrap();
+    let mut second = groups[0].clone();
+    for (group, p) in [(&mut groups[0], &players[0]), (&mut second, &players[1])] {
+      let rd = group.pointer_mut("/value/RawData/value").unwrap();
+      rd["players"] = json!([{"player_uid": p.uid, "player_info": {"last_online_real_time": 0, "player_name": p.name}}]);
+      rd["individual_character_handle_ids"] = json!([{"guid": p.uid, "instance_id": p.instance_id}]);
+    }
+    second["key"] = json!(other);
+    second["value"]["RawData"]["value"]["group_id"] = json!(other);
+    groups.push(second);
+    // The friend and their pal belong to the second guild
+    for entry in &mut world_data["CharacterSaveParameterMap"]["value"].as_array_mut().unwrap()[2..] {
+      entry["value"]["RawData"]["value"]["group_id"] = json!(other);
+    }
+    // One camp each; every other byte of the blob spells the source guild
+    let camp_owner = "77777777-0000-0000-0000-000000000007";
+    world_data["BaseCampSaveData"] =
+      gvas::base_camp_test_property(&[(FIXTURE_GUILD, camp_owner), (other, camp_owner)], other);
+
+    assert!(merge_guilds_in_level(&mut level, other, other).is_err());
+    assert!(merge_guilds_in_level(&mut level, other, "66666666-0000-0000-0000-000000000006").is_err());
+    // RawData that didn't decode would silently drop the source's members
+    let mut undecoded = level.clone();
+    undecoded.pointer_mut("/properties/worldSaveData/value/GroupSaveDataMap/value/1/value/RawData/value").unwrap()["players"] = Value::Null;
+    assert!(merge_guilds_in_level(&mut undecoded, other, FIXTURE_GUILD).unwrap_err().contains("Source guild"));
+    let unmerged = level.clone();
+
+    let source_raw = merge_guilds_in_level(&mut level, &other.to_ascii_uppercase(), FIXTURE_GUILD).unwrap();
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    let world_data = &check["properties"]["worldSaveData"]["value"];
+    check_guild_merge(world_data, other, FIXTURE_GUILD, &source_raw).unwrap();
+    let before = &unmerged["properties"]["worldSaveData"]["value"];
+    assert!(check_guild_merge(before, other, FIXTURE_GUILD, &source_raw).is_err(), "source still there");
+    let mut lost_member = world_data.clone();
+    lost_member["GroupSaveDataMap"]["value"][0]["value"]["RawData"]["value"]["players"].as_array_mut().unwrap().pop();
+    assert!(check_guild_merge(&lost_member, other, FIXTURE_GUILD, &source_raw).unwrap_err().contains("players"));
+    let groups = world_data["GroupSaveDataMap"]["value"].as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["key"], FIXTURE_GUILD);
+    assert_eq!(groups[0]["value"]["RawData"]["value"]["players"].as_array().map(Vec::len), Some(2));
+    assert!(character_entries(world_data).all(|c| c.raw["group_id"] == FIXTURE_GUILD));
+    assert_eq!(
+      level["properties"]["worldSaveData"]["value"]["BaseCampSaveData"],
+      gvas::base_camp_test_property(&[(FIXTURE_GUILD, camp_owner), (FIXTURE_GUILD, camp_owner)], other),
+      "only the camps' group ids may change",
+    );
+  }
+
+  #[test]
+  fn test_host_in_world_scans_only_the_host() {
+    let world = write_fixture_world("host_cache");
+    let pdir = world.join("Players");
+    let level_sav = world.join("Level.sav");
+    assert!(cached_level_players(&level_sav).is_none());
+
+    let host = host_in_world(&world, &pdir).unwrap().unwrap();
+    assert_eq!(host.id, DEFAULT_HOST_ID);
+    assert!(host.is_host && !host.id_mismatch);
+    assert_eq!((host.name.as_str(), host.level, host.pals_count), ("Host", 30, 1));
+    assert_eq!(host.guild_name, "Fixture Guild");
+    assert!(cached_level_players(&level_sav).is_none(), "a partial scan isn't cached");
+
+    // The scan agrees with the full parse, which is then served from the cache
+    let full = extract_players_from_level(&world).unwrap();
+    let full_host = build_players(std::slice::from_ref(&host.id), &host.id, &full).pop().unwrap();
+    assert_eq!(serde_json::to_value(&full_host).unwrap(), serde_json::to_value(&host).unwrap());
+    assert!(cached_level_players(&level_sav).is_some());
+    assert_eq!(host_in_world(&world, &pdir).unwrap().unwrap().name, host.name);
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_world_lock_file_is_shared_and_released() {
+    let data_dir = std::env::temp_dir().join(format!("palhost_fixture_locks_{}", uuid::Uuid::new_v4().simple()));
+    let lock_file = world_lock_path(&data_dir, "acct/world");
+    assert_eq!(lock_file, world_lock_path(&data_dir, "ACCT/World"));
+
+    let locks = WorldLocks::default();
+    let guard = lock_world_in(&locks, &data_dir, "acct", "world").unwrap();
+    assert!(lock_file.exists());
+    assert!(lock_world_in(&locks, &data_dir, "acct", "world").is_err());
+    drop(guard);
+    assert!(!lock_file.exists());
+
+    // A lock held by another live process keeps the world busy; a dead
+    // owner's is taken over
+    fs::write(&lock_file, "4000000").unwrap();
+    assert_eq!(claim_lock_file(&lock_file, |_| true), Err("World is busy".to_string()));
+    assert_eq!(claim_lock_file(&lock_file, |_| false).unwrap(), lock_file);
+    assert_eq!(fs::read_to_string(&lock_file).unwrap(), std::process::id().to_string());
+
+    let _ = fs::remove_dir_all(&data_dir);
+  }
+
+  #[test]
+  fn test_list_worlds_skips_non_world_dirs() {
+    let root = std::env::temp_dir().join("palhost_fixture_mixed_account");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("WorldWithLevel")).unwrap();
+    fs::write(root.join("WorldWithLevel").join("Level.sav"), b"").unwrap();
+    fs::create_dir_all(root.join("WorldWithPlayers").join("Players")).unwrap();
+    fs::create_dir_all(root.join("notes")).unwrap();
+    fs::write(root.join("stray.txt"), b"").unwrap();
+
+    let mut ids: Vec<String> = list_worlds(&root, false, None).into_iter().map(|w| w.id).collect();
+    ids.sort();
+    assert_eq!(ids, ["WorldWithLevel", "WorldWithPlayers"]);
+    assert_eq!(list_worlds(&root, true, None).len(), 3);
+
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn test_custom_host_ids_take_priority() {
+    let custom = "ab000000000000000000000000000042".to_string();
+    let ids = vec![DEFAULT_HOST_ID.to_string(), custom.clone()];
+    let wc = WorldConfig::default();
+    assert_eq!(host_slot_in(&wc, &ids, &host_slot_ids_with(Vec::new())).as_deref(), Some(DEFAULT_HOST_ID));
+    let slots = host_slot_ids_with(vec![custom.clone()]);
+    assert_eq!(host_slot_in(&wc, &ids, &slots), Some(custom.clone()));
+    assert_eq!(slots, [custom.as_str(), DEFAULT_HOST_ID, LEGACY_HOST_ID]);
+    assert!(!host_slot_ids_with(Vec::new()).contains(&custom));
+
+    // Ids from the settings file are normalized like the command's
+    let stored = ["AB000000-0000-0000-0000-000000000042".to_string(), custom.clone(), "not-an-id".to_string()];
+    assert_eq!(valid_custom_host_ids(&stored), [custom]);
+  }
+
+  #[test]
+  fn test_configured_host_takes_precedence() {
+    let other = "baab90a2000000000000000000000000".to_string();
+    let ids = vec![other.clone(), DEFAULT_HOST_ID.to_string(), LEGACY_HOST_ID.to_string()];
+    let mut wc = WorldConfig { host_id: Some(LEGACY_HOST_ID.to_ascii_uppercase()), ..Default::default() };
+    assert_eq!(resolve_host_id(&wc, &ids).as_deref(), Some(LEGACY_HOST_ID));
+    wc.host_id = Some(other.clone());
+    assert_eq!(resolve_host_id(&wc, &ids), Some(other.clone()));
+
+    // A host_id with no player file falls back to the slot, then the first player
+    wc.host_id = Some("cc000000000000000000000000000000".into());
+    assert_eq!(resolve_host_id(&wc, &ids).as_deref(), Some(DEFAULT_HOST_ID));
+    assert_eq!(resolve_host_id(&wc, &ids[..1]), Some(other));
+    assert_eq!(resolve_host_id(&wc, &[]), None);
+  }
+
+  #[test]
+  fn test_set_host_ignores_non_slot_configured_host() {
+    let world = write_fixture_world("non_slot_host");
+    let pdir = world.join("Players");
+    let friend = "baab90a2000000000000000000000000";
+    let wc = WorldConfig { host_id: Some(friend.into()), ..Default::default() };
+    save_world_config(&pdir, &wc).unwrap();
+
+    assert!(set_host_in_dir(&world, &pdir, friend, &SwapOptions::default()).unwrap());
+    let host_json = read_sav(&pdir.join(format!("{DEFAULT_HOST_ID}.sav")));
+    assert_eq!(
+      host_json.pointer("/properties/SaveData/value/IndividualId/value/InstanceId/value").and_then(|v| v.as_str()),
+      Some("22222222-0000-0000-0000-000000000002"),
+    );
+    assert_eq!(load_world_config(&pdir).host_id.as_deref(), Some(DEFAULT_HOST_ID), "the slot is recorded as host");
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_resolve_account_across_roots() {
+    let default = std::env::temp_dir().join("palhost_default_root");
+    let epic = std::env::temp_dir().join("palhost_epic_root");
+    assert_eq!(roots_with_epic(default.clone(), Some(default.clone())).len(), 1);
+    let roots = roots_with_epic(default.clone(), Some(epic.clone()));
+    assert_eq!(roots.len(), 2);
+    assert_eq!(qualified_account_id(1, roots[1].0, "abc"), "abc@epic");
+    let (root, id) = resolve_account_in(roots.clone(), "abc@epic");
+    assert_eq!((&root, id.as_str()), (&epic, "abc"));
+    assert_eq!(world_dir_in(&root, &id, "w"), epic.join("abc").join("w"));
+    // Untagged ids (and unknown tags) stay in the default root
+    assert_eq!(resolve_account_in(roots.clone(), "abc").0, default);
+    assert_eq!(resolve_account_in(roots, "a@b"), (default, "a@b".to_string()));
+  }
+
+  #[test]
+  fn test_root_status_follows_epic_root() {
+    let default = std::env::temp_dir().join("palhost_status_default_root");
+    let epic = std::env::temp_dir().join("palhost_status_epic_root");
+    let _ = fs::remove_dir_all(&default);
+    fs::create_dir_all(epic.join("abc")).unwrap();
+    let roots = roots_with_epic(default.clone(), Some(epic.clone()));
+    let status = root_status_in(&roots);
+    assert_eq!(status.path, epic.to_string_lossy());
+    assert!(status.exists);
+    assert_eq!(status.account_count, 1);
+
+    fs::create_dir_all(default.join("123")).unwrap();
+    let status = root_status_in(&roots);
+    assert_eq!(status.path, default.to_string_lossy(), "the default root wins once it exists");
+    assert_eq!(status.account_count, 2);
+
+    let _ = fs::remove_dir_all(&default);
+    let _ = fs::remove_dir_all(&epic);
+  }
+
+  #[test]
+  fn test_save_root_changes_that_emit() {
+    let parent = std::env::temp_dir().join("palhost_fixture_save_root_watch");
+    let root = parent.join("SaveGames");
+    let _ = fs::remove_dir_all(&parent);
+    fs::create_dir_all(&parent).unwrap();
+
+    let missing = root_snapshot(&root);
+    assert_eq!(missing, (false, Vec::new()));
+    fs::create_dir_all(&root).unwrap();
+    let empty = root_snapshot(&root);
+    assert_ne!(empty, missing, "the root appearing is a change");
+    fs::create_dir_all(root.join("76561198000000000")).unwrap();
+    let one = root_snapshot(&root);
+    assert_eq!(one, (true, vec!["76561198000000000".to_string()]));
+
+    // A world save changing deep inside an account is neither watched nor a change
+    let world = root.join("76561198000000000").join("World1");
+    fs::create_dir_all(&world).unwrap();
+    assert!(!touches_save_root(&root, &[world.join("Level.sav")]));
+    assert_eq!(root_snapshot(&root), one);
+    assert!(touches_save_root(&root, &[root.join("76561198000000000")]));
+    assert!(touches_save_root(&root, std::slice::from_ref(&root)));
+
+    let _ = fs::remove_dir_all(&parent);
+  }
+
+  #[test]
+  fn test_classify_account() {
+    let root = Path::new("SaveGames");
+    assert_eq!(classify_account(&root.join("76561198000000000")), "steam");
+    assert_eq!(classify_account(&root.join("0123456789abcdef0123456789ABCDEF")), "epic");
+    assert_eq!(classify_account(&root.join("01234567-89ab-cdef-0123-456789abcdef")), "epic");
+    assert_eq!(classify_account(&Path::new("Packages").join("wgs").join("ABCD")), "xbox");
+    assert_eq!(classify_account(&root.join("notes")), "unknown");
+    assert_eq!(classify_account_id("../../123"), "unknown");
+    assert_eq!(classify_account_id("123/456"), "unknown");
+    assert_eq!(classify_account_id(""), "unknown");
+  }
+
+  #[test]
+  fn test_progress_throttle_bounds_events() {
+    let total = 100;
+    let mut coarse = ProgressThrottle::new(10);
+    let events = (1..=total).filter(|done| coarse.tick(*done, total).is_some()).count();
+    assert!(events <= 10, "granularity 10 emitted {events} events");
+
+    // Out-of-range granularity is clamped, and the final event always fires.
+    let mut fine = ProgressThrottle::new(0);
+    let events: Vec<u32> = (1..=total).filter_map(|done| fine.tick(done, total)).collect();
+    assert_eq!(events.len(), 100);
+    assert_eq!(events.last(), Some(&100));
+  }
+
+  #[test]
+  fn test_set_guild_name_round_trips_unicode() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    set_guild_name_in_level(&mut level, FIXTURE_GUILD, "パル団 ✦", players[1].uid).unwrap();
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    let guild = &guilds_from_world_data(&check["properties"]["worldSaveData"]["value"])[0];
+    assert_eq!(guild.name, "パル団 ✦");
+    let rd = check
+      .pointer("/properties/worldSaveData/value/GroupSaveDataMap/value/0/value/RawData/value")
+      .unwrap();
+    assert_eq!(rd["last_guild_name_modifier_player_uid"], players[1].uid);
+  }
+
+  #[test]
+  fn test_set_guild_base_camp_level_round_trips() {
+    let mut level = fixture_level_json(&fixture_players());
+    let rd = "/properties/worldSaveData/value/GroupSaveDataMap/value/0/value/RawData/value";
+    let before = level.pointer(rd).unwrap().clone();
+    set_guild_base_camp_level_in_level(&mut level, FIXTURE_GUILD, 17).unwrap();
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    let mut after = check.pointer(rd).unwrap().clone();
+    assert_eq!(after["base_camp_level"], 17);
+    after["base_camp_level"] = before["base_camp_level"].clone();
+    assert_eq!(after, before);
+    assert!(set_guild_base_camp_level_in_level(&mut level, ZERO_GUID, 2).is_err());
+  }
+
+  #[test]
+  fn test_wait_until_idle() {
+    let ms = std::time::Duration::from_millis;
+    let path = std::env::temp_dir().join("palhost_fixture_idle.sav");
+    fs::write(&path, b"GVAS").unwrap();
+    assert!(wait_until_idle(None, ms(0), ms(0), || false));
+    assert!(!wait_until_idle(None, ms(0), ms(10), || true));
+    assert!(wait_until_idle(Some(&path), ms(10), ms(5_000), || true));
+    let _ = fs::remove_file(&path);
+  }
+
+  #[test]
+  fn test_duplicate_candidates_need_same_roster_and_close_size() {
+    let roster = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let worlds = vec![
+      ("a".to_string(), roster(&["p1", "p2"]), 1000),
+      ("b".to_string(), roster(&["p1", "p2"]), 1040),
+      ("c".to_string(), roster(&["p1", "p2"]), 2000),
+      ("d".to_string(), roster(&["p1"]), 1000),
+      ("e".to_string(), roster(&["p1", "p2"]), 2010),
+      ("f".to_string(), roster(&[]), 500),
+      ("g".to_string(), roster(&[]), 500),
+    ];
+    assert_eq!(duplicate_candidates(worlds), [roster(&["a", "b"]), roster(&["c", "e"])]);
+  }
+
+  #[test]
+  fn test_world_summary_from_one_parse() {
+    let world = write_fixture_world("summary");
+    let pdir = world.join("Players");
+    let broot = pdir.join("backup");
+
+    let summary = world_summary_in(&world, &pdir, &broot).unwrap();
+    assert_eq!((summary.player_count, summary.pal_count), (2, 2));
+    assert_eq!(summary.guilds.len(), 1);
+    assert_eq!(summary.engine_version, engine_version_string(&read_sav(&world.join("Level.sav"))["header"]));
+    assert!(summary.disk_usage_bytes > 0);
+    assert!(summary.last_backup.is_none());
+    let host = summary.players.iter().find(|p| p.is_host).expect("a host");
+    assert_eq!(host.name, "Host");
+
+    let snap = backup_snapshot(&load_world_config(&pdir), "manual");
+    let backup = backup_files(&pdir, &world, &broot, &list_player_ids(&pdir), &snap, false).unwrap();
+    let summary = world_summary_in(&world, &pdir, &broot).unwrap();
+    assert_eq!(summary.last_backup.map(|b| b.name), backup.file_name().map(|n| n.to_string_lossy().to_string()));
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_in_game_days_from_ticks() {
+    let clock = |ticks: u64| json!({"GameTimeSaveData": {"value": {
+      "GameDateTimeTicks": {"id": null, "value": ticks, "type": "Int64Property"},
+    }}});
+    assert_eq!(in_game_days(&clock(0)), 0);
+    assert_eq!(in_game_days(&clock(TICKS_PER_DAY - 1)), 0, "a partial day doesn't count");
+    assert_eq!(in_game_days(&clock(TICKS_PER_DAY)), 1);
+    assert_eq!(in_game_days(&clock(3 * TICKS_PER_DAY + 36_000_000_000)), 3);
+    assert_eq!(in_game_days(&json!({})), 0, "no clock");
+  }
+
+  #[test]
+  fn test_world_population_counts() {
+    let mut level = fixture_level_json(&fixture_players());
+    let guild = "/properties/worldSaveData/value/GroupSaveDataMap/value/0/value/RawData/value";
+    level.pointer_mut(guild).unwrap()["base_ids"] =
+      json!(["cccccccc-0000-0000-0000-0000000000b1", "cccccccc-0000-0000-0000-0000000000b2"]);
+    let population = population_from_world_data(&level["properties"]["worldSaveData"]["value"]);
+    assert_eq!(population, Population { players: 2, pals: 2, guilds: 1, bases: 2 });
+  }
+
+  #[test]
+  fn test_set_player_level_round_trips() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    let old = set_player_level_in_level(&mut level, players[1].uid, 42).unwrap();
+    assert_eq!(old, u32::from(players[1].level));
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    let friend = players_from_level_json(&check).into_iter().find(|p| p.uuid.eq_ignore_ascii_case(players[1].uid)).unwrap();
+    assert_eq!(friend.level, 42);
+    assert!(set_player_level_in_level(&mut level, ZERO_GUID, 2).is_err());
+  }
+
+  #[test]
+  fn test_set_player_level_exp_when_raising_and_lowering() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    let param = "/properties/worldSaveData/value/CharacterSaveParameterMap/value/2/value/RawData/value/object/SaveParameter/value";
+    level.pointer_mut(param).unwrap()["Exp"] = json!({"id": null, "value": 5000, "type": "Int64Property"});
+    let exp = |level: &Value| level.pointer(param).unwrap()["Exp"]["value"].as_i64();
+
+    set_player_level_in_level(&mut level, players[1].uid, 50).unwrap();
+    assert_eq!(exp(&level), Some(2696856), "raising to 50 sets the exp for 50");
+    set_player_level_in_level(&mut level, players[1].uid, 3).unwrap();
+    assert_eq!(exp(&level), Some(56), "lowering to 3 sets the exp for 3");
+    set_player_level_in_level(&mut level, players[1].uid, 1).unwrap();
+    assert_eq!(exp(&level), Some(0));
+
+    // The host has no Exp property yet; one is added
+    let host_param = param.replace("/value/2/", "/value/0/");
+    set_player_level_in_level(&mut level, players[0].uid, 10).unwrap();
+    assert_eq!(level.pointer(&host_param).unwrap()["Exp"]["value"], 843);
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    assert_eq!(check.pointer(&host_param).unwrap()["Exp"]["value"], 843);
+
+    assert!(set_player_level_in_level(&mut level, players[1].uid, PLAYER_TOTAL_EXP.len() as u32 + 1).is_err());
+    assert_eq!(player_exp_for_level(0), None);
+  }
+
+  #[test]
+  fn test_clear_inventory_containers() {
+    let players = fixture_players();
+    let level = fixture_level_json(&players);
+    let mut player = build_player_sav_json(&level["header"], players[1].uid, players[1].instance_id);
+    assert_eq!(clear_inventory_containers(&mut player), 0);
+
+    let container = |id: &str| json!({
+      "struct_type": "PalContainerId", "struct_id": ZERO_GUID, "id": null,
+      "value": {"ID": guid_prop(id)}, "type": "StructProperty",
+    });
+    player["properties"]["SaveData"]["value"]["InventoryInfo"] = json!({
+      "struct_type": "PalPlayerDataInventoryInfo", "struct_id": ZERO_GUID, "id": null,
+      "value": {
+        "CommonContainerId": container("44444444-0000-0000-0000-000000000001"),
+        "EssentialContainerId": container("44444444-0000-0000-0000-000000000002"),
+      },
+      "type": "StructProperty",
+    });
+    assert_eq!(clear_inventory_containers(&mut player), 2);
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&player, 0x32).unwrap()).unwrap();
+    let info = &check["properties"]["SaveData"]["value"]["InventoryInfo"]["value"];
+    assert_eq!(info["CommonContainerId"]["value"]["ID"]["value"], ZERO_GUID);
+    assert_eq!(info["EssentialContainerId"]["value"]["ID"]["value"], ZERO_GUID);
+  }
+
+  #[test]
+  fn test_copy_player_in_level_uses_fresh_instance_ids() {
+    let players = fixture_players();
+    let src = fixture_level_json(&players);
+    let mut dst = fixture_level_json(&players[..1]);
+    let friend = players[1].uid;
+
+    let (instance, pals, _) = copy_player_in_level(&src, &mut dst, friend, &[], true).unwrap();
+    assert_eq!(pals, 1);
+    assert_ne!(instance, players[1].instance_id);
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&dst, 0x32).unwrap()).unwrap();
+    assert_eq!(find_player_instance_id(&check, friend), Some(instance));
+    let world_data = &check["properties"]["worldSaveData"]["value"];
+    let pal = character_entries(world_data).find(|c| c.owner() == Some(friend)).unwrap();
+    assert!(!pal.instance_id.starts_with("22222222"));
+    assert_eq!(pal.raw["group_id"], ZERO_GUID);
+    assert_eq!(character_entries(world_data).count(), 4);
+
+    assert!(copy_player_in_level(&src, &mut dst, friend, &[], true).is_err(), "a second copy must be refused");
+    assert!(copy_player_in_level(&src, &mut dst, "cccccccc-0000-0000-0000-000000000000", &[], false).is_err());
+  }
+
+  #[test]
+  fn test_copy_player_in_level_remaps_containers() {
+    let players = fixture_players();
+    let friend = players[1].uid;
+    let (party, base) = ("33333333-0000-0000-0000-000000000003", "55555555-0000-0000-0000-000000000005");
+    let in_container = |c: &str| json!({
+      "struct_type": "PalCharacterSlotId", "struct_id": ZERO_GUID, "id": null,
+      "value": {"ContainerId": {
+        "struct_type": "PalContainerId", "struct_id": ZERO_GUID, "id": null,
+        "value": {"ID": guid_prop(c)}, "type": "StructProperty",
+      }},
+      "type": "StructProperty",
+    });
+    let mut src = fixture_level_json(&players);
+    let party_pal = "22222222-0000-0000-0000-0000000000aa";
+    let cspm = src.pointer_mut("/properties/worldSaveData/value/CharacterSaveParameterMap/value").unwrap().as_array_mut().unwrap();
+    for entry in cspm.iter_mut() {
+      if entry.pointer("/key/InstanceId/value") == Some(&json!(party_pal)) {
+        entry.pointer_mut("/value/RawData/value/object/SaveParameter/value").unwrap()["SlotID"] = in_container(party);
+      }
+    }
+    // A second pal working at a base, whose container isn't copied
+    cspm.push(character_entry(ZERO_GUID, "22222222-0000-0000-0000-0000000000bb", json!({
+      "CharacterID": {"id": null, "value": "PinkCat", "type": "NameProperty"},
+      "OwnerPlayerUId": guid_prop(friend),
+      "SlotID": in_container(base),
+    })));
+    let raw = gvas::set_container_slot_instance(&[0; 36], party_pal).unwrap();
+    src["properties"]["worldSaveData"]["value"]["CharacterContainerSaveData"] = json!({"value": [{
+      "key": {"ID": {"value": party}},
+      "value": {"Slots": {"value": {"values": [{"SlotIndex": {"value": 0}, "RawData": {"value": {"values": raw}}}]}}},
+    }]});
+    let mut dst = fixture_level_json(&players[..1]);
+    dst["properties"]["worldSaveData"]["value"]["CharacterContainerSaveData"] = json!({"value": []});
+
+    let (_, pals, new_containers) = copy_player_in_level(&src, &mut dst, friend, &[party.to_uppercase()], true).unwrap();
+    assert_eq!(pals, 1, "the base pal stays behind");
+    let fresh = new_containers[party].clone();
+    assert_ne!(fresh, party);
+    let world_data = &dst["properties"]["worldSaveData"]["value"];
+    assert_eq!(character_entries(world_data).count(), 4);
+    let copied = world_data["CharacterSaveParameterMap"]["value"].as_array().unwrap().last().unwrap();
+    assert_eq!(copied.pointer(PAL_SLOT_CONTAINER), Some(&json!(fresh)));
+    let container = &world_data["CharacterContainerSaveData"]["value"][0];
+    assert_eq!(container["key"]["ID"]["value"], fresh);
+    let slot: Vec<u8> = container["value"]["Slots"]["value"]["values"][0]["RawData"]["value"]["values"]
+      .as_array().unwrap().iter().map(|b| b.as_u64().unwrap() as u8).collect();
+    assert_eq!(Some(gvas::decode_container_slot_rawdata(&slot).unwrap().1.as_str()), copied.pointer("/key/InstanceId/value").and_then(|v| v.as_str()));
+
+    // The player save follows the party; the uncopied palbox is cleared
+    let level = fixture_level_json(&players);
+    let mut player = build_player_sav_json(&level["header"], friend, players[1].instance_id);
+    let container_id = |id: &str| json!({
+      "struct_type": "PalContainerId", "struct_id": ZERO_GUID, "id": null,
+      "value": {"ID": guid_prop(id)}, "type": "StructProperty",
+    });
+    let save = &mut player["properties"]["SaveData"]["value"];
+    save["OtomoCharacterContainerId"] = container_id(party);
+    save["PalStorageContainerId"] = container_id(base);
+    assert_eq!(remap_pal_containers(&mut player, &new_containers), 1);
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&player, 0x32).unwrap()).unwrap();
+    assert_eq!(pal_container_ids(&check), [(fresh, "party"), (ZERO_GUID.to_string(), "palbox")]);
+  }
+
+  #[test]
+  fn test_repair_player_ids_rewrites_both_fields() {
+    let level = fixture_level_json(&fixture_players());
+    let mut json = build_player_sav_json(&level["header"], "aaaaaaaa-0000-0000-0000-000000000000", "11111111-0000-0000-0000-000000000001");
+    json["properties"]["SaveData"]["value"]["IndividualId"]["value"]["PlayerUId"]["value"] = json!("bbbbbbbb-0000-0000-0000-000000000000");
+    let (mut json, _) = gvas::sav_to_json(&gvas::json_to_sav(&json, 0x32).unwrap()).unwrap();
+
+    let expected = filename_to_uuid("00000001000000000000000000000000");
+    let replaced = repair_player_ids_in_json(&mut json, &expected).unwrap();
+    assert_eq!(replaced, ["aaaaaaaa-0000-0000-0000-000000000000", "bbbbbbbb-0000-0000-0000-000000000000"]);
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&json, 0x32).unwrap()).unwrap();
+    let save_data = &check["properties"]["SaveData"]["value"];
+    assert_eq!(save_data["PlayerUId"]["value"], expected.as_str());
+    assert_eq!(save_data["IndividualId"]["value"]["PlayerUId"]["value"], expected.as_str());
+    assert_eq!(save_data["IndividualId"]["value"]["InstanceId"]["value"], "11111111-0000-0000-0000-000000000001");
+    assert!(repair_player_ids_in_json(&mut json, &expected).unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_compact_level_removes_orphans_and_empty_guilds() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    let opts = CompactOptions { remove_orphan_pals: true, remove_empty_guilds: true };
+    let everyone = players.iter().map(|p| p.uid.to_ascii_lowercase()).collect();
+    let report = compact_level(&mut level.clone(), &everyone, opts).unwrap();
+    assert_eq!((report.orphan_pals_removed, report.guilds_removed), (0, 0));
+
+    // Only the host still has a file: the friend's pal is an orphan
+    let host_only = std::iter::once(players[0].uid.to_ascii_lowercase()).collect();
+    let rd = "/properties/worldSaveData/value/GroupSaveDataMap/value/0/value/RawData/value";
+    level.pointer_mut(rd).unwrap()["players"] = json!([]);
+    let report = compact_level(&mut level, &host_only, opts).unwrap();
+    assert_eq!((report.orphan_pals_removed, report.guilds_removed), (1, 1));
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    let world_data = &check["properties"]["worldSaveData"]["value"];
+    assert_eq!(character_entries(world_data).filter(|c| !c.is_player()).count(), 1);
+    assert!(guilds_from_world_data(world_data).is_empty());
+  }
+
+  #[test]
+  fn test_compact_level_empties_container_slots_of_orphans() {
+    let players = fixture_players();
+    let mut level = fixture_level_json(&players);
+    let slot = |inst: &str| {
+      let raw = gvas::set_container_slot_instance(&[0; 36], inst).unwrap();
+      json!({"SlotIndex": {"value": 0}, "RawData": {"value": {"values": raw}}})
+    };
+    let host_pal = format!("{}-0000000000aa", &players[0].instance_id[..23]);
+    let friend_pal = format!("{}-0000000000aa", &players[1].instance_id[..23]);
+    level["properties"]["worldSaveData"]["value"]["CharacterContainerSaveData"] = json!({"value": [{
+      "key": {"ID": {"value": "33333333-0000-0000-0000-000000000003"}},
+      "value": {"Slots": {"value": {"values": [slot(&host_pal), slot(&friend_pal)]}}},
+    }]});
+
+    let host_only = std::iter::once(players[0].uid.to_ascii_lowercase()).collect();
+    let opts = CompactOptions { remove_orphan_pals: true, remove_empty_guilds: false };
+    assert_eq!(compact_level(&mut level, &host_only, opts).unwrap().orphan_pals_removed, 1);
+    let slots = &level["properties"]["worldSaveData"]["value"]["CharacterContainerSaveData"]["value"][0]["value"]["Slots"]["value"]["values"];
+    let inst = |i: usize| {
+      let raw: Vec<u8> = slots[i]["RawData"]["value"]["values"].as_array().unwrap().iter().map(|b| b.as_u64().unwrap() as u8).collect();
+      assert_eq!(raw.len(), 36);
+      gvas::decode_container_slot_rawdata(&raw).unwrap().1
+    };
+    assert_eq!(inst(0), host_pal);
+    assert_eq!(inst(1), ZERO_GUID);
+  }
+
+  #[test]
+  fn test_world_watch_ignores_config_and_backups() {
+    let world = Path::new("/saves/acct/world");
+    assert!(is_world_save_change(world, &world.join("Level.sav")));
+    assert!(is_world_save_change(world, &world.join("Players").join("00000001000000000000000000000000.sav")));
+    assert!(!is_world_save_change(world, &world.join("Players").join(WORLD_CONFIG_FILE)));
+    assert!(!is_world_save_change(world, &world.join("Players").join("backup").join("pre-swap_1").join("Level.sav")));
+    assert!(!is_world_save_change(world, Path::new("/saves/acct/other/Level.sav")));
+  }
+
+  #[test]
+  fn test_hand_base_camps_to() {
+    let players = fixture_players();
+    let (host, friend) = (players[0].uid, players[1].uid);
+    let mut level = fixture_level_json(&players);
+    let world_data = level.pointer_mut("/properties/worldSaveData/value").unwrap();
+    // Two camps of the host-run guild; the palbox id is a map object
+    let palbox = "7a000000-0000-0000-0000-00000000b0c5";
+    world_data["BaseCampSaveData"] =
+      gvas::base_camp_test_property(&[(FIXTURE_GUILD, palbox), (FIXTURE_GUILD, palbox)], host);
+    let camps_before = world_data["BaseCampSaveData"].clone();
+    assert_eq!(base_camps_run_by(world_data, host), 2);
+
+    // Not the admin: nothing to hand over
+    assert_eq!(hand_base_camps_to(world_data, friend, host), (0, vec![]));
+    let outsider = "33333333-0000-0000-0000-000000000003";
+    assert_eq!(hand_base_camps_to(world_data, host, outsider), (0, vec![FIXTURE_GUILD.to_string()]));
+    assert_eq!(base_camps_run_by(world_data, host), 2);
+    assert_eq!(hand_base_camps_to(world_data, host, &friend.to_ascii_uppercase()), (2, vec![]));
+    let (check, _) = gvas::sav_to_json(&gvas::json_to_sav(&level, 0x32).unwrap()).unwrap();
+    let world_data = &check["properties"]["worldSaveData"]["value"];
+    assert_eq!(base_camps_run_by(world_data, host), 0);
+    assert_eq!(base_camps_run_by(world_data, friend), 2);
+    assert_eq!(world_data["BaseCampSaveData"], camps_before, "the camps themselves stay with the guild");
+
+    // A blob that doesn't decode falls back to the guild's base_ids
+    let mut world_data = world_data.clone();
+    world_data["BaseCampSaveData"] = json!({"value": []});
+    world_data["GroupSaveDataMap"]["value"][0]["value"]["RawData"]["value"]["base_ids"] = json!([palbox]);
+    assert_eq!(hand_base_camps_to(&mut world_data, friend, host), (1, vec![]));
+    assert_eq!(hand_base_camps_to(&mut json!({}), host, friend), (0, vec![]));
+  }
+
+  #[test]
+  fn test_pal_dex_counts_owned_species() {
+    let level = fixture_level_json(&fixture_players());
+    let world_data = &level["properties"]["worldSaveData"]["value"];
+    let dex = pal_dex(world_data, "00000001-0000-0000-0000-000000000000");
+    assert_eq!(dex.len(), 1);
+    assert_eq!(dex["SheepBall"], 1);
+    assert!(pal_dex(world_data, "cccccccc-0000-0000-0000-000000000000").is_empty());
+  }
+
+  #[test]
+  fn test_query_players_filters_and_sorts() {
+    let level_info = players_from_level_json(&fixture_level_json(&fixture_players()));
+    let ids = vec!["baab90a2000000000000000000000000".to_string(), "00000001000000000000000000000000".to_string()];
+    let players = build_players(&ids, &ids[1], &level_info);
+    let names = |query: PlayerQuery| -> Vec<String> {
+      query_players(players.clone(), &query).unwrap().into_iter().map(|p| p.name).collect()
+    };
+
+    assert_eq!(names(PlayerQuery::default()), ["Friend", "Host"]);
+    assert_eq!(names(PlayerQuery { sort_by: Some("level".into()), descending: true, ..Default::default() }), ["Host", "Friend"]);
+    assert_eq!(names(PlayerQuery { sort_by: Some("name".into()), ..Default::default() }), ["Friend", "Host"]);
+    assert_eq!(names(PlayerQuery { exclude_host: true, ..Default::default() }), ["Friend"]);
+    assert_eq!(names(PlayerQuery { guild_id: Some(FIXTURE_GUILD.to_uppercase()), ..Default::default() }).len(), 2);
+    assert!(names(PlayerQuery { guild_id: Some(ZERO_GUID.into()), ..Default::default() }).is_empty());
+    assert!(query_players(players, &PlayerQuery { sort_by: Some("age".into()), ..Default::default() }).is_err());
+  }
+
+  #[test]
+  fn test_clean_temp_transfers_only_touches_palhost_items() {
+    let root = std::env::temp_dir().join("palhost_temp_root_test");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("palhost_p2p_extract").join("World")).unwrap();
+    fs::write(root.join("palhost_p2p_extract").join("World").join("Level.sav"), b"data").unwrap();
+    fs::write(root.join("palhost_share_w.zip"), b"zip").unwrap();
+    fs::write(root.join("someone_else.zip"), b"keep").unwrap();
+
+    assert_eq!(temp_transfer_entries(&root).len(), 2);
+    let hour = std::time::Duration::from_secs(3600);
+    assert!(clean_temp_transfers_in(&root, Some(hour), &[]).is_empty(), "fresh items are kept");
+    // A ZIP still being sent survives even a full clean
+    let sending = [root.join("palhost_share_w.zip")];
+    let removed = clean_temp_transfers_in(&root, None, &sending);
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].size, 4);
+    assert!(sending[0].exists());
+    let removed = clean_temp_transfers_in(&root, None, &[]);
+    assert_eq!(removed.len(), 1);
+    assert!(root.join("someone_else.zip").exists());
+
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn test_retried_transfer_starts_clean() {
+    let path = std::env::temp_dir().join("palhost_retried_transfer.zip");
+    let p = path.to_string_lossy().to_string();
+    // First attempt dies after one chunk
+    begin_file_transfer(p.clone()).unwrap();
+    append_file_chunk_b64(p.clone(), "c3RhbGUtcGFydGlhbA==".into()).unwrap();
+
+    begin_file_transfer(p.clone()).unwrap();
+    append_file_chunk_b64(p.clone(), "UEs=".into()).unwrap();
+    append_file_chunk_b64(p.clone(), "AwRyZXN0".into()).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), b"PK\x03\x04rest");
+
+    let _ = fs::remove_file(&path);
+  }
+
+  /// A writer that only tracks how much it was handed, and in what sizes.
+  #[derive(Default)]
+  struct WriteTally {
+    total: usize,
+    largest_write: usize,
+  }
+
+  impl Write for &mut WriteTally {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+      self.total += buf.len();
+      self.largest_write = self.largest_write.max(buf.len());
+      Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_json_dump_streams_in_small_writes() {
+    let blob = "QUJD".repeat(4096);
+    let entries: Vec<Value> = (0..600).map(|i| json!({"key": i, "skip_type": "MapProperty", "value": blob})).collect();
+    let mut tally = WriteTally::default();
+    write_json_pretty(&json!({"entries": entries}), &mut tally).unwrap();
+    // ~10 MB of output, never more than one buffer (or one blob) at a time
+    assert!(tally.total > 9_000_000, "{}", tally.total);
+    assert!(tally.largest_write <= 8 * 1024 + blob.len(), "{}", tally.largest_write);
+  }
+
+  #[test]
+  fn test_read_file_chunk_caps_allocation() {
+    let path = std::env::temp_dir().join("palhost_fixture_chunk.bin");
+    fs::write(&path, [7u8; 100]).unwrap();
+    let p = path.to_string_lossy().to_string();
+
+    assert!(read_file_chunk(p.clone(), 100, 64).unwrap().is_empty());
+    assert!(read_file_chunk(p.clone(), 500, 64).unwrap().is_empty());
+
+    let tail = read_file_chunk(p.clone(), 90, 1 << 30).unwrap();
+    assert_eq!(tail, [7u8; 10]);
+    assert_eq!(tail.capacity(), 10);
+
+    let _ = fs::remove_file(&path);
+  }
+
+  #[test]
+  fn test_backup_page_slices_and_sums_sizes() {
+    let root = std::env::temp_dir().join(format!("palhost_fixture_backups_{}", uuid::Uuid::new_v4().simple()));
+    for (name, len) in [("2024-01-01_10-00-00", 10), ("2024-01-02_10-00-00", 20), ("2024-01-03_10-00-00", 30)] {
+      fs::create_dir_all(root.join(name)).unwrap();
+      fs::write(root.join(name).join("Level.sav"), vec![0u8; len]).unwrap();
+    }
+
+    let page = backup_page(&root, 1, 1);
+    assert_eq!(page.total, 3);
+    assert_eq!(page.total_bytes, 60);
+    let names: Vec<&str> = page.entries.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, ["2024-01-02_10-00-00"]);
+    assert!(backup_page(&root, 5, 10).entries.is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+  }
+
+  #[test]
+  fn test_export_to_memory_is_a_valid_zip() {
+    let world = write_fixture_world("export_memory");
+    let cursor = std::io::Cursor::new(Vec::new());
+    let mut files = 0;
+    let cursor = write_world_zip(zip::ZipWriter::new(cursor), &world, "w", false, false, None, &mut |done, _| files = done).unwrap();
+    let mut archive = zip::ZipArchive::new(cursor).unwrap();
+    assert_eq!(files, 3);
+    assert!(archive.index_for_name("w/Level.sav").is_some());
+    let mut manifest = String::new();
+    archive.by_name(&format!("w/{EXPORT_MANIFEST_FILE}")).unwrap().read_to_string(&mut manifest).unwrap();
+    let manifest: ExportManifest = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(manifest.files.len(), 3);
+
+    // The game's backup/ only goes in when asked for; the swap backups in
+    // Players/backup only when a backup root override moved them elsewhere
+    for dir in [world.join("Players").join("backup").join("pre-swap_1"), world.join("backup").join("world")] {
+      fs::create_dir_all(&dir).unwrap();
+      fs::write(dir.join("Level.sav"), b"GVAS").unwrap();
+    }
+    let cursor = std::io::Cursor::new(Vec::new());
+    let cursor = write_world_zip(zip::ZipWriter::new(cursor), &world, "w", false, false, None, &mut |done, _| files = done).unwrap();
+    assert_eq!(files, 3);
+    let archive = zip::ZipArchive::new(cursor).unwrap();
+    assert!(archive.index_for_name("w/Players/backup/pre-swap_1/Level.sav").is_none());
+    assert!(archive.index_for_name("w/backup/world/Level.sav").is_none());
+    let cursor = std::io::Cursor::new(Vec::new());
+    let cursor = write_world_zip(zip::ZipWriter::new(cursor), &world, "w", true, false, None, &mut |done, _| files = done).unwrap();
+    assert_eq!(files, 4);
+    let archive = zip::ZipArchive::new(cursor).unwrap();
+    assert!(archive.index_for_name("w/Players/backup/pre-swap_1/Level.sav").is_none());
+    assert!(archive.index_for_name("w/backup/world/Level.sav").is_some());
+    let cursor = std::io::Cursor::new(Vec::new());
+    let cursor = write_world_zip(zip::ZipWriter::new(cursor), &world, "w", false, true, None, &mut |done, _| files = done).unwrap();
+    assert_eq!(files, 4);
+    let archive = zip::ZipArchive::new(cursor).unwrap();
+    assert!(archive.index_for_name("w/Players/backup/pre-swap_1/Level.sav").is_some());
+    assert!(archive.index_for_name("w/backup/world/Level.sav").is_none());
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_manifest_paths_stay_inside_the_world() {
+    let world = write_fixture_world("manifest_paths");
+    let manifest = |path: &str| {
+      let m = ExportManifest { files: vec![ManifestEntry { path: path.into(), sha256: sha256_file(&world.join("Level.sav")).unwrap() }] };
+      fs::write(world.join(EXPORT_MANIFEST_FILE), serde_json::to_string(&m).unwrap()).unwrap();
+      verify_export_manifest(&world)
+    };
+    assert_eq!(manifest("Level.sav"), Ok(()));
+    assert!(manifest("./Level.sav").is_err());
+    assert!(manifest("../manifest_paths/Level.sav").unwrap_err().contains("outside the world"));
+    assert!(manifest(&world.join("Level.sav").to_string_lossy()).unwrap_err().contains("outside the world"));
+    assert!(manifest("").is_err());
+
+    let _ = fs::remove_dir_all(&world);
+  }
+
+  #[test]
+  fn test_encrypted_export_needs_password() {
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    zip.start_file("w/Level.sav", export_file_options(Some("hunter2"))).unwrap();
+    zip.write_all(b"GVAS").unwrap();
+    let mut archive = zip::ZipArchive::new(zip.finish().unwrap()).unwrap();
+
+    assert!(open_zip_entry(&mut archive, 0, None).err().unwrap().contains("password-protected"));
+    assert!(open_zip_entry(&mut archive, 0, Some("wrong")).err().unwrap().contains("Wrong password"));
+    let mut data = Vec::new();
+    open_zip_entry(&mut archive, 0, Some("hunter2")).unwrap().read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"GVAS");
+  }
 
   /// Integration test: perform swap on original save files and compare with
   /// PalworldSaveTools "correct" output.
@@ -1760,7 +9765,7 @@ mod tests {
       &players_dir,
       "00000000000000000000000000000001",
       "BAAB90A2000000000000000000000000",
-      None,
+      &SwapOptions::default(),
     );
     assert!(result.is_ok(), "swap_players_full failed: {:?}", result.err());
 
@@ -3,6 +3,40 @@
 //! Uses the open-source `oozextract` crate — a pure Rust implementation of
 //! Kraken / Mermaid / Selkie / Leviathan decompressors.  No external DLL
 //! or proprietary library is required.
+//!
+//! Compression has no open implementation, so `compress` loads the game's
+//! own `oo2core_*.dll` — only when the user has pointed us at it via
+//! `set_dll_path` (the `oodle_dll_path` setting).
+
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// `OodleLZ_Compressor_Mermaid` — what Palworld's PlM saves use.
+const COMPRESSOR_MERMAID: i32 = 9;
+
+/// `OodleLZ_CompressionLevel_Normal`.
+pub const LEVEL_NORMAL: i32 = 4;
+
+/// The configured DLL (or the game folder holding it); `None` disables `compress`.
+static DLL_PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The last DLL loaded by `compress_with`, kept so each save doesn't reload it.
+static LOADED_DLL: Mutex<Option<(PathBuf, libloading::Library)>> = Mutex::new(None);
+
+type CompressFn = unsafe extern "C" fn(
+    i32,
+    *const u8,
+    isize,
+    *mut u8,
+    i32,
+    *const c_void,
+    *const c_void,
+    *const c_void,
+    *mut c_void,
+    isize,
+) -> isize;
+type SizeNeededFn = unsafe extern "C" fn(i32, isize) -> isize;
 
 /// Decompress an Oodle-compressed buffer.
 ///
@@ -23,3 +57,169 @@ pub fn decompress(compressed: &[u8], uncompressed_len: usize) -> Result<Vec<u8>,
     }
     Ok(output)
 }
+
+/// A GVAS header packed as a hand-built, literal-only Oodle block (an
+/// entropy-only chunk of raw literals), and what it decodes to. It exercises
+/// the block and chunk framing, not Mermaid's match decoding. Real encoder
+/// output is checked against `fixtures/oodle_gvas_header.bin`, which
+/// `test_dll_output_decodes` writes when given the game's DLL.
+const LITERAL_TEST_BLOCK: &[u8] = include_bytes!("../fixtures/oodle_literal_block.bin");
+const TEST_GVAS_HEADER: &[u8] = b"GVAS\x03\0\0\0\x0a\x02\0\0\xf1\x03\0\0\x05\0\x01\0\x01\0\0\0\0\0\x12\0\0\0++UE5+Release-5.1\0";
+
+/// Decode the embedded literal-only block, so a decompressor that can't
+/// even frame a block shows up before it is trusted with a real save.
+pub fn literal_block_self_test() -> Result<bool, String> {
+    Ok(decompress(LITERAL_TEST_BLOCK, TEST_GVAS_HEADER.len())? == TEST_GVAS_HEADER)
+}
+
+/// Set (or clear) the `oo2core` DLL used by `compress`. A folder is searched
+/// for `oo2core_*.dll`, both directly and under `Pal/Binaries/Win64`.
+pub fn set_dll_path(path: Option<PathBuf>) {
+    if let Ok(mut slot) = DLL_PATH.lock() {
+        *slot = path;
+    }
+}
+
+fn locate_dll(configured: &Path) -> Option<PathBuf> {
+    if configured.is_file() {
+        return Some(configured.to_path_buf());
+    }
+    [configured.to_path_buf(), configured.join("Pal").join("Binaries").join("Win64")]
+        .iter()
+        .filter_map(|dir| std::fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .find(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("oo2core_") && n.to_ascii_lowercase().ends_with(".dll"))
+        })
+}
+
+/// Compress raw GVAS bytes with Oodle Mermaid through the game's DLL.
+/// Returns the compressed payload (no PlM header), or `None` when no DLL is
+/// configured so callers can fall back to zlib. A configured DLL that is
+/// missing or fails is an error.
+pub fn compress(data: &[u8], level: i32) -> Result<Option<Vec<u8>>, String> {
+    let Some(configured) = DLL_PATH
+        .lock()
+        .map_err(|_| "Oodle DLL path lock poisoned".to_string())?
+        .clone()
+    else {
+        return Ok(None);
+    };
+    let dll = locate_dll(&configured)
+        .ok_or_else(|| format!("No oo2core DLL found at {}", configured.display()))?;
+    compress_with(&dll, data, level).map(Some)
+}
+
+/// `compress` through the given `oo2core` DLL file.
+fn compress_with(dll: &Path, data: &[u8], level: i32) -> Result<Vec<u8>, String> {
+    let mut loaded = LOADED_DLL
+        .lock()
+        .map_err(|_| "Oodle DLL lock poisoned".to_string())?;
+    if !matches!(&*loaded, Some((path, _)) if path == dll) {
+        // SAFETY: loading runs the DLL's initializers; it is the game's own oo2core.
+        let lib = unsafe { libloading::Library::new(dll) }
+            .map_err(|e| format!("Cannot load {}: {e}", dll.display()))?;
+        *loaded = Some((dll.to_path_buf(), lib));
+    }
+    let lib = &loaded.as_ref().ok_or("oo2core DLL not loaded")?.1;
+    // SAFETY: the signatures match the Oodle 2.x exports, the output buffer is
+    // sized by the DLL itself (or the documented worst case), and all
+    // optional pointer arguments are null.
+    let compressed = unsafe {
+        let compress_fn: libloading::Symbol<CompressFn> = lib
+            .get(b"OodleLZ_Compress\0")
+            .map_err(|e| format!("OodleLZ_Compress not found in oo2core DLL: {e}"))?;
+        let bound = match lib.get::<SizeNeededFn>(b"OodleLZ_GetCompressedBufferSizeNeeded\0") {
+            Ok(size_fn) => size_fn(COMPRESSOR_MERMAID, data.len() as isize).max(0) as usize,
+            Err(_) => data.len() + 274 * data.len().div_ceil(0x40000),
+        };
+        let mut out = vec![0u8; bound];
+        let written = compress_fn(
+            COMPRESSOR_MERMAID,
+            data.as_ptr(),
+            data.len() as isize,
+            out.as_mut_ptr(),
+            level,
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null(),
+            std::ptr::null_mut(),
+            0,
+        );
+        if written <= 0 || written as usize > out.len() {
+            return Err(format!("OodleLZ_Compress failed ({written})"));
+        }
+        out.truncate(written as usize);
+        out
+    };
+
+    // Never hand back something our own decompressor can't read
+    if decompress(&compressed, data.len())? != data {
+        return Err("Oodle output did not round-trip".into());
+    }
+    Ok(compressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_block_decodes_to_gvas_header() {
+        let out = decompress(LITERAL_TEST_BLOCK, TEST_GVAS_HEADER.len()).unwrap();
+        assert!(out.starts_with(b"GVAS"));
+        assert_eq!(out, TEST_GVAS_HEADER);
+        assert_eq!(literal_block_self_test(), Ok(true));
+    }
+
+    /// `TEST_GVAS_HEADER` as the game's encoder packs it.
+    fn encoder_fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join("oodle_gvas_header.bin")
+    }
+
+    /// Needs the game's DLL (a file or folder in `PALHOST_OODLE_DLL`); skipped
+    /// otherwise. Checks our decoder against real encoder output and saves
+    /// that output as the encoder fixture.
+    #[test]
+    fn test_dll_output_decodes() {
+        let Some(dll) = std::env::var_os("PALHOST_OODLE_DLL") else {
+            eprintln!("Skipping: PALHOST_OODLE_DLL not set");
+            return;
+        };
+        let dll = locate_dll(Path::new(&dll)).expect("no oo2core DLL at PALHOST_OODLE_DLL");
+        let block = compress_with(&dll, TEST_GVAS_HEADER, LEVEL_NORMAL).unwrap();
+        assert_eq!(decompress(&block, TEST_GVAS_HEADER.len()).unwrap(), TEST_GVAS_HEADER);
+        std::fs::write(encoder_fixture(), &block).unwrap();
+    }
+
+    #[test]
+    fn test_encoder_block_decodes_to_gvas_header() {
+        let Ok(block) = std::fs::read(encoder_fixture()) else {
+            eprintln!("Skipping: no encoder fixture; run test_dll_output_decodes with PALHOST_OODLE_DLL set");
+            return;
+        };
+        assert_eq!(decompress(&block, TEST_GVAS_HEADER.len()).unwrap(), TEST_GVAS_HEADER);
+    }
+
+    #[test]
+    fn test_locate_dll() {
+        let root = std::env::temp_dir().join("palhost_fixture_oodle");
+        let bin = root.join("Pal").join("Binaries").join("Win64");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&bin).unwrap();
+        assert_eq!(locate_dll(&root), None);
+
+        let dll = bin.join("oo2core_9_win64.DLL");
+        std::fs::write(&dll, b"").unwrap();
+        std::fs::write(bin.join("oo2net_9_win64.dll"), b"").unwrap();
+        assert_eq!(locate_dll(&root), Some(dll.clone()), "game folder");
+        assert_eq!(locate_dll(&bin), Some(dll.clone()), "binaries folder");
+        assert_eq!(locate_dll(&dll), Some(dll.clone()), "the DLL itself");
+        assert_eq!(locate_dll(&root.join("missing")), None);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -24,6 +24,7 @@ import {
   isPalworldRunning,
   exportWorldToTemp,
   deleteTempFile,
+  getTempPath,
   type Player,
   type WorldInfo,
 } from "./services/palworldService";
@@ -1099,6 +1100,16 @@ function App() {
   const [importing, setImporting] = useState(false);
   const [exportProgress, setExportProgress] = useState<number | null>(null);
   const [importProgress, setImportProgress] = useState<number | null>(null);
+  // Folder a received P2P world was extracted to; deleted once its import
+  // is done with, which also lets temp cleaning reclaim it
+  const p2pExtractRef = useRef<string | null>(null);
+
+  const releaseP2PExtract = () => {
+    if (p2pExtractRef.current) {
+      deleteTempFile(p2pExtractRef.current).catch(() => {});
+      p2pExtractRef.current = null;
+    }
+  };
 
   /* Listen for Tauri native drag-drop events (file drops from OS) */
   useEffect(() => {
@@ -1204,6 +1215,8 @@ function App() {
   };
 
   const processImportFolder = async (folderPath: string) => {
+    // A different source replaces a received world that was never imported
+    releaseP2PExtract();
     try {
       const result = await validateWorldFolder(folderPath);
       setImportFolder(result.path);
@@ -1264,6 +1277,7 @@ function App() {
       pushLog(`World imported: ${targetName}`);
       pushToast("World imported successfully.");
       // Reset import state
+      releaseP2PExtract();
       setImportFolder(null);
       setImportFolderName("");
       setImportConflict(false);
@@ -1278,6 +1292,7 @@ function App() {
   };
 
   const handleCancelImport = () => {
+    releaseP2PExtract();
     setImportFolder(null);
     setImportFolderName("");
     setImportConflict(false);
@@ -1374,6 +1389,8 @@ function App() {
       pushLog(`P2P: World received and saved to ${dest}`);
       // Use existing import flow
       await processImportFolder(folderPath);
+      // extract_zip_to_temp's folder, which holds the received world
+      p2pExtractRef.current = await getTempPath("palhost_p2p_extract");
       pushToast("World received! Review import options below.", "success");
     } catch (err) {
       pushLog(`P2P receive error: ${err}`);
@@ -1620,8 +1637,8 @@ function App() {
                   <option value="">Select a world…</option>
                   {worlds.map((world) => (
                     <option key={world.id} value={world.id}>
-                      {world.displayName
-                        ? `${world.displayName} (${world.id})`
+                      {(world.displayName ?? world.inGameName)
+                        ? `${world.displayName ?? world.inGameName} (${world.id})`
                         : world.id}{" "}
                       — {world.playerCount} players
                     </option>
@@ -2161,9 +2178,12 @@ function App() {
                   ) : (
                     <div className="world-name-header__display">
                       <h2 className="world-name-header__title">
-                        {currentWorld?.displayName ?? worldId}
+                        {currentWorld?.displayName ??
+                          currentWorld?.inGameName ??
+                          worldId}
                       </h2>
-                      {currentWorld?.displayName && (
+                      {(currentWorld?.displayName ??
+                        currentWorld?.inGameName) && (
                         <span className="world-name-header__id">{worldId}</span>
                       )}
                       <button
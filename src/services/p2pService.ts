@@ -14,7 +14,7 @@ import {
   getFileSize,
   readFileChunk,
   appendFileChunkB64,
-  deleteTempFile,
+  beginFileTransfer,
   extractZipToTemp,
   validateWorldFolder,
 } from "./palworldService";
@@ -721,10 +721,8 @@ export async function startReceiving(
             if (msg.type === "meta") {
               totalSize = msg.totalSize;
               tempPath = destZipPath;
-              // Clear any previous file at destination (queued)
-              writeChain = writeChain.then(() =>
-                deleteTempFile(tempPath).catch(() => {}),
-              );
+              // Truncate any partial file a failed transfer left (queued)
+              writeChain = writeChain.then(() => beginFileTransfer(tempPath));
               callbacks.onStatus("transferring", "Receiving file…");
               callbacks.onProgress(0);
               transferStarted = true;
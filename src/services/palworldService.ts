@@ -9,41 +9,479 @@ export type Player = {
   palsCount: number;
   lastOnline: string;
   guildName: string;
+  hostFallback: boolean;
+  instanceId: string;
+  groupId: string | null;
+  idMismatch: boolean;
 };
 
 export type WorldInfo = {
   id: string;
   playerCount: number;
   displayName: string | null;
+  inGameName: string | null;
+  lastBackup: string | null;
+  lastBackupAction: string | null;
+  favorite: boolean;
 };
 
+export type AppSettings = {
+  accountId: string | null;
+  worldId: string | null;
+  autoBackup: boolean;
+  progressGranularity: number | null;
+  backupRootOverride: string | null;
+  oodleDllPath: string | null;
+  epicSaveRoot: string | null;
+  waitForIdle: boolean;
+  idleWindowMs: number | null;
+};
+
+export async function waitForGameIdle(
+  timeoutMs: number,
+  accountId?: string,
+  worldId?: string,
+): Promise<boolean> {
+  return invoke<boolean>("wait_for_game_idle", {
+    timeoutMs,
+    accountId,
+    worldId,
+  });
+}
+
+export async function getAppConfig(): Promise<AppSettings> {
+  return invoke<AppSettings>("get_app_config");
+}
+
+export async function updateAppConfig(
+  patch: Partial<AppSettings>,
+): Promise<AppSettings> {
+  return invoke<AppSettings>("update_app_config", { patch });
+}
+
+export async function setCustomHostIds(ids: string[]): Promise<string[]> {
+  return invoke<string[]>("set_custom_host_ids", { ids });
+}
+
 export async function getAccounts(): Promise<string[]> {
   return invoke<string[]>("get_accounts");
 }
 
+export type AccountInfo = {
+  id: string;
+  platform: "steam" | "epic" | "xbox" | "unknown";
+  worldCount: number;
+};
+
+export async function getAccountsDetailed(): Promise<AccountInfo[]> {
+  return invoke<AccountInfo[]>("get_accounts_detailed");
+}
+
+export type RootStatus = {
+  path: string;
+  exists: boolean;
+  accountCount: number;
+};
+
+export async function getSaveGamesRoot(): Promise<string> {
+  return invoke<string>("get_save_games_root");
+}
+
+export async function saveRootStatus(): Promise<RootStatus> {
+  return invoke<RootStatus>("save_root_status");
+}
+
 export async function getWorlds(accountId: string): Promise<string[]> {
   return invoke<string[]>("get_worlds", { accountId });
 }
 
 export async function getWorldsWithCounts(
   accountId: string,
+  includeInvalid = false,
 ): Promise<WorldInfo[]> {
-  return invoke<WorldInfo[]>("get_worlds_with_counts", { accountId });
+  return invoke<WorldInfo[]>("get_worlds_with_counts", {
+    accountId,
+    includeInvalid,
+  });
+}
+
+export type AccountNode = {
+  id: string;
+  platform: string;
+  worlds: WorldInfo[];
+  error?: string;
+};
+
+export async function getFullTree(): Promise<AccountNode[]> {
+  return invoke<AccountNode[]>("get_full_tree");
+}
+
+export async function prewarmWorld(
+  accountId: string,
+  worldId: string,
+): Promise<void> {
+  await invoke("prewarm_world", { accountId, worldId });
 }
 
+export type PlayerQuery = {
+  sortBy?: "name" | "level" | "pals" | "lastSeen";
+  descending?: boolean;
+  guildId?: string;
+  excludeHost?: boolean;
+};
+
 export async function getPlayers(
   accountId: string,
   worldId: string,
+  opts?: PlayerQuery,
 ): Promise<Player[]> {
-  return invoke<Player[]>("get_players", { accountId, worldId });
+  return invoke<Player[]>("get_players", { accountId, worldId, opts });
+}
+
+export type PalContainer = {
+  containerId: string;
+  kind: "party" | "palbox";
+  slots: { slotIndex: number; instanceId: string }[];
+};
+
+export async function getPalContainers(
+  accountId: string,
+  worldId: string,
+  playerId: string,
+): Promise<PalContainer[]> {
+  return invoke<PalContainer[]>("get_pal_containers", {
+    accountId,
+    worldId,
+    playerId,
+  });
+}
+
+export type BaseStructures = {
+  guildId: string;
+  // Per-base counts are estimates (id occurrences in undecoded data)
+  bases: {
+    baseId: string;
+    estimatedMapObjects: number | null;
+    estimatedWorkItems: number | null;
+  }[];
+  totalMapObjects: number | null;
+  totalWorkItems: number | null;
+};
+
+export async function getBaseStructures(
+  accountId: string,
+  worldId: string,
+  guildId: string,
+): Promise<BaseStructures> {
+  return invoke<BaseStructures>("get_base_structures", {
+    accountId,
+    worldId,
+    guildId,
+  });
+}
+
+export type OrphanPal = {
+  instanceId: string;
+  characterId: string;
+  level: number;
+  ownerUid: string;
+};
+
+export async function listOrphanPals(
+  accountId: string,
+  worldId: string,
+): Promise<OrphanPal[]> {
+  return invoke<OrphanPal[]>("list_orphan_pals", { accountId, worldId });
+}
+
+export type GuildSummary = {
+  id: string;
+  groupType: "Guild" | "IndependentGuild" | "Organization";
+  name: string;
+  memberCount: number;
+  baseCount: number;
+  baseCampLevel: number | null;
+};
+
+export type WorldSummary = {
+  players: Player[];
+  guilds: GuildSummary[];
+  engineVersion: string;
+  playerCount: number;
+  palCount: number;
+  diskUsageBytes: number;
+  lastBackup: BackupEntry | null;
+};
+
+export type ScanFinding = {
+  severity: "info" | "warning" | "error";
+  category: "level" | "player" | "ids" | "host" | "references";
+  message: string;
+  target: string | null;
+};
+
+export async function scanWorld(
+  accountId: string,
+  worldId: string,
+): Promise<ScanFinding[]> {
+  return invoke<ScanFinding[]>("scan_world", { accountId, worldId });
+}
+
+export async function getGuilds(
+  accountId: string,
+  worldId: string,
+): Promise<GuildSummary[]> {
+  return invoke<GuildSummary[]>("get_guilds", { accountId, worldId });
+}
+
+export async function getWorldSummary(
+  accountId: string,
+  worldId: string,
+): Promise<WorldSummary> {
+  return invoke<WorldSummary>("get_world_summary", { accountId, worldId });
+}
+
+export type Population = {
+  players: number;
+  pals: number;
+  guilds: number;
+  bases: number;
+};
+
+export async function getWorldPopulation(
+  accountId: string,
+  worldId: string,
+): Promise<Population> {
+  return invoke<Population>("get_world_population", { accountId, worldId });
+}
+
+export async function getHost(
+  accountId: string,
+  worldId: string,
+): Promise<Player | null> {
+  return invoke<Player | null>("get_host", { accountId, worldId });
+}
+
+export async function toPlayerUuid(filename: string): Promise<string> {
+  return invoke<string>("to_player_uuid", { filename });
+}
+
+export async function toFilename(uuid: string): Promise<string> {
+  return invoke<string>("to_filename", { uuid });
+}
+
+export async function isValidPlayerId(s: string): Promise<boolean> {
+  return invoke<boolean>("is_valid_player_id", { s });
+}
+
+export type BatchHostResult = {
+  worldId: string;
+  error: string | null;
+};
+
+export async function setHostInWorlds(
+  accountId: string,
+  worldIds: string[],
+  playerNameOrId: string,
+): Promise<BatchHostResult[]> {
+  return invoke<BatchHostResult[]>("set_host_in_worlds", {
+    accountId,
+    worldIds,
+    playerNameOrId,
+  });
+}
+
+export type WorldChangedPayload = {
+  accountId: string;
+  worldId: string;
+  paths: string[];
+};
+
+export async function watchWorld(
+  accountId: string,
+  worldId: string,
+): Promise<void> {
+  await invoke("watch_world", { accountId, worldId });
+}
+
+export async function stopWatching(): Promise<void> {
+  await invoke("stop_watching");
+}
+
+export async function getLevelSavMtime(
+  accountId: string,
+  worldId: string,
+): Promise<string | null> {
+  return invoke<string | null>("get_level_sav_mtime", { accountId, worldId });
 }
 
+export type CharMapping = {
+  filename: string;
+  filePlayerUid: string | null;
+  fileInstanceId: string | null;
+  cspmPlayerUid: string | null;
+  cspmInstanceId: string | null;
+  matched: boolean;
+};
+
+export async function getCharacterInstanceMap(
+  accountId: string,
+  worldId: string,
+): Promise<CharMapping[]> {
+  return invoke<CharMapping[]>("get_character_instance_map", {
+    accountId,
+    worldId,
+  });
+}
+
+export async function getPlayerCount(
+  accountId: string,
+  worldId: string,
+): Promise<number> {
+  return invoke<number>("get_player_count", { accountId, worldId });
+}
+
+export async function getPalDex(
+  accountId: string,
+  worldId: string,
+  playerId: string,
+): Promise<Record<string, number>> {
+  return invoke<Record<string, number>>("get_pal_dex", {
+    accountId,
+    worldId,
+    playerId,
+  });
+}
+
+export type WorldAge = {
+  inGameDays: number;
+  realFirstModified: string;
+};
+
+export async function getWorldAge(
+  accountId: string,
+  worldId: string,
+): Promise<WorldAge> {
+  return invoke<WorldAge>("get_world_age", { accountId, worldId });
+}
+
+export async function findDuplicateWorlds(
+  accountId: string,
+): Promise<string[][]> {
+  return invoke<string[][]>("find_duplicate_worlds", { accountId });
+}
+
+export async function getWorldRealName(
+  accountId: string,
+  worldId: string,
+): Promise<string | null> {
+  return invoke<string | null>("get_world_real_name", { accountId, worldId });
+}
+
+export async function getWorldThumbnail(
+  accountId: string,
+  worldId: string,
+): Promise<number[] | null> {
+  return invoke<number[] | null>("get_world_thumbnail", { accountId, worldId });
+}
+
+export async function estimateOperationTime(
+  accountId: string,
+  worldId: string,
+  op: "swap" | "set-host" | "export",
+): Promise<number> {
+  return invoke<number>("estimate_operation_time", { accountId, worldId, op });
+}
+
+export async function verifyOodle(): Promise<boolean> {
+  return invoke<boolean>("verify_oodle");
+}
+
+export type WorldDataKey = {
+  name: string;
+  typeName: string;
+  size: number;
+  entryCount: number | null;
+};
+
+export async function listWorldDataKeys(
+  accountId: string,
+  worldId: string,
+): Promise<WorldDataKey[]> {
+  return invoke<WorldDataKey[]>("list_world_data_keys", { accountId, worldId });
+}
+
+export type WorldVersion = {
+  engineVersion: string;
+  saveGameVersion: number | null;
+  saveType: number;
+};
+
+export async function getWorldVersion(
+  accountId: string,
+  worldId: string,
+): Promise<WorldVersion> {
+  return invoke<WorldVersion>("get_world_version", { accountId, worldId });
+}
+
+export type MatchStrategy = "instanceId" | "playerUId" | "auto";
+
 export async function setHostPlayer(
   accountId: string,
   worldId: string,
   playerId: string,
+  matchStrategy?: MatchStrategy,
+  fixBaseCamps?: boolean,
+): Promise<Player[]> {
+  return invoke<Player[]>("set_host_player", {
+    accountId,
+    worldId,
+    playerId,
+    matchStrategy,
+    fixBaseCamps,
+  });
+}
+
+export async function repairMissingHost(
+  accountId: string,
+  worldId: string,
+  chosenPlayerId: string,
+): Promise<Player[]> {
+  return invoke<Player[]>("repair_missing_host", {
+    accountId,
+    worldId,
+    chosenPlayerId,
+  });
+}
+
+export async function fixBaseCampOwnership(
+  accountId: string,
+  worldId: string,
+  fromId: string,
+  toId: string,
+): Promise<number> {
+  return invoke<number>("fix_base_camp_ownership", {
+    accountId,
+    worldId,
+    fromId,
+    toId,
+  });
+}
+
+export async function resyncPlayerId(
+  accountId: string,
+  worldId: string,
+  playerId: string,
 ): Promise<Player[]> {
-  return invoke<Player[]>("set_host_player", { accountId, worldId, playerId });
+  return invoke<Player[]>("resync_player_id", { accountId, worldId, playerId });
+}
+
+export async function repairPlayerIds(
+  accountId: string,
+  worldId: string,
+  playerId: string,
+): Promise<Player[]> {
+  return invoke<Player[]>("repair_player_ids", { accountId, worldId, playerId });
 }
 
 export async function swapPlayers(
@@ -51,12 +489,223 @@ export async function swapPlayers(
   worldId: string,
   firstId: string,
   secondId: string,
+  matchStrategy?: MatchStrategy,
 ): Promise<Player[]> {
   return invoke<Player[]>("swap_players", {
     accountId,
     worldId,
     firstId,
     secondId,
+    matchStrategy,
+  });
+}
+
+export type SwapRecord = {
+  timestamp: string;
+  firstId: string;
+  secondId: string;
+  firstName: string | null;
+  secondName: string | null;
+  ownershipFieldsSwapped: number;
+  matchStrategy: MatchStrategy;
+};
+
+export async function getSwapHistory(
+  accountId: string,
+  worldId: string,
+): Promise<SwapRecord[]> {
+  return invoke<SwapRecord[]>("get_swap_history", { accountId, worldId });
+}
+
+export async function clearSwapHistory(
+  accountId: string,
+  worldId: string,
+): Promise<void> {
+  return invoke<void>("clear_swap_history", { accountId, worldId });
+}
+
+export async function setSwapHistoryLimit(
+  accountId: string,
+  worldId: string,
+  limit: number | null,
+): Promise<SwapRecord[]> {
+  return invoke<SwapRecord[]>("set_swap_history_limit", {
+    accountId,
+    worldId,
+    limit,
+  });
+}
+
+// Resolves false if no swap is running in that world
+export async function cancelSwap(
+  accountId: string,
+  worldId: string,
+): Promise<boolean> {
+  return invoke<boolean>("cancel_swap", { accountId, worldId });
+}
+
+export async function mergeGuilds(
+  accountId: string,
+  worldId: string,
+  sourceGuildId: string,
+  targetGuildId: string,
+): Promise<Player[]> {
+  return invoke<Player[]>("merge_guilds", {
+    accountId,
+    worldId,
+    sourceGuildId,
+    targetGuildId,
+  });
+}
+
+export type CompactOptions = {
+  removeOrphanPals?: boolean;
+  removeEmptyGuilds?: boolean;
+};
+
+export type CompactReport = {
+  orphanPalsRemoved: number;
+  guildsRemoved: number;
+  bytesBefore: number;
+  bytesAfter: number;
+  bytesSaved: number;
+};
+
+export async function compactWorld(
+  accountId: string,
+  worldId: string,
+  options: CompactOptions,
+): Promise<CompactReport> {
+  return invoke<CompactReport>("compact_world", {
+    accountId,
+    worldId,
+    options,
+  });
+}
+
+export async function setGuildName(
+  accountId: string,
+  worldId: string,
+  guildId: string,
+  newName: string,
+): Promise<GuildSummary[]> {
+  return invoke<GuildSummary[]>("set_guild_name", {
+    accountId,
+    worldId,
+    guildId,
+    newName,
+  });
+}
+
+export async function setGuildBaseCampLevel(
+  accountId: string,
+  worldId: string,
+  guildId: string,
+  level: number,
+): Promise<GuildSummary[]> {
+  return invoke<GuildSummary[]>("set_guild_base_camp_level", {
+    accountId,
+    worldId,
+    guildId,
+    level,
+  });
+}
+
+export async function setPlayerLevel(
+  accountId: string,
+  worldId: string,
+  playerId: string,
+  level: number,
+): Promise<Player[]> {
+  return invoke<Player[]>("set_player_level", {
+    accountId,
+    worldId,
+    playerId,
+    level,
+  });
+}
+
+export async function deletePlayer(
+  accountId: string,
+  worldId: string,
+  playerId: string,
+  deletePals = false,
+  force = false,
+): Promise<Player[]> {
+  return invoke<Player[]>("delete_player", {
+    accountId,
+    worldId,
+    playerId,
+    deletePals,
+    force,
+  });
+}
+
+export async function copyPlayerBetweenWorlds(
+  accountId: string,
+  srcWorld: string,
+  dstWorld: string,
+  playerId: string,
+  copyPals = false,
+): Promise<void> {
+  return invoke<void>("copy_player_between_worlds", {
+    accountId,
+    srcWorld,
+    dstWorld,
+    playerId,
+    copyPals,
+  });
+}
+
+export type RosterEntry = {
+  uuid: string;
+  name: string;
+  level: number;
+  guildName: string;
+};
+
+export type WorldDiff = {
+  onlyInA: RosterEntry[];
+  onlyInB: RosterEntry[];
+  changed: {
+    uuid: string;
+    name: string;
+    levelA: number;
+    levelB: number;
+    guildA: string;
+    guildB: string;
+  }[];
+};
+
+export async function diffWorlds(
+  accountId: string,
+  worldA: string,
+  worldB: string,
+): Promise<WorldDiff> {
+  return invoke<WorldDiff>("diff_worlds", { accountId, worldA, worldB });
+}
+
+export type SwapPreview = {
+  success: boolean;
+  error: string | null;
+  issues: string[];
+  rosterDiff: WorldDiff;
+  matchedBy: MatchStrategy | null;
+};
+
+export async function rehearseSwap(
+  accountId: string,
+  worldId: string,
+  firstId: string,
+  secondId: string,
+  matchStrategy?: MatchStrategy,
+): Promise<SwapPreview> {
+  return invoke<SwapPreview>("rehearse_swap", {
+    accountId,
+    worldId,
+    firstId,
+    secondId,
+    matchStrategy,
   });
 }
 
@@ -64,9 +713,15 @@ export async function createBackup(
   accountId: string,
   worldId: string,
   players: Player[],
+  full = false,
 ): Promise<string> {
   const playerIds = players.map((player) => player.id);
-  return invoke<string>("create_backup", { accountId, worldId, playerIds });
+  return invoke<string>("create_backup", {
+    accountId,
+    worldId,
+    playerIds,
+    full,
+  });
 }
 
 export async function listBackups(
@@ -76,6 +731,113 @@ export async function listBackups(
   return invoke<string[]>("list_backups", { accountId, worldId });
 }
 
+export type BackupEntry = {
+  name: string;
+  createdAt: string;
+  label: string | null;
+};
+
+export async function listBackupsDetailed(
+  accountId: string,
+  worldId: string,
+): Promise<BackupEntry[]> {
+  return invoke<BackupEntry[]>("list_backups_detailed", { accountId, worldId });
+}
+
+export type AccountBackup = BackupEntry & {
+  worldId: string;
+  sizeBytes: number;
+};
+
+export type BackupPage = {
+  entries: BackupEntry[];
+  total: number;
+  totalBytes: number;
+};
+
+export async function listBackupsPaged(
+  accountId: string,
+  worldId: string,
+  offset: number,
+  limit: number,
+): Promise<BackupPage> {
+  return invoke<BackupPage>("list_backups_paged", {
+    accountId,
+    worldId,
+    offset,
+    limit,
+  });
+}
+
+export async function listAllBackups(
+  accountId: string,
+): Promise<AccountBackup[]> {
+  return invoke<AccountBackup[]>("list_all_backups", { accountId });
+}
+
+export async function pruneBackups(
+  accountId: string,
+  keepPerWorld: number,
+): Promise<number> {
+  return invoke<number>("prune_backups", { accountId, keepPerWorld });
+}
+
+export async function copyBackupTo(
+  accountId: string,
+  worldId: string,
+  backupName: string,
+  destPath: string,
+): Promise<string> {
+  return invoke<string>("copy_backup_to", {
+    accountId,
+    worldId,
+    backupName,
+    destPath,
+  });
+}
+
+export async function importBackupFrom(
+  accountId: string,
+  worldId: string,
+  zipPath: string,
+): Promise<string[]> {
+  return invoke<string[]>("import_backup_from", { accountId, worldId, zipPath });
+}
+
+export async function restoreFromExternalZip(
+  accountId: string,
+  worldId: string,
+  zipPath: string,
+  preserveDisplayName = true,
+): Promise<Player[]> {
+  return invoke<Player[]>("restore_from_external_zip", {
+    accountId,
+    worldId,
+    zipPath,
+    preserveDisplayName,
+  });
+}
+
+export type BackupVerification = {
+  backupName: string;
+  ok: boolean;
+  filesChecked: number;
+  snapshotOk: boolean;
+  unreadable: { file: string; error: string }[];
+};
+
+export async function verifyBackup(
+  accountId: string,
+  worldId: string,
+  backupName: string,
+): Promise<BackupVerification> {
+  return invoke<BackupVerification>("verify_backup", {
+    accountId,
+    worldId,
+    backupName,
+  });
+}
+
 export async function deleteBackup(
   accountId: string,
   worldId: string,
@@ -84,6 +846,20 @@ export async function deleteBackup(
   return invoke<string[]>("delete_backup", { accountId, worldId, backupName });
 }
 
+export async function renameBackup(
+  accountId: string,
+  worldId: string,
+  backupName: string,
+  newLabel: string,
+): Promise<BackupEntry[]> {
+  return invoke<BackupEntry[]>("rename_backup", {
+    accountId,
+    worldId,
+    backupName,
+    newLabel,
+  });
+}
+
 export async function deleteAllBackups(
   accountId: string,
   worldId: string,
@@ -95,8 +871,36 @@ export async function restoreBackup(
   accountId: string,
   worldId: string,
   backupName: string,
+  preserveDisplayName = true,
 ): Promise<Player[]> {
-  return invoke<Player[]>("restore_backup", { accountId, worldId, backupName });
+  return invoke<Player[]>("restore_backup", {
+    accountId,
+    worldId,
+    backupName,
+    preserveDisplayName,
+  });
+}
+
+export type RestorePreview = {
+  files: { path: string; overwrites: boolean }[];
+  hasSnapshot: boolean;
+  currentDisplayName: string | null;
+  restoredDisplayName: string | null;
+  displayNameChanges: boolean;
+};
+
+export async function previewRestore(
+  accountId: string,
+  worldId: string,
+  backupName: string,
+  preserveDisplayName = true,
+): Promise<RestorePreview> {
+  return invoke<RestorePreview>("preview_restore", {
+    accountId,
+    worldId,
+    backupName,
+    preserveDisplayName,
+  });
 }
 
 export async function rescanStorage(): Promise<void> {
@@ -118,25 +922,121 @@ export async function resetWorldName(
   return invoke<WorldInfo[]>("reset_world_name", { accountId, worldId });
 }
 
+export async function resetWorldConfig(
+  accountId: string,
+  worldId: string,
+  keepDisplayName = true,
+): Promise<Player[]> {
+  return invoke<Player[]>("reset_world_config", {
+    accountId,
+    worldId,
+    keepDisplayName,
+  });
+}
+
+export async function setWorldFavorite(
+  accountId: string,
+  worldId: string,
+  favorite: boolean,
+): Promise<WorldInfo[]> {
+  return invoke<WorldInfo[]>("set_world_favorite", {
+    accountId,
+    worldId,
+    favorite,
+  });
+}
+
+export async function normalizeSave(
+  accountId: string,
+  worldId: string,
+  playerId?: string,
+): Promise<void> {
+  await invoke("normalize_save", {
+    accountId,
+    worldId,
+    playerId,
+  });
+}
+
+export async function exportLogBundle(
+  accountId: string,
+  worldId: string,
+  outPath: string,
+): Promise<string> {
+  return invoke<string>("export_log_bundle", { accountId, worldId, outPath });
+}
+
+export async function convertSavFormat(
+  inPath: string,
+  outPath: string,
+  target: "plz" | "cnk" | "plm" | "gvas",
+): Promise<void> {
+  await invoke("convert_sav_format", { inPath, outPath, target });
+}
+
+export async function dumpSavToJson(
+  inPath: string,
+  outPath: string,
+): Promise<number> {
+  return invoke<number>("dump_sav_to_json", { inPath, outPath });
+}
+
 // ── World Transfer ──────────────────────────────────
 
+export type WorldCompleteness = "full" | "missingLevel" | "levelOnly" | "savesOnly";
+
+export type ValidationMode = "strict" | "lenient";
+
 export type ValidatedFolder = {
   name: string;
   path: string;
+  completeness: WorldCompleteness;
 };
 
 export async function exportWorld(
   accountId: string,
   worldId: string,
   destPath: string,
+  includeGameBackups = false,
+  password?: string,
 ): Promise<string> {
-  return invoke<string>("export_world", { accountId, worldId, destPath });
+  return invoke<string>("export_world", {
+    accountId,
+    worldId,
+    destPath,
+    includeGameBackups,
+    password,
+  });
 }
 
 export async function validateWorldFolder(
   folderPath: string,
+  mode: ValidationMode = "strict",
 ): Promise<ValidatedFolder> {
-  return invoke<ValidatedFolder>("validate_world_folder", { folderPath });
+  return invoke<ValidatedFolder>("validate_world_folder", { folderPath, mode });
+}
+
+export type PlayerSavInfo = {
+  playerUid: string;
+  instanceId: string | null;
+  nickName: string | null;
+  saveType: number;
+};
+
+export async function validatePlayerSav(path: string): Promise<PlayerSavInfo> {
+  return invoke<PlayerSavInfo>("validate_player_sav", { path });
+}
+
+export type HeaderInspection = {
+  header: unknown;
+  headerHex: string;
+  saveType: number;
+};
+
+export async function inspectSavHeader(
+  path: string,
+): Promise<HeaderInspection> {
+  return invoke<HeaderInspection>("inspect_sav_header", { path });
 }
 
 export async function checkWorldExists(
@@ -146,17 +1046,78 @@ export async function checkWorldExists(
   return invoke<boolean>("check_world_exists", { accountId, worldName });
 }
 
+export type CompatReport = {
+  compatible: boolean;
+  engineVersion: string;
+  engineSupported: boolean;
+  hostId: string | null;
+  hostSlotPresent: boolean;
+  missingPlayerFiles: string[];
+  warnings: string[];
+};
+
+export async function verifySharedWorldCompatibility(
+  accountId: string,
+  sourceDir: string,
+): Promise<CompatReport> {
+  return invoke<CompatReport>("verify_shared_world_compatibility", {
+    accountId,
+    sourceDir,
+  });
+}
+
 export async function importWorld(
   accountId: string,
   folderPath: string,
   mode: string,
   newName?: string,
+  createAccountIfMissing = false,
 ): Promise<WorldInfo[]> {
   return invoke<WorldInfo[]>("import_world", {
     accountId,
     folderPath,
     mode,
     newName: newName ?? null,
+    createAccountIfMissing,
+  });
+}
+
+export async function previewSharedWorld(
+  zipPath: string,
+  password?: string,
+): Promise<RosterEntry[]> {
+  return invoke<RosterEntry[]>("preview_shared_world", { zipPath, password });
+}
+
+export type ImportAction = {
+  kind: "create" | "overwrite" | "deleteExisting" | "skipBackup";
+  path: string;
+};
+
+export async function planImport(
+  accountId: string,
+  folderPath: string,
+  mode: string,
+  newName?: string,
+): Promise<ImportAction[]> {
+  return invoke<ImportAction[]>("import_world", {
+    accountId,
+    folderPath,
+    mode,
+    newName: newName ?? null,
+    dryRun: true,
+  });
+}
+
+export async function importSavesOnly(
+  accountId: string,
+  worldId: string,
+  sourceDir: string,
+): Promise<Player[]> {
+  return invoke<Player[]>("import_saves_only", {
+    accountId,
+    worldId,
+    sourceDir,
   });
 }
 
@@ -164,13 +1125,24 @@ export async function isPalworldRunning(): Promise<boolean> {
   return invoke<boolean>("is_palworld_running");
 }
 
+export type LogEntry = {
+  timestamp: string;
+  level: string;
+  message: string;
+};
+
+export async function getRecentLogs(limit?: number): Promise<LogEntry[]> {
+  return invoke<LogEntry[]>("get_recent_logs", { limit });
+}
+
 // ── P2P Transfer helpers ────────────────────────────
 
 export async function exportWorldToTemp(
   accountId: string,
   worldId: string,
+  password?: string,
 ): Promise<string> {
-  return invoke<string>("export_world_to_temp", { accountId, worldId });
+  return invoke<string>("export_world_to_temp", { accountId, worldId, password });
 }
 
 export async function getFileSize(path: string): Promise<number> {
@@ -185,6 +1157,10 @@ export async function readFileChunk(
   return invoke<ArrayBuffer>("read_file_chunk", { path, offset, length });
 }
 
+export async function beginFileTransfer(path: string): Promise<void> {
+  await invoke("begin_file_transfer", { path });
+}
+
 export async function appendFileChunkB64(
   path: string,
   dataB64: string,
@@ -196,10 +1172,29 @@ export async function getTempPath(filename: string): Promise<string> {
   return invoke<string>("get_temp_path", { filename });
 }
 
+export type TempItem = {
+  path: string;
+  size: number;
+  createdAt: string;
+};
+
+export async function listTempTransfers(): Promise<TempItem[]> {
+  return invoke<TempItem[]>("list_temp_transfers");
+}
+
+export async function cleanTempTransfers(
+  olderThanMinutes?: number,
+): Promise<TempItem[]> {
+  return invoke<TempItem[]>("clean_temp_transfers", { olderThanMinutes });
+}
+
 export async function deleteTempFile(path: string): Promise<void> {
   await invoke("delete_temp_file", { path });
 }
 
-export async function extractZipToTemp(zipPath: string): Promise<string> {
-  return invoke<string>("extract_zip_to_temp", { zipPath });
+export async function extractZipToTemp(
+  zipPath: string,
+  password?: string,
+): Promise<string> {
+  return invoke<string>("extract_zip_to_temp", { zipPath, password });
 }